@@ -1,7 +1,8 @@
 use eframe::egui;
-use sagacraft_rs::{AdventureGame, BasicWorldSystem, CombatSystem, InventorySystem, ItemType, MonsterStatus, QuestSystem};
-use std::path::PathBuf;
-use std::collections::HashMap;
+use sagacraft_rs::{direction_cmp, retain_valid, Adventure, AdventureGame, Engine, ItemType, LineKind, MonsterStatus, OutputLine, SkippedEntity, TemplateKind, DIRECTION_ORDER};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use serde::{Serialize, Deserialize};
 
@@ -9,6 +10,11 @@ fn default_one() -> i32 { 1 }
 fn default_six() -> i32 { 6 }
 fn default_true() -> bool { true }
 
+/// Next free id: one past the largest existing id, so ids never repeat after deletions.
+fn next_id(ids: impl Iterator<Item = i32>) -> i32 {
+    ids.max().unwrap_or(0) + 1
+}
+
 fn main() -> eframe::Result<()> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1400.0, 900.0]),
@@ -48,6 +54,10 @@ struct AdventureSettings {
     enable_puzzles: bool,
     #[serde(default)]
     enable_combat_xp: bool,
+    /// Engine settings the editor has no widgets for (e.g. `inventory`),
+    /// kept so they survive a load/save round trip.
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,7 +65,7 @@ struct RoomData {
     id: i32,
     name: String,
     description: String,
-    exits: HashMap<String, i32>,
+    exits: BTreeMap<String, i32>,
     #[serde(default)]
     is_dark: bool,
     #[serde(default)]
@@ -70,6 +80,12 @@ struct RoomData {
     trap_damage: i32,
     #[serde(default)]
     environmental_effects: Vec<String>,
+    #[serde(default)]
+    is_checkpoint: bool,
+    #[serde(default)]
+    auto_save_on_enter: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    hidden_exits: BTreeMap<String, i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +115,20 @@ struct ItemData {
     is_takeable: bool,
     #[serde(default)]
     is_wearable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_hardiness: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_level: Option<i32>,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    is_light_source: bool,
+    #[serde(default)]
+    disarms_traps: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    contents: Vec<i32>,
+    #[serde(default)]
+    is_open: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,16 +171,210 @@ struct SagaCraftIDE {
     selected_quest: Option<usize>,
     // Play tab state
     game: Option<AdventureGame>,
-    game_output: Vec<String>,
+    game_output: Vec<OutputLine>,
     game_input: String,
     // Exit confirmation
     show_exit_confirm: bool,
     // Add-exit dialog state
     new_exit_direction: String,
     new_exit_target: i32,
+    // Always-on lint
+    issues: Vec<ValidationIssue>,
+    lint_current: bool,
+    last_lint: Option<Instant>,
+    // Undo/redo: `committed` is the adventure as of the last history entry
+    history: History<AdventureData>,
+    committed: AdventureData,
+    edited: bool,
+    // Command palette (Ctrl+P)
+    show_palette: bool,
+    palette_filter: String,
+}
+
+/// Actions offered by the command palette.
+type PaletteAction = (&'static str, fn(&mut SagaCraftIDE));
+
+const PALETTE_ACTIONS: &[PaletteAction] = &[
+    ("New Adventure", SagaCraftIDE::new_adventure),
+    ("Open Adventure", SagaCraftIDE::open_adventure),
+    ("Save Adventure", SagaCraftIDE::save_adventure),
+    ("Save Adventure As", SagaCraftIDE::save_adventure_as),
+    ("Export JSON", SagaCraftIDE::export_to_json),
+    ("Validate Adventure", SagaCraftIDE::validate_adventure),
+    ("Undo", SagaCraftIDE::undo),
+    ("Redo", SagaCraftIDE::redo),
+    ("Add Room", SagaCraftIDE::add_room),
+    ("Delete Room", SagaCraftIDE::delete_room),
+    ("Add Item", SagaCraftIDE::add_item),
+    ("Delete Item", SagaCraftIDE::delete_item),
+    ("Add Monster", SagaCraftIDE::add_monster),
+    ("Delete Monster", SagaCraftIDE::delete_monster),
+    ("Add Quest", SagaCraftIDE::add_quest),
+    ("Delete Quest", SagaCraftIDE::delete_quest),
+    ("Start Game", SagaCraftIDE::start_game),
+    ("Playtest From Here", SagaCraftIDE::playtest_from_selected),
+    ("Stop Game", SagaCraftIDE::stop_game),
+];
+
+/// Palette actions whose name contains every word of `filter` (case-insensitive).
+fn palette_matches(filter: &str) -> Vec<&'static PaletteAction> {
+    let words: Vec<String> = filter.split_whitespace().map(str::to_lowercase).collect();
+    PALETTE_ACTIONS.iter()
+        .filter(|(name, _)| {
+            let name = name.to_lowercase();
+            words.iter().all(|w| name.contains(w.as_str()))
+        })
+        .collect()
+}
+
+/// Ids of every room reachable from `start_room` by following exits.
+fn reachable_rooms(adventure: &AdventureData) -> HashSet<i32> {
+    let mut seen = HashSet::from([adventure.start_room]);
+    let mut frontier = vec![adventure.start_room];
+    while let Some(id) = frontier.pop() {
+        let Some(room) = adventure.rooms.iter().find(|r| r.id == id) else { continue };
+        for &next in room.exits.values() {
+            if seen.insert(next) {
+                frontier.push(next);
+            }
+        }
+    }
+    seen
+}
+
+/// Tabs in display order; Ctrl+1 … Ctrl+7 switch to them.
+const TABS: [Tab; 7] = [Tab::Play, Tab::Info, Tab::Rooms, Tab::Items, Tab::Monsters, Tab::Quests, Tab::Preview];
+
+/// Maximum number of undo steps kept.
+const HISTORY_LIMIT: usize = 100;
+
+/// Temp file the Play tab writes the adventure to before loading it.
+const PLAY_FILE: &str = "sagacraft_play.json";
+
+/// Bounded undo/redo stacks of snapshots.
+#[derive(Debug)]
+struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self { undo: Vec::new(), redo: Vec::new(), limit: HISTORY_LIMIT }
+    }
+}
+
+impl<T> History<T> {
+    /// Remember `before` as an undo point; a new edit invalidates the redo stack.
+    fn record(&mut self, before: T) {
+        self.undo.push(before);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Step back: returns the previous state and keeps `current` for redo.
+    fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Step forward again after an undo.
+    fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Minimum time between lint passes while the adventure is being edited.
+const LINT_INTERVAL: Duration = Duration::from_millis(300);
+
+/// A problem found by the always-on lint, pointing at the tab and list entry it concerns.
+#[derive(Debug, Clone, PartialEq)]
+struct ValidationIssue {
+    tab: Tab,
+    index: Option<usize>,
+    message: String,
+}
+
+/// Cheap structural checks rerun shortly after each edit to the adventure.
+fn lint_adventure(adventure: &AdventureData) -> Vec<ValidationIssue> {
+
+    let mut issues = Vec::new();
+    let mut issue = |tab, index, message: String| issues.push(ValidationIssue { tab, index, message });
+    let room_ids: HashSet<i32> = adventure.rooms.iter().map(|r| r.id).collect();
+    let item_ids: HashSet<i32> = adventure.items.iter().map(|i| i.id).collect();
+
+    if !room_ids.contains(&adventure.start_room) {
+        issue(Tab::Info, None, format!("Start room {} does not exist", adventure.start_room));
+    }
+
+    let mut seen = HashSet::new();
+    for (i, room) in adventure.rooms.iter().enumerate() {
+        if !seen.insert(room.id) {
+            issue(Tab::Rooms, Some(i), format!("Duplicate room id {}", room.id));
+        }
+        let mut exits: Vec<_> = room.exits.iter().filter(|(_, to)| !room_ids.contains(to)).collect();
+        exits.sort_by(|a, b| direction_cmp(a.0, b.0));
+        for (dir, to) in exits {
+            issue(Tab::Rooms, Some(i), format!("Exit {} leads to missing room {}", dir, to));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (i, item) in adventure.items.iter().enumerate() {
+        if !seen.insert(item.id) {
+            issue(Tab::Items, Some(i), format!("Duplicate item id {}", item.id));
+        }
+        if item.location > 0 && !room_ids.contains(&item.location) {
+            issue(Tab::Items, Some(i), format!("Located in missing room {}", item.location));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (i, monster) in adventure.monsters.iter().enumerate() {
+        if !seen.insert(monster.id) {
+            issue(Tab::Monsters, Some(i), format!("Duplicate monster id {}", monster.id));
+        }
+        if !room_ids.contains(&monster.room_id) {
+            issue(Tab::Monsters, Some(i), format!("Placed in missing room {}", monster.room_id));
+        }
+        if let Some(weapon) = monster.weapon_id.filter(|w| !item_ids.contains(w)) {
+            issue(Tab::Monsters, Some(i), format!("Wields missing item {}", weapon));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (i, quest) in adventure.quests.iter().enumerate() {
+        if !seen.insert(quest.id) {
+            issue(Tab::Quests, Some(i), format!("Duplicate quest id {}", quest.id));
+        }
+    }
+
+    issues
+}
+
+/// A list entry, flagged with a warning marker and tooltip when it has lint issues.
+fn list_entry(ui: &mut egui::Ui, label: String, selected: bool, issues: &[String]) -> bool {
+    if issues.is_empty() {
+        return ui.add(egui::Button::new(label).selected(selected)).clicked();
+    }
+    let text = egui::RichText::new(format!("⚠ {}", label)).color(egui::Color32::RED);
+    ui.add(egui::Button::new(text).selected(selected))
+        .on_hover_text(issues.join("\n"))
+        .clicked()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 enum Tab {
     #[default]
     Play,
@@ -172,12 +396,15 @@ impl Default for AdventureData {
                 id: 1,
                 name: "Starting Room".to_string(),
                 description: "A simple room to begin your adventure.".to_string(),
-                exits: HashMap::new(),
+                exits: BTreeMap::new(),
                 is_dark: false,
                 light_level: None,
                 is_safe_zone: false,
                 ambient_sound: None,
                 has_trap: false,
+                is_checkpoint: false,
+                auto_save_on_enter: false,
+                hidden_exits: BTreeMap::new(),
                 trap_damage: 0,
                 environmental_effects: vec![],
             }],
@@ -197,6 +424,13 @@ impl Default for AdventureData {
                 armor_value: 0,
                 is_takeable: true,
                 is_wearable: false,
+                min_hardiness: None,
+                min_level: None,
+                hidden: false,
+                is_light_source: false,
+                disarms_traps: false,
+                contents: Vec::new(),
+                is_open: false,
             }],
             monsters: vec![MonsterData {
                 id: 1,
@@ -226,6 +460,16 @@ impl Default for AdventureData {
 
 impl eframe::App for SagaCraftIDE {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+        if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+            self.redo();
+        }
+        self.handle_shortcuts(ctx);
+
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             self.show_menu_bar(ctx, ui);
         });
@@ -267,6 +511,16 @@ impl eframe::App for SagaCraftIDE {
                     });
                 });
         }
+
+        if self.show_palette {
+            self.show_command_palette(ctx);
+        }
+
+        if std::mem::take(&mut self.edited) {
+            self.commit_edit();
+            self.lint_current = false;
+        }
+        self.refresh_issues(ctx);
     }
 }
 
@@ -274,10 +528,19 @@ impl SagaCraftIDE {
     fn show_menu_bar(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         egui::MenuBar::new().ui(ui, |ui| {
             ui.menu_button("File", |ui| {
-                if ui.button("New Adventure").clicked() {
-                    self.new_adventure();
-                    ui.close();
-                }
+                ui.menu_button("New Adventure", |ui| {
+                    if ui.button("Blank").clicked() {
+                        self.new_adventure();
+                        ui.close();
+                    }
+                    ui.separator();
+                    for kind in TemplateKind::ALL {
+                        if ui.button(Adventure::template(kind).title).clicked() {
+                            self.new_from_template(kind);
+                            ui.close();
+                        }
+                    }
+                });
                 if ui.button("Open Adventure...").clicked() {
                     self.open_adventure();
                     ui.close();
@@ -300,6 +563,17 @@ impl SagaCraftIDE {
                 }
             });
 
+            ui.menu_button("Edit", |ui| {
+                if ui.add_enabled(!self.history.undo.is_empty(), egui::Button::new("Undo (Ctrl+Z)")).clicked() {
+                    self.undo();
+                    ui.close();
+                }
+                if ui.add_enabled(!self.history.redo.is_empty(), egui::Button::new("Redo (Ctrl+Y)")).clicked() {
+                    self.redo();
+                    ui.close();
+                }
+            });
+
             ui.menu_button("Tools", |ui| {
                 if ui.button("Validate Adventure").clicked() {
                     self.validate_adventure();
@@ -309,6 +583,11 @@ impl SagaCraftIDE {
                     self.export_to_json();
                     ui.close();
                 }
+                ui.separator();
+                if ui.add_enabled(self.selected_room.is_some(), egui::Button::new("Playtest From Here")).clicked() {
+                    self.playtest_from_selected();
+                    ui.close();
+                }
             });
 
             ui.menu_button("View", |ui| {
@@ -330,30 +609,157 @@ impl SagaCraftIDE {
         });
     }
 
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let shortcut = |key| egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, key);
+        let pressed = |key| ctx.input_mut(|i| i.consume_shortcut(&shortcut(key)));
+
+        if pressed(egui::Key::S) {
+            self.save_adventure();
+        }
+        if pressed(egui::Key::N) {
+            self.new_adventure();
+        }
+        if pressed(egui::Key::O) {
+            self.open_adventure();
+        }
+        if pressed(egui::Key::P) {
+            self.show_palette = !self.show_palette;
+            self.palette_filter.clear();
+        }
+        let number_keys = [
+            egui::Key::Num1, egui::Key::Num2, egui::Key::Num3, egui::Key::Num4,
+            egui::Key::Num5, egui::Key::Num6, egui::Key::Num7,
+        ];
+        for (key, tab) in number_keys.into_iter().zip(TABS) {
+            if pressed(key) {
+                self.active_tab = tab;
+            }
+        }
+    }
+
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        let mut chosen: Option<fn(&mut SagaCraftIDE)> = None;
+        let mut open = true;
+        egui::Window::new("Command Palette")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let filter = ui.text_edit_singleline(&mut self.palette_filter);
+                filter.request_focus();
+                let matches = palette_matches(&self.palette_filter);
+                if filter.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    chosen = matches.first().map(|(_, action)| *action);
+                }
+                ui.separator();
+                for (name, action) in matches {
+                    if ui.button(*name).clicked() {
+                        chosen = Some(*action);
+                    }
+                }
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            open = false;
+        }
+        if let Some(action) = chosen {
+            open = false;
+            action(self);
+        }
+        self.show_palette = open;
+    }
+
+    /// Record the state before this frame's edits as an undo point.
+    fn commit_edit(&mut self) {
+        let before = std::mem::replace(&mut self.committed, self.adventure.clone());
+        self.history.record(before);
+    }
+
+    fn undo(&mut self) {
+        if let Some(previous) = self.history.undo(self.adventure.clone()) {
+            self.restore(previous);
+            self.status = "Undone".to_string();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.history.redo(self.adventure.clone()) {
+            self.restore(next);
+            self.status = "Redone".to_string();
+        }
+    }
+
+    /// Swap in a snapshot from the history and keep the selections in range.
+    fn restore(&mut self, adventure: AdventureData) {
+        self.adventure = adventure;
+        self.committed = self.adventure.clone();
+        self.modified = true;
+        self.lint_current = false;
+        self.selected_room = self.selected_room.filter(|&i| i < self.adventure.rooms.len());
+        self.selected_item = self.selected_item.filter(|&i| i < self.adventure.items.len());
+        self.selected_monster = self.selected_monster.filter(|&i| i < self.adventure.monsters.len());
+        self.selected_quest = self.selected_quest.filter(|&i| i < self.adventure.quests.len());
+    }
+
+    /// Start a fresh history for a newly created or opened adventure.
+    fn reset_history(&mut self) {
+        self.history.clear();
+        self.committed = self.adventure.clone();
+        self.edited = false;
+        self.lint_current = false;
+    }
+
+    /// Re-run the lint after an edit, at most once per [`LINT_INTERVAL`], and
+    /// repaint if that changed what is flagged.
+    fn refresh_issues(&mut self, ctx: &egui::Context) {
+        if self.lint_current {
+            return;
+        }
+        if let Some(wait) = self.last_lint.and_then(|t| LINT_INTERVAL.checked_sub(t.elapsed()))
+            && !wait.is_zero()
+        {
+            ctx.request_repaint_after(wait);
+            return;
+        }
+        self.lint_current = true;
+        self.last_lint = Some(Instant::now());
+        let issues = lint_adventure(&self.adventure);
+        if issues != self.issues {
+            self.issues = issues;
+            ctx.request_repaint();
+        }
+    }
+
+    /// Lint messages attached to one list entry of `tab`.
+    fn issues_for(&self, tab: Tab, index: usize) -> Vec<String> {
+        self.issues.iter()
+            .filter(|i| i.tab == tab && i.index == Some(index))
+            .map(|i| i.message.clone())
+            .collect()
+    }
+
+    fn tab_button(&mut self, ui: &mut egui::Ui, tab: Tab, label: &str) {
+        let count = self.issues.iter().filter(|i| i.tab == tab).count();
+        let text = if count == 0 {
+            egui::RichText::new(label)
+        } else {
+            egui::RichText::new(format!("{} ({})", label, count)).color(egui::Color32::RED)
+        };
+        if ui.add(egui::Button::new(text).selected(self.active_tab == tab)).clicked() {
+            self.active_tab = tab;
+        }
+    }
+
     fn show_main_ui(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             // Tab buttons
-            if ui.add(egui::Button::new("🎮 Play").selected(self.active_tab == Tab::Play)).clicked() {
-                self.active_tab = Tab::Play;
-            }
-            if ui.add(egui::Button::new("ℹ Info").selected(self.active_tab == Tab::Info)).clicked() {
-                self.active_tab = Tab::Info;
-            }
-            if ui.add(egui::Button::new("🏠 Rooms").selected(self.active_tab == Tab::Rooms)).clicked() {
-                self.active_tab = Tab::Rooms;
-            }
-            if ui.add(egui::Button::new("🎒 Items").selected(self.active_tab == Tab::Items)).clicked() {
-                self.active_tab = Tab::Items;
-            }
-            if ui.add(egui::Button::new("👹 Monsters").selected(self.active_tab == Tab::Monsters)).clicked() {
-                self.active_tab = Tab::Monsters;
-            }
-            if ui.add(egui::Button::new("📜 Quests").selected(self.active_tab == Tab::Quests)).clicked() {
-                self.active_tab = Tab::Quests;
-            }
-            if ui.add(egui::Button::new(" Preview").selected(self.active_tab == Tab::Preview)).clicked() {
-                self.active_tab = Tab::Preview;
-            }
+            self.tab_button(ui, Tab::Play, "🎮 Play");
+            self.tab_button(ui, Tab::Info, "ℹ Info");
+            self.tab_button(ui, Tab::Rooms, "🏠 Rooms");
+            self.tab_button(ui, Tab::Items, "🎒 Items");
+            self.tab_button(ui, Tab::Monsters, "👹 Monsters");
+            self.tab_button(ui, Tab::Quests, "📜 Quests");
+            self.tab_button(ui, Tab::Preview, " Preview");
         });
 
         ui.separator();
@@ -384,12 +790,29 @@ impl SagaCraftIDE {
             }
         });
 
+        if let Some(game) = &self.game {
+            egui::CollapsingHeader::new("Character").show(ui, |ui| {
+                for line in game.character_sheet() {
+                    ui.monospace(line);
+                }
+            });
+        }
+
         ui.separator();
 
         // Game output
         egui::ScrollArea::vertical().show(ui, |ui| {
             for line in &self.game_output {
-                ui.label(line);
+                let text = egui::RichText::new(&line.text);
+                let text = match line.kind {
+                    LineKind::RoomTitle => text.strong().color(egui::Color32::LIGHT_BLUE),
+                    LineKind::Exits => text.color(egui::Color32::LIGHT_GREEN),
+                    LineKind::Item => text.color(egui::Color32::GOLD),
+                    LineKind::Combat => text.color(egui::Color32::LIGHT_RED),
+                    LineKind::Error => text.color(egui::Color32::RED),
+                    LineKind::Description | LineKind::System => text,
+                };
+                ui.label(text);
             }
         });
 
@@ -424,7 +847,7 @@ impl SagaCraftIDE {
                 changed |= ui.add(egui::DragValue::new(&mut self.adventure.start_room)).changed();
                 ui.end_row();
             });
-        if changed { self.modified = true; }
+        if changed { self.modified = true; self.edited = true; }
 
         ui.separator();
         ui.label(format!("Rooms: {}", self.adventure.rooms.len()));
@@ -453,7 +876,8 @@ impl SagaCraftIDE {
             egui::ScrollArea::vertical().show(&mut columns[0], |ui| {
                 for (i, room) in self.adventure.rooms.iter().enumerate() {
                     let selected = self.selected_room == Some(i);
-                    if ui.add(egui::Button::new(format!("{}: {}", room.id, room.name)).selected(selected)).clicked() {
+                    let issues = self.issues_for(Tab::Rooms, i);
+                    if list_entry(ui, format!("{}: {}", room.id, room.name), selected, &issues) {
                         self.selected_room = Some(i);
                     }
                 }
@@ -483,11 +907,22 @@ impl SagaCraftIDE {
                             ui.label("Dark:");
                             changed |= ui.checkbox(&mut room.is_dark, "").changed();
                             ui.end_row();
+
+                            ui.label("Trapped:");
+                            changed |= ui.checkbox(&mut room.has_trap, "").changed();
+                            ui.end_row();
+
+                            if room.has_trap {
+                                ui.label("Trap Damage:");
+                                changed |= ui.add(egui::DragValue::new(&mut room.trap_damage).range(0..=100)).changed();
+                                ui.end_row();
+                            }
                         });
 
                     columns[1].separator();
                     columns[1].label("Exits:");
-                    let exit_dirs: Vec<String> = room.exits.keys().cloned().collect();
+                    let mut exit_dirs: Vec<String> = room.exits.keys().cloned().collect();
+                    exit_dirs.sort_by(|a, b| direction_cmp(a, b));
                     let mut remove_dir: Option<String> = None;
                     for direction in &exit_dirs {
                         if let Some(room_id) = room.exits.get_mut(direction) {
@@ -509,7 +944,7 @@ impl SagaCraftIDE {
                         egui::ComboBox::from_id_salt("add_exit_dir")
                             .selected_text(if self.new_exit_direction.is_empty() { "direction" } else { &self.new_exit_direction })
                             .show_ui(ui, |ui| {
-                                for d in &["north", "south", "east", "west", "up", "down"] {
+                                for d in DIRECTION_ORDER {
                                     ui.selectable_value(&mut self.new_exit_direction, d.to_string(), *d);
                                 }
                             });
@@ -519,7 +954,7 @@ impl SagaCraftIDE {
                             changed = true;
                         }
                     });
-                    if changed { self.modified = true; }
+                    if changed { self.modified = true; self.edited = true; }
                 }
             } else {
                 columns[1].label("Select a room to edit");
@@ -547,7 +982,8 @@ impl SagaCraftIDE {
             egui::ScrollArea::vertical().show(&mut columns[0], |ui| {
                 for (i, item) in self.adventure.items.iter().enumerate() {
                     let selected = self.selected_item == Some(i);
-                    if ui.add(egui::Button::new(format!("{}: {}", item.id, item.name)).selected(selected)).clicked() {
+                    let issues = self.issues_for(Tab::Items, i);
+                    if list_entry(ui, format!("{}: {}", item.id, item.name), selected, &issues) {
                         self.selected_item = Some(i);
                     }
                 }
@@ -604,6 +1040,24 @@ impl SagaCraftIDE {
                             changed |= ui.checkbox(&mut item.is_takeable, "").changed();
                             ui.end_row();
 
+                            ui.label("Hidden (found by search):");
+                            changed |= ui.checkbox(&mut item.hidden, "").changed();
+                            ui.end_row();
+
+                            ui.label("Light Source:");
+                            changed |= ui.checkbox(&mut item.is_light_source, "").changed();
+                            ui.end_row();
+
+                            ui.label("Disarms Traps:");
+                            changed |= ui.checkbox(&mut item.disarms_traps, "").changed();
+                            ui.end_row();
+
+                            if item.item_type == ItemType::Container {
+                                ui.label("Starts Open:");
+                                changed |= ui.checkbox(&mut item.is_open, "").changed();
+                                ui.end_row();
+                            }
+
                             ui.label("Is Weapon:");
                             changed |= ui.checkbox(&mut item.is_weapon, "").changed();
                             ui.end_row();
@@ -636,7 +1090,7 @@ impl SagaCraftIDE {
                                 ui.end_row();
                             }
                         });
-                    if changed { self.modified = true; }
+                    if changed { self.modified = true; self.edited = true; }
                 }
             } else {
                 columns[1].label("Select an item to edit");
@@ -664,7 +1118,8 @@ impl SagaCraftIDE {
             egui::ScrollArea::vertical().show(&mut columns[0], |ui| {
                 for (i, monster) in self.adventure.monsters.iter().enumerate() {
                     let selected = self.selected_monster == Some(i);
-                    if ui.add(egui::Button::new(format!("{}: {}", monster.id, monster.name)).selected(selected)).clicked() {
+                    let issues = self.issues_for(Tab::Monsters, i);
+                    if list_entry(ui, format!("{}: {}", monster.id, monster.name), selected, &issues) {
                         self.selected_monster = Some(i);
                     }
                 }
@@ -717,7 +1172,7 @@ impl SagaCraftIDE {
                                 });
                             ui.end_row();
                         });
-                    if changed { self.modified = true; }
+                    if changed { self.modified = true; self.edited = true; }
                 }
             } else {
                 columns[1].label("Select a monster to edit");
@@ -745,7 +1200,8 @@ impl SagaCraftIDE {
             egui::ScrollArea::vertical().show(&mut columns[0], |ui| {
                 for (i, quest) in self.adventure.quests.iter().enumerate() {
                     let selected = self.selected_quest == Some(i);
-                    if ui.add(egui::Button::new(format!("{}: {}", quest.id, quest.title)).selected(selected)).clicked() {
+                    let issues = self.issues_for(Tab::Quests, i);
+                    if list_entry(ui, format!("{}: {}", quest.id, quest.title), selected, &issues) {
                         self.selected_quest = Some(i);
                     }
                 }
@@ -804,7 +1260,7 @@ impl SagaCraftIDE {
                             changed |= ui.add(egui::DragValue::new(&mut quest.rewards_xp)).changed();
                             ui.end_row();
                         });
-                    if changed { self.modified = true; }
+                    if changed { self.modified = true; self.edited = true; }
                 }
             } else {
                 columns[1].label("Select a quest to edit");
@@ -866,11 +1322,27 @@ impl SagaCraftIDE {
     // File operations
     fn new_adventure(&mut self) {
         self.adventure = AdventureData::default();
+        self.reset_history();
         self.current_file = None;
         self.modified = false;
         self.status = "New adventure created".to_string();
     }
 
+    fn new_from_template(&mut self, kind: TemplateKind) {
+        match template_data(kind) {
+            Ok(adventure) => {
+                self.adventure = adventure;
+                self.reset_history();
+                self.current_file = None;
+                self.modified = false;
+                self.status = format!("New adventure from the {} template", kind.name());
+            }
+            Err(e) => {
+                self.status = format!("Error creating adventure: {}", e);
+            }
+        }
+    }
+
     fn open_adventure(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("JSON files", &["json"][..])
@@ -878,11 +1350,7 @@ impl SagaCraftIDE {
             .pick_file()
         {
             match self.load_from_file(&path) {
-                Ok(_) => {
-                    self.current_file = Some(path.clone());
-                    self.modified = false;
-                    self.status = format!("Opened adventure: {}", path.display());
-                }
+                Ok(skipped) => self.finish_open(path, &skipped),
                 Err(e) => {
                     self.status = format!("Error opening file: {}", e);
                 }
@@ -890,6 +1358,25 @@ impl SagaCraftIDE {
         }
     }
 
+    /// Point the editor at the freshly opened `path`. If anything was left
+    /// out, the original file is kept out of reach of Save: the document
+    /// counts as unsaved and has no file, so saving asks where to put it.
+    fn finish_open(&mut self, path: PathBuf, skipped: &[SkippedEntity]) {
+        if skipped.is_empty() {
+            self.status = format!("Opened adventure: {}", path.display());
+            self.current_file = Some(path);
+            self.modified = false;
+        } else {
+            let list: Vec<String> = skipped.iter().map(ToString::to_string).collect();
+            self.status = format!(
+                "Opened {} without {} malformed entries ({}); use Save As so the original keeps them",
+                path.display(), skipped.len(), list.join("; ")
+            );
+            self.current_file = None;
+            self.modified = true;
+        }
+    }
+
     fn save_adventure(&mut self) {
         if let Some(path) = self.current_file.clone() {
             match self.save_to_file(&path) {
@@ -931,10 +1418,16 @@ impl SagaCraftIDE {
         Ok(())
     }
 
-    fn load_from_file(&mut self, path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        self.adventure = serde_json::from_str(&content)?;
-        Ok(())
+    /// Open `path`, leaving out rooms, items and monsters that don't parse so
+    /// the rest can still be edited. Returns what was left out.
+    fn load_from_file(&mut self, path: &PathBuf) -> Result<Vec<SkippedEntity>, Box<dyn std::error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut skipped = retain_valid::<RoomData>(&mut value, "rooms", "room");
+        skipped.extend(retain_valid::<ItemData>(&mut value, "items", "item"));
+        skipped.extend(retain_valid::<MonsterData>(&mut value, "monsters", "monster"));
+        self.adventure = serde_json::from_value(value)?;
+        self.reset_history();
+        Ok(skipped)
     }
 
     fn validate_adventure(&mut self) {
@@ -977,21 +1470,25 @@ impl SagaCraftIDE {
 
     // CRUD operations
     fn add_room(&mut self) {
-        let id = self.adventure.rooms.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let id = next_id(self.adventure.rooms.iter().map(|r| r.id));
         self.adventure.rooms.push(RoomData {
             id,
             name: format!("Room {}", id),
             description: "A new room".to_string(),
-            exits: HashMap::new(),
+            exits: BTreeMap::new(),
             is_dark: false,
             light_level: None,
             is_safe_zone: false,
             ambient_sound: None,
             has_trap: false,
+            is_checkpoint: false,
+            auto_save_on_enter: false,
+            hidden_exits: BTreeMap::new(),
             trap_damage: 0,
             environmental_effects: vec![],
         });
         self.modified = true;
+        self.edited = true;
         self.status = format!("Room {} added", id);
     }
 
@@ -1000,12 +1497,13 @@ impl SagaCraftIDE {
             self.adventure.rooms.remove(idx);
             self.selected_room = None;
             self.modified = true;
+            self.edited = true;
             self.status = "Room deleted".to_string();
         }
     }
 
     fn add_item(&mut self) {
-        let id = self.adventure.items.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let id = next_id(self.adventure.items.iter().map(|r| r.id));
         // Default location to start_room so new items appear on the ground
         let location = self.adventure.start_room;
         self.adventure.items.push(ItemData {
@@ -1024,8 +1522,16 @@ impl SagaCraftIDE {
             armor_value: 0,
             is_takeable: true,
             is_wearable: false,
+            min_hardiness: None,
+            min_level: None,
+            hidden: false,
+            is_light_source: false,
+            disarms_traps: false,
+            contents: Vec::new(),
+            is_open: false,
         });
         self.modified = true;
+        self.edited = true;
         self.status = format!("Item {} added", id);
     }
 
@@ -1034,12 +1540,13 @@ impl SagaCraftIDE {
             self.adventure.items.remove(idx);
             self.selected_item = None;
             self.modified = true;
+            self.edited = true;
             self.status = "Item deleted".to_string();
         }
     }
 
     fn add_monster(&mut self) {
-        let id = self.adventure.monsters.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let id = next_id(self.adventure.monsters.iter().map(|r| r.id));
         let room_id = self.adventure.start_room;
         self.adventure.monsters.push(MonsterData {
             id,
@@ -1054,6 +1561,7 @@ impl SagaCraftIDE {
             room_id,
         });
         self.modified = true;
+        self.edited = true;
         self.status = format!("Monster {} added", id);
     }
 
@@ -1062,12 +1570,13 @@ impl SagaCraftIDE {
             self.adventure.monsters.remove(idx);
             self.selected_monster = None;
             self.modified = true;
+            self.edited = true;
             self.status = "Monster deleted".to_string();
         }
     }
 
     fn add_quest(&mut self) {
-        let id = self.adventure.quests.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let id = next_id(self.adventure.quests.iter().map(|r| r.id));
         self.adventure.quests.push(QuestData {
             id,
             title: format!("Quest {}", id),
@@ -1077,6 +1586,7 @@ impl SagaCraftIDE {
             rewards_xp: 100,
         });
         self.modified = true;
+        self.edited = true;
         self.status = format!("Quest {} added", id);
     }
 
@@ -1085,44 +1595,48 @@ impl SagaCraftIDE {
             self.adventure.quests.remove(idx);
             self.selected_quest = None;
             self.modified = true;
+            self.edited = true;
             self.status = "Quest deleted".to_string();
         }
     }
 
     // Game operations
     fn start_game(&mut self) {
-        self.game_output.clear();
+        self.launch_game(None);
+    }
 
-        // Serialise the current adventure to a temp file and load it into AdventureGame
-        let tmp_path = std::env::temp_dir().join("sagacraft_play.json");
-        match serde_json::to_string_pretty(&self.adventure) {
-            Ok(json) => {
-                if let Err(e) = fs::write(&tmp_path, &json) {
-                    self.game_output.push(format!("Error writing temp file: {e}"));
-                    return;
-                }
-            }
-            Err(e) => {
-                self.game_output.push(format!("Error serialising adventure: {e}"));
-                return;
-            }
+    /// Start a session in the room selected on the Rooms tab, if it can be
+    /// reached from the start room. The adventure's own `start_room` is left
+    /// untouched.
+    fn playtest_from_selected(&mut self) {
+        let Some(room) = self.selected_room.and_then(|i| self.adventure.rooms.get(i)) else {
+            self.status = "Select a room to playtest from".to_string();
+            return;
+        };
+        let room_id = room.id;
+        if !reachable_rooms(&self.adventure).contains(&room_id) {
+            self.status = format!("Room {room_id} cannot be reached from the start room; add an exit leading to it first");
+            return;
         }
+        self.launch_game(Some(room_id));
+        if self.game.is_some() {
+            self.active_tab = Tab::Play;
+            self.status = format!("Playtesting from room {room_id}");
+        }
+    }
 
-        let mut adventure_game = AdventureGame::new(tmp_path.to_string_lossy().to_string());
-        adventure_game.add_system(Box::new(BasicWorldSystem));
-        adventure_game.add_system(Box::new(InventorySystem));
-        adventure_game.add_system(Box::new(CombatSystem));
-        adventure_game.add_system(Box::new(QuestSystem::new()));
-
-        match adventure_game.load_adventure() {
-            Ok(intro) => {
-                self.game_output.push(intro);
-                self.game_output.push(adventure_game.look());
+    fn launch_game(&mut self, start_room: Option<i32>) {
+        self.game_output.clear();
+        let tmp_path = std::env::temp_dir().join(PLAY_FILE);
+        match load_for_play(&self.adventure, &tmp_path, start_room) {
+            Ok((adventure_game, intro)) => {
+                self.game_output.push(OutputLine::new(LineKind::System, intro));
+                self.game_output.extend(adventure_game.look_lines());
                 self.game = Some(adventure_game);
                 self.status = "Game started".to_string();
             }
             Err(e) => {
-                self.game_output.push(format!("Failed to load adventure: {e}"));
+                self.game_output.push(OutputLine::new(LineKind::Error, e));
                 self.status = "Failed to start game".to_string();
             }
         }
@@ -1145,11 +1659,11 @@ impl SagaCraftIDE {
         }
         let command = self.game_input.clone();
         self.game_input.clear();
-        self.game_output.push(format!("> {}", command));
+        self.game_output.push(OutputLine::new(LineKind::System, format!("> {}", command)));
 
         match command.trim().to_lowercase().as_str() {
             "quit" | "q" | "exit" => {
-                self.game_output.push("Game stopped.".to_string());
+                self.game_output.push(OutputLine::new(LineKind::System, "Game stopped."));
                 self.game = None;
                 self.status = "Game stopped".to_string();
                 return;
@@ -1158,10 +1672,10 @@ impl SagaCraftIDE {
         }
 
         if let Some(game) = &mut self.game {
-            let lines = game.process_command(&command);
-            self.game_output.extend(lines);
+            let result = game.step(&command);
+            self.game_output.extend(result.lines);
         } else {
-            self.game_output.push("No game running. Press \u{25B6} Start Game first.".to_string());
+            self.game_output.push(OutputLine::new(LineKind::Error, "No game running. Press \u{25B6} Start Game first."));
         }
     }
 
@@ -1185,3 +1699,176 @@ impl SagaCraftIDE {
             .unwrap_or_else(|e| format!("JSON serialisation error: {e}"))
     }
 }
+
+/// Write `adventure` to `path` and load it back into a game with every
+/// standard system, the way the player would run it. Starts in `start_room`
+/// when given, otherwise in the adventure's own start room. Returns the game
+/// and its intro text.
+fn load_for_play(adventure: &AdventureData, path: &Path, start_room: Option<i32>) -> Result<(AdventureGame, String), String> {
+    let json = serde_json::to_string_pretty(adventure).map_err(|e| format!("Error serialising adventure: {e}"))?;
+    fs::write(path, json).map_err(|e| format!("Error writing temp file: {e}"))?;
+
+    let mut game = Engine::new(path.to_string_lossy()).game;
+    let intro = game.load_adventure().map_err(|e| format!("Failed to load adventure: {e}"))?;
+    if let Some(room_id) = start_room {
+        game.start_in_room(room_id).map_err(|e| format!("Failed to load adventure: {e}"))?;
+    }
+    Ok((game, intro))
+}
+
+/// Starter content for `kind`, converted into the editor's model.
+fn template_data(kind: TemplateKind) -> serde_json::Result<AdventureData> {
+    serde_json::from_value(Adventure::template(kind).to_engine_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_flags_dangling_exit_on_its_room() {
+        let mut adventure = AdventureData::default();
+        adventure.rooms[0].exits.insert("north".to_string(), 99);
+
+        let issues = lint_adventure(&adventure);
+        assert_eq!(issues, vec![ValidationIssue {
+            tab: Tab::Rooms,
+            index: Some(0),
+            message: "Exit north leads to missing room 99".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn edited_adventure_plays_as_saved() {
+        let mut adventure = AdventureData::default();
+        adventure.rooms[0].name = "Gatehouse".to_string();
+        let path = std::env::temp_dir().join(format!("sagacraft_gui_play_{}.json", std::process::id()));
+
+        let (mut game, intro) = load_for_play(&adventure, &path, None).unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(intro.contains("Welcome to your new adventure!"));
+        assert!(game.look().contains("Gatehouse"));
+        assert_eq!(game.step("take lantern").to_plain_lines(), ["Taken: Brass Lantern."]);
+        assert!(game.step("talk").to_plain_lines()[0].contains("Talk to whom?"));
+    }
+
+    #[test]
+    fn templates_open_without_lint_issues() {
+        for kind in TemplateKind::ALL {
+            let adventure = template_data(kind).unwrap();
+            assert!(lint_adventure(&adventure).is_empty(), "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn opening_a_file_with_malformed_entries_keeps_save_off_the_original() {
+        let path = std::env::temp_dir().join(format!("sagacraft_gui_open_{}.json", std::process::id()));
+        let mut adventure = serde_json::to_value(AdventureData::default()).unwrap();
+        adventure["rooms"].as_array_mut().unwrap().push(serde_json::json!({"id": "two", "name": "Broken"}));
+        fs::write(&path, adventure.to_string()).unwrap();
+
+        let mut ide = SagaCraftIDE::default();
+        let skipped = ide.load_from_file(&path).unwrap();
+        ide.finish_open(path.clone(), &skipped);
+        let _ = fs::remove_file(&path);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(ide.adventure.rooms.len(), 1);
+        assert!(ide.current_file.is_none());
+        assert!(ide.modified);
+
+        let mut ide = SagaCraftIDE::default();
+        ide.finish_open(path.clone(), &[]);
+        assert_eq!(ide.current_file, Some(path));
+        assert!(!ide.modified);
+    }
+
+    #[test]
+    fn history_undo_redo_round_trip() {
+        let mut history = History::default();
+        history.record(1);
+        history.record(2);
+
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+        assert_eq!(history.redo(1), Some(2));
+
+        // A fresh edit discards what could have been redone
+        history.record(2);
+        assert_eq!(history.redo(5), None);
+    }
+
+    #[test]
+    fn history_drops_oldest_entries_past_the_limit() {
+        let mut history = History { limit: 2, ..History::default() };
+        for state in 0..3 {
+            history.record(state);
+        }
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+    }
+
+    #[test]
+    fn palette_filter_matches_all_words_case_insensitively() {
+        let names: Vec<&str> = palette_matches("room").iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["Add Room", "Delete Room"]);
+
+        let names: Vec<&str> = palette_matches("ADD q").iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["Add Quest"]);
+        assert_eq!(palette_matches("").len(), PALETTE_ACTIONS.len());
+    }
+
+    #[test]
+    fn reachable_rooms_follows_exits_from_the_start_room() {
+        let mut adventure = AdventureData::default();
+        let template = adventure.rooms[0].clone();
+        adventure.rooms.extend((2..=3).map(|id| RoomData { id, exits: BTreeMap::new(), ..template.clone() }));
+        adventure.rooms[0].exits = BTreeMap::from([("north".to_string(), 2)]);
+
+        let reachable = reachable_rooms(&adventure);
+        assert!(reachable.contains(&2));
+        assert!(!reachable.contains(&3));
+    }
+
+    #[test]
+    fn playtest_refuses_a_room_the_start_room_cannot_reach() {
+        let mut ide = SagaCraftIDE::default();
+        let template = ide.adventure.rooms[0].clone();
+        ide.adventure.rooms.push(RoomData { id: 2, exits: BTreeMap::new(), ..template });
+        ide.selected_room = Some(1);
+
+        ide.playtest_from_selected();
+        assert!(ide.game.is_none());
+        assert_eq!(ide.status, "Room 2 cannot be reached from the start room; add an exit leading to it first");
+
+        ide.adventure.rooms[0].exits.insert("north".to_string(), 2);
+        ide.playtest_from_selected();
+        assert_eq!(ide.game.as_ref().map(|game| game.player.current_room), Some(2));
+    }
+
+    #[test]
+    fn ids_stay_unique_after_deleting_a_middle_entity() {
+        let mut ide = SagaCraftIDE::default();
+        ide.add_room();
+        ide.add_room();
+        ide.add_room();
+        let last = ide.adventure.rooms.last().unwrap().id;
+        ide.selected_room = Some(ide.adventure.rooms.len() - 2);
+        ide.delete_room();
+        ide.add_room();
+        let added = ide.adventure.rooms.last().unwrap().id;
+        assert_eq!(added, last + 1);
+        assert_eq!(ide.adventure.rooms.iter().filter(|r| r.id == added).count(), 1);
+
+        ide.add_item();
+        ide.add_item();
+        let last = ide.adventure.items.last().unwrap().id;
+        ide.selected_item = Some(ide.adventure.items.len() - 2);
+        ide.delete_item();
+        ide.add_item();
+        let added = ide.adventure.items.last().unwrap().id;
+        assert_eq!(added, last + 1);
+        assert_eq!(ide.adventure.items.iter().filter(|i| i.id == added).count(), 1);
+    }
+}
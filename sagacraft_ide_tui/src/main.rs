@@ -1,6 +1,7 @@
+use std::ffi::OsString;
 use std::io;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
@@ -12,7 +13,7 @@ use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
 use ratatui::Terminal;
 
-use sagacraft_rs::{Adventure, AdventureItem, AdventureRoom};
+use sagacraft_rs::{direction_cmp, Adventure, AdventureGame, AdventureItem, AdventureRoom};
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse(std::env::args().skip(1).collect());
@@ -34,11 +35,15 @@ fn main() -> anyhow::Result<()> {
     let mut app = if is_new {
         App::new_with_file(file_path, Adventure::demo())
     } else {
-        match Adventure::load_json_file(&file_path) {
+        match Adventure::load_file(&file_path) {
             Ok(adv) => App::new_with_file(file_path, adv),
             Err(_) => App::new_with_file(file_path, Adventure::demo()),
         }
     };
+    app.autosave_after = args.autosave_secs.filter(|&secs| secs > 0).map(Duration::from_secs);
+    if !is_new {
+        app.offer_recovery();
+    }
 
     let mut tui = Tui::new()?;
     let res = run(&mut tui, &mut app);
@@ -53,12 +58,16 @@ fn print_help() {
     println!("  sagacraft_ide_tui --file <path>");
     println!("  sagacraft_ide_tui --new <path>");
     println!();
+    println!("Options:");
+    println!("  --autosave <secs>  after this many idle seconds, write unsaved changes to <path>.autosave");
+    println!();
     println!("Keys:");
     println!("  Up/Down   select room");
     println!("  :         command mode");
     println!("  Esc       normal mode");
     println!("  q         quit");
     println!("  s         save");
+    println!("  r         recover from <path>.autosave, when offered at startup");
     println!();
     println!("Commands (type after ':' then Enter):");
     println!("  w | write              save");
@@ -74,6 +83,8 @@ fn print_help() {
     println!("  exit del <dir>         delete selected room exit");
     println!("  item add <id> <name> <desc>   add item to selected room");
     println!("  item del <name>               delete item by name");
+    println!("  merge <file> <id_prefix>      append another adventure, its ids prefixed");
+    println!("  lint                          list one-way exits, dead ends and unreachable rooms");
     println!();
     println!("Tip: quote values with spaces, e.g. item add key \"Ancient Key\" \"A key.\"");
 }
@@ -82,6 +93,7 @@ fn print_help() {
 struct Args {
     file: Option<PathBuf>,
     new: Option<PathBuf>,
+    autosave_secs: Option<u64>,
     show_help: bool,
 }
 
@@ -104,6 +116,18 @@ impl Args {
                         break;
                     }
                 }
+                "--autosave" => {
+                    match argv.first().and_then(|secs| secs.parse().ok()) {
+                        Some(secs) => {
+                            argv.remove(0);
+                            out.autosave_secs = Some(secs);
+                        }
+                        None => {
+                            out.show_help = true;
+                            break;
+                        }
+                    }
+                }
                 "--new" => {
                     if let Some(p) = argv.first().cloned() {
                         argv.remove(0);
@@ -156,51 +180,100 @@ enum Mode {
 struct App {
     file: PathBuf,
     adventure: Adventure,
-    selected_room: usize,
+    /// Id of the selected room. Resolved to an index on use, so adding or
+    /// deleting other rooms never moves the selection to a different room.
+    selected_room: Option<String>,
+    /// Index the selection was last resolved to; picks a neighbour when the
+    /// selected room itself is deleted.
+    selection_hint: usize,
     mode: Mode,
     cmd: String,
     status: String,
     dirty: bool,
     quit_confirm: bool,
+    /// Idle time after which unsaved changes are written to the autosave file.
+    autosave_after: Option<Duration>,
+    /// When the last key was pressed.
+    last_input: Instant,
+    /// Unsaved changes have been made since the last autosave.
+    autosave_due: bool,
+    /// An autosave newer than `file`, offered for recovery until the next key.
+    recovery: Option<PathBuf>,
+    /// Character sheet from `sheet`, shown in place of the room details until the next key.
+    sheet: Option<Vec<String>>,
 }
 
 impl App {
     fn new_with_file(file: PathBuf, adventure: Adventure) -> Self {
-        let selected_room = 0;
+        let selected_room = adventure.rooms.first().map(|r| r.id.clone());
         Self {
             file,
             adventure,
             selected_room,
+            selection_hint: 0,
             mode: Mode::Normal,
             cmd: String::new(),
             status: "Press ':' for commands. 's' to save.".to_string(),
             dirty: false,
             quit_confirm: false,
+            autosave_after: None,
+            last_input: Instant::now(),
+            autosave_due: false,
+            recovery: None,
+            sheet: None,
         }
     }
 
+    /// Index of the selected room: wherever its id now is, or the room
+    /// nearest its last position if it no longer exists.
+    fn selected_index(&self) -> Option<usize> {
+        let last = self.adventure.rooms.len().checked_sub(1)?;
+        self.selected_room.as_ref()
+            .and_then(|id| self.adventure.rooms.iter().position(|r| &r.id == id))
+            .or(Some(self.selection_hint.min(last)))
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.selected_room = self.adventure.rooms.get(index).map(|r| r.id.clone());
+        self.selection_hint = index;
+    }
+
     fn selected_room_mut(&mut self) -> Option<&mut AdventureRoom> {
-        self.adventure.rooms.get_mut(self.selected_room)
+        let index = self.selected_index()?;
+        self.adventure.rooms.get_mut(index)
     }
 
     fn selected_room(&self) -> Option<&AdventureRoom> {
-        self.adventure.rooms.get(self.selected_room)
+        self.adventure.rooms.get(self.selected_index()?)
     }
 
+    /// Re-resolve the selection after the room list changed.
     fn clamp_selection(&mut self) {
-        if self.adventure.rooms.is_empty() {
-            self.selected_room = 0;
-        } else if self.selected_room >= self.adventure.rooms.len() {
-            self.selected_room = self.adventure.rooms.len() - 1;
+        match self.selected_index() {
+            Some(index) => self.select_index(index),
+            None => {
+                self.selected_room = None;
+                self.selection_hint = 0;
+            }
         }
     }
 
+    /// The character sheet a player starts the adventure with, as the engine loads it.
+    fn starting_sheet(&self) -> Result<Vec<String>, String> {
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(&self.adventure.to_engine_json().to_string()).map_err(|e| e.to_string())?;
+        Ok(game.character_sheet())
+    }
+
     fn save(&mut self) {
-        match self.adventure.save_json_file(&self.file) {
+        match self.adventure.save_file(&self.file) {
             Ok(()) => {
                 self.status = format!("Saved {}", self.file.display());
                 self.dirty = false;
                 self.quit_confirm = false;
+                self.autosave_due = false;
+                // The real file is now the newest copy
+                let _ = std::fs::remove_file(autosave_path(&self.file));
             }
             Err(e) => {
                 self.status = format!("Save failed: {e}");
@@ -208,6 +281,43 @@ impl App {
         }
     }
 
+    /// Write unsaved changes to the autosave file once the user has been idle
+    /// for `autosave_after`. The real file and the dirty flag are left alone.
+    fn autosave_if_idle(&mut self) {
+        let Some(after) = self.autosave_after else {
+            return;
+        };
+        if !self.dirty || !self.autosave_due || self.last_input.elapsed() < after {
+            return;
+        }
+        let path = autosave_path(&self.file);
+        self.autosave_due = false;
+        match self.adventure.save_file(&path) {
+            Ok(()) => self.status = format!("Autosaved to {}", path.display()),
+            Err(e) => self.status = format!("Autosave failed: {e}"),
+        }
+    }
+
+    /// Offer the autosave for recovery if it is newer than the file itself.
+    fn offer_recovery(&mut self) {
+        let path = autosave_path(&self.file);
+        if should_offer_recovery(modified(&self.file), modified(&path)) {
+            self.status = format!("{} is newer than the file. Press r to recover it, any other key to ignore.", path.display());
+            self.recovery = Some(path);
+        }
+    }
+
+    fn recover(&mut self, path: &Path) {
+        match Adventure::load_file(path) {
+            Ok(adventure) => {
+                self.adventure = adventure;
+                self.dirty = true;
+                self.status = format!("Recovered {}. Save with s to keep it.", path.display());
+            }
+            Err(e) => self.status = format!("Could not recover {}: {e}", path.display()),
+        }
+    }
+
     fn exec_command(&mut self, raw: &str) {
         let line = raw.trim();
         if line.is_empty() {
@@ -228,8 +338,22 @@ impl App {
 
         match cmd0 {
             "help" => {
-                self.status = "Commands: w, q, wq, set start <room>, room add/del/set, exit set/del, item add/del".to_string();
+                self.status = "Commands: w, q, wq, set start <room>, room add/del/set, exit set/del, item add/del, merge <file> <prefix>, lint, sheet".to_string();
             }
+            "lint" => {
+                let warnings = self.adventure.lint();
+                self.status = match warnings.len() {
+                    0 => "Lint: no problems found".to_string(),
+                    n => format!("Lint: {} warning{}: {}", n, if n == 1 { "" } else { "s" }, warnings.join("; ")),
+                };
+            }
+            "sheet" => match self.starting_sheet() {
+                Ok(sheet) => {
+                    self.sheet = Some(sheet);
+                    self.status = "Character sheet at the start of play; press any key to go back".to_string();
+                }
+                Err(e) => self.status = format!("Could not load the adventure: {e}"),
+            },
             "w" | "write" => self.save(),
             "q" | "quit" => {
                 self.status = "quit".to_string();
@@ -251,6 +375,20 @@ impl App {
                     self.status = "usage: set start <room_id>".to_string();
                 }
             }
+            "merge" => {
+                let (Some(path), Some(prefix)) = (words.get(1), words.get(2)) else {
+                    self.status = "usage: merge <file> <id_prefix>".to_string();
+                    return;
+                };
+                let merged = Adventure::load_file(path).and_then(|other| self.adventure.merge(other, prefix));
+                match merged {
+                    Ok(()) => {
+                        self.dirty = true;
+                        self.status = format!("Merged {path} with ids prefixed '{prefix}'");
+                    }
+                    Err(e) => self.status = format!("Merge failed: {e}"),
+                }
+            }
             "room" => self.exec_room_command(&words),
             "exit" => self.exec_exit_command(&words),
             "item" => self.exec_item_command(&words),
@@ -277,11 +415,10 @@ impl App {
                     description: "".to_string(),
                     exits: Default::default(),
                     items: vec![],
+                    locks: Default::default(),
                 });
-                self.selected_room = self.adventure.rooms.len() - 1;
-                if self.adventure.start_room.trim().is_empty() {
-                    self.adventure.start_room = id.clone();
-                }
+                self.select_index(self.adventure.rooms.len() - 1);
+                let _ = self.adventure.ensure_start_room(false);
                 self.dirty = true;
                 self.status = format!("Added room '{id}'");
             }
@@ -298,7 +435,12 @@ impl App {
                 self.adventure.rooms.remove(idx);
                 self.clamp_selection();
                 self.dirty = true;
-                self.status = format!("Deleted room '{id}'");
+                self.status = match self.adventure.ensure_start_room(false) {
+                    Ok(Some(start)) => format!(
+                        "Deleted start room '{id}'; '{start}' is the start room now (pick another with: set start <room>)"
+                    ),
+                    _ => format!("Deleted room '{id}'"),
+                };
             }
             Some("set") => {
                 let Some(field) = words.get(2).map(|s| s.as_str()) else {
@@ -452,6 +594,27 @@ fn parse_words(s: &str) -> Result<Vec<String>, String> {
     Ok(out)
 }
 
+/// Where unsaved changes to `file` are autosaved: `<file>.autosave`.
+fn autosave_path(file: &Path) -> PathBuf {
+    let mut name = OsString::from(file.as_os_str());
+    name.push(".autosave");
+    PathBuf::from(name)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Whether an autosave last modified at `autosave` should be offered over the
+/// file last modified at `file` (`None` if either doesn't exist).
+fn should_offer_recovery(file: Option<SystemTime>, autosave: Option<SystemTime>) -> bool {
+    match (file, autosave) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(file), Some(autosave)) => autosave > file,
+    }
+}
+
 fn run(tui: &mut Tui, app: &mut App) -> anyhow::Result<()> {
     loop {
         app.clamp_selection();
@@ -473,9 +636,16 @@ fn run(tui: &mut Tui, app: &mut App) -> anyhow::Result<()> {
 
         if event::poll(Duration::from_millis(100))?
             && let Event::Key(key) = event::read()?
-            && handle_key(app, key)
         {
-            break;
+            app.last_input = Instant::now();
+            if handle_key(app, key) {
+                break;
+            }
+            if app.dirty {
+                app.autosave_due = true;
+            }
+        } else {
+            app.autosave_if_idle();
         }
     }
 
@@ -488,6 +658,7 @@ fn draw_main(f: &mut ratatui::Frame, area: Rect, app: &App) {
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
         .split(area);
 
+    let selected = app.selected_index();
     let items: Vec<ListItem> = app
         .adventure
         .rooms
@@ -495,7 +666,7 @@ fn draw_main(f: &mut ratatui::Frame, area: Rect, app: &App) {
         .enumerate()
         .map(|(i, r)| {
             let mut style = Style::default();
-            if i == app.selected_room {
+            if Some(i) == selected {
                 style = style.add_modifier(Modifier::BOLD);
             }
             let start_mark = if r.id == app.adventure.start_room { "*" } else { " " };
@@ -522,6 +693,9 @@ fn draw_main(f: &mut ratatui::Frame, area: Rect, app: &App) {
 }
 
 fn room_details_text(app: &App) -> Text<'static> {
+    if let Some(sheet) = &app.sheet {
+        return Text::from(sheet.iter().map(|line| Line::from(line.clone())).collect::<Vec<_>>());
+    }
     let mut lines: Vec<Line<'static>> = Vec::new();
 
     lines.push(Line::from(format!(
@@ -553,7 +727,7 @@ fn room_details_text(app: &App) -> Text<'static> {
         lines.push(Line::from("  (none)"));
     } else {
         let mut exits: Vec<_> = room.exits.iter().collect();
-        exits.sort_by(|a, b| a.0.cmp(b.0));
+        exits.sort_by(|a, b| direction_cmp(a.0, b.0));
         for (dir, dest) in exits {
             lines.push(Line::from(format!("  {dir} -> {dest}")));
         }
@@ -598,6 +772,13 @@ fn draw_status(f: &mut ratatui::Frame, area: Rect, app: &App) {
 }
 
 fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    app.sheet = None;
+    if let Some(path) = app.recovery.take()
+        && key.code == KeyCode::Char('r')
+    {
+        app.recover(&path);
+        return false;
+    }
     match app.mode {
         Mode::Normal => handle_key_normal(app, key),
         Mode::Command => handle_key_command(app, key),
@@ -631,15 +812,15 @@ fn handle_key_normal(app: &mut App, key: KeyEvent) -> bool {
             false
         }
         (KeyCode::Up, _) => {
-            if app.selected_room > 0 {
-                app.selected_room -= 1;
+            if let Some(index) = app.selected_index().filter(|&i| i > 0) {
+                app.select_index(index - 1);
             }
             app.quit_confirm = false;
             false
         }
         (KeyCode::Down, _) => {
-            if app.selected_room + 1 < app.adventure.rooms.len() {
-                app.selected_room += 1;
+            if let Some(index) = app.selected_index().filter(|&i| i + 1 < app.adventure.rooms.len()) {
+                app.select_index(index + 1);
             }
             app.quit_confirm = false;
             false
@@ -674,3 +855,83 @@ fn handle_key_command(app: &mut App, key: KeyEvent) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with_rooms(ids: &[&str]) -> App {
+        let mut app = App::new_with_file(PathBuf::from("test.json"), Adventure::demo());
+        app.adventure.rooms.clear();
+        for id in ids {
+            app.exec_command(&format!("room add {id}"));
+        }
+        app
+    }
+
+    #[test]
+    fn recovery_is_offered_only_for_a_newer_autosave() {
+        let earlier = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let later = earlier + Duration::from_secs(60);
+        assert!(should_offer_recovery(Some(earlier), Some(later)));
+        assert!(!should_offer_recovery(Some(later), Some(earlier)));
+        assert!(!should_offer_recovery(Some(later), Some(later)));
+        assert!(!should_offer_recovery(Some(later), None));
+        assert!(should_offer_recovery(None, Some(earlier)));
+        assert_eq!(autosave_path(Path::new("maps/keep.json")), PathBuf::from("maps/keep.json.autosave"));
+    }
+
+    #[test]
+    fn sheet_shows_the_starting_character_until_the_next_key() {
+        let mut app = app_with_rooms(&["cave"]);
+        app.exec_command("sheet");
+        let sheet = app.sheet.clone().unwrap();
+        assert!(sheet.iter().any(|line| line.contains("Level:     1  (XP 0/100)")), "{sheet:?}");
+        assert!(sheet.iter().any(|line| line.contains("cave")), "{sheet:?}");
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(app.sheet.is_none());
+    }
+
+    #[test]
+    fn lint_reports_rooms_left_unconnected() {
+        let mut app = app_with_rooms(&["cave", "hall"]);
+        app.exec_command("lint");
+        assert!(app.status.starts_with("Lint: 3 warnings:"), "{}", app.status);
+        assert!(app.status.contains("room 'hall' can't be reached from 'cave'"), "{}", app.status);
+    }
+
+    #[test]
+    fn selection_follows_the_room_id_when_others_are_deleted() {
+        let mut app = app_with_rooms(&["cave", "hall", "tower"]);
+        app.select_index(2);
+        app.exec_command("room del cave");
+        assert_eq!(app.selected_room().map(|r| r.id.as_str()), Some("tower"));
+    }
+
+    #[test]
+    fn deleting_the_start_room_picks_the_lowest_remaining_id() {
+        let mut app = app_with_rooms(&["tower", "cave", "hall"]);
+        app.exec_command("set start hall");
+        app.exec_command("room del hall");
+        assert_eq!(app.adventure.start_room, "cave");
+        assert!(app.status.contains("'cave' is the start room now"), "{}", app.status);
+
+        app.exec_command("room del tower");
+        assert_eq!(app.adventure.start_room, "cave");
+        assert_eq!(app.status, "Deleted room 'tower'");
+    }
+
+    #[test]
+    fn deleting_the_selected_room_selects_a_neighbour() {
+        let mut app = app_with_rooms(&["cave", "hall", "tower"]);
+        app.select_index(1);
+        app.exec_command("room del");
+        assert_eq!(app.selected_room().map(|r| r.id.as_str()), Some("tower"));
+
+        app.exec_command("room del");
+        assert_eq!(app.selected_room().map(|r| r.id.as_str()), Some("cave"));
+        app.exec_command("room del");
+        assert_eq!(app.selected_index(), None);
+    }
+}
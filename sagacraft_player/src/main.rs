@@ -1,44 +1,221 @@
-use std::io::{self, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
-use sagacraft_rs::Engine;
+use crossterm::event::{self, Event};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+
+use sagacraft_rs::command::{help_text, ArgShape, CommandSpec};
+use sagacraft_rs::{catalog, Adventure, Bundle, Engine, HallOfFame, Level, Logger, Replay, ScoreEntry, TemplateKind, Transcript, HALL_OF_FAME_FILE};
 
 const DEFAULT_ADVENTURE: &str = "shattered_realms_demo.json";
 
+/// Prompt used when neither `--prompt` nor the adventure's `ui.prompt` sets one.
+const DEFAULT_PROMPT: &str = "> ";
+
+/// Characters per second for `--typewriter` given without a rate.
+const DEFAULT_TYPEWRITER_CPS: u32 = 40;
+
+/// Environment variable naming a directory to look for adventures in.
+const ADVENTURES_DIR_VAR: &str = "SAGACRAFT_ADVENTURES_DIR";
+
+/// Commands the player handles itself, on top of the engine's grammar.
+const CLI_COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("scores", &["highscores"], ArgShape::None, "Show the hall of fame"),
+    CommandSpec::new("hud", &[], ArgShape::None, "Toggle the room/health header"),
+    CommandSpec::new("quit", &["q", "exit"], ArgShape::None, "Quit"),
+];
+
+/// Parsed command-line options.
+struct Options {
+    adventure_path: String,
+    /// Where to write a replay of this session, if anywhere.
+    record_path: Option<String>,
+    /// Where to append a readable transcript of this session, if anywhere.
+    transcript_path: Option<String>,
+    log_level: Level,
+    /// Write the adventure as a `.saga` bundle here instead of playing it.
+    bundle_path: Option<String>,
+    /// Start with the heads-up header shown before each prompt.
+    hud: bool,
+    /// Directory searched for adventures not found as given.
+    adventures_dir: Option<String>,
+    /// Skip malformed rooms, items and monsters instead of refusing to load.
+    lenient: bool,
+    /// Prompt template; overrides the adventure's `ui.prompt`.
+    prompt: Option<String>,
+    /// Print engine output this many characters per second.
+    typewriter: Option<u32>,
+    /// Offer the engine's debugging commands (`systems`, `enable`, `disable`).
+    debug: bool,
+}
+
 fn main() {
-    let adventure_path = parse_args(std::env::args().skip(1));
+    let Options { adventure_path, record_path, transcript_path, log_level, bundle_path, hud, adventures_dir, lenient, prompt, typewriter, debug } = parse_args(std::env::args().skip(1));
 
-    let mut engine = match Engine::load(&adventure_path) {
-        Ok(e) => e,
-        Err(err) => {
-            eprintln!("Failed to load adventure '{}': {}", adventure_path, err);
+    let configured = adventures_dir.or_else(|| std::env::var(ADVENTURES_DIR_VAR).ok());
+    let adventure_path = match resolve_adventure_path(&adventure_path, &adventure_dirs(configured.as_deref())) {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(tried) => {
+            eprintln!("Could not find adventure '{}'. Looked in:", adventure_path);
+            for path in tried {
+                eprintln!("  {}", path.display());
+            }
             std::process::exit(1);
         }
     };
 
+    if let Some(out) = bundle_path {
+        match Bundle::from_adventure_file(&adventure_path).and_then(|bundle| bundle.save(&out)) {
+            Ok(()) => println!("Bundled '{}' into {}.", adventure_path, out),
+            Err(err) => {
+                eprintln!("Could not bundle '{}': {}", adventure_path, err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut engine = Engine::new(&adventure_path);
+    engine.game.logger = Logger::new(log_level);
+    let started = if lenient { engine.start_lenient() } else { engine.start() };
+    if let Err(err) = started {
+        eprintln!("Failed to load adventure '{}': {}", adventure_path, err);
+        std::process::exit(1);
+    }
+    if debug {
+        engine.game.settings.gameplay.debug_commands = true;
+    }
+    if !engine.game.skipped_entities.is_empty() {
+        eprintln!("Skipped {} malformed entries:", engine.game.skipped_entities.len());
+        for entity in &engine.game.skipped_entities {
+            eprintln!("  {}", entity);
+        }
+        eprintln!();
+    }
+
     println!("SagaCraft — CLI Player");
     println!("Type 'help' for commands. Type 'quit' to exit.\n");
 
+    let typewriter = typewriter_rate(typewriter, io::stdin().is_terminal() && io::stdout().is_terminal());
+
     // Print intro/banner text from adventure file, then room description
     let intro = engine.intro();
     if !intro.is_empty() {
-        println!("{}\n", intro);
+        print_lines(&mut io::stdout(), &[intro.to_string(), String::new()], typewriter);
+    }
+    print_lines(&mut io::stdout(), &[engine.look()], typewriter);
+
+    let mut transcript = transcript_path.and_then(|path| {
+        let opened = Transcript::open(&path, &engine.game.adventure_title)
+            .and_then(|mut transcript| transcript.write_lines(&[engine.look()]).map(|()| transcript));
+        opened.map_err(|err| eprintln!("Could not write transcript '{}': {}", path, err)).ok()
+    });
+
+    // Ctrl-C doesn't wake a blocking read, so leave from the handler itself;
+    // `run` has already saved the replay up to the last command.
+    let interrupted_replay = record_path.clone();
+    if let Err(err) = ctrlc::set_handler(move || {
+        println!("\nInterrupted. Farewell, adventurer.");
+        if let Some(path) = &interrupted_replay {
+            println!("Replay written to {}.", path);
+        }
+        let _ = io::stdout().flush();
+        std::process::exit(130);
+    }) {
+        eprintln!("Could not install Ctrl-C handler: {}", err);
     }
-    println!("{}", engine.look());
 
-    let stdin = io::stdin();
+    if record_path.is_some() {
+        engine.game.start_recording(time_seed());
+    }
+
+    let prompt = prompt
+        .or_else(|| engine.game.settings.ui.prompt.clone())
+        .unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+    run(&mut engine, io::stdin().lock(), hud, &prompt, typewriter, transcript.as_mut(), record_path.as_deref());
+
+    if let Some(path) = record_path
+        && let Some(replay) = engine.game.finish_recording()
+    {
+        match replay.write(&path) {
+            Ok(()) => println!("Replay written to {}.", path),
+            Err(err) => eprintln!("Could not write replay '{}': {}", path, err),
+        }
+    }
+}
+
+/// A seed for a recorded session; the replay file stores it, so it only needs to vary.
+fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Re-run a recorded session and exit, non-zero if it diverged.
+fn replay_and_exit(path: &str) -> ! {
+    let replay = match Replay::load(path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("Could not read replay '{}': {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    match replay.run() {
+        Ok(turns) => {
+            println!("Replay matched: {} turns, identical final state.", turns);
+            std::process::exit(0)
+        }
+        Err(err) => {
+            eprintln!("Replay diverged: {}", err);
+            std::process::exit(1)
+        }
+    }
+}
+
+/// The read-eval-print loop. Returns when the game ends, the player quits,
+/// or `input` reaches end-of-file. Every command and its output also go to
+/// `transcript`, if there is one, and the replay being recorded is saved to
+/// `replay_path` after each game command so an interrupted session keeps it.
+fn run(
+    engine: &mut Engine,
+    mut input_source: impl BufRead,
+    mut hud: bool,
+    prompt: &str,
+    typewriter: Option<u32>,
+    mut transcript: Option<&mut Transcript>,
+    mut replay_path: Option<&str>,
+) {
     loop {
         if engine.is_over() {
             println!("\n--- Game Over ---");
+            record_result(engine);
             break;
         }
 
-        print!("> ");
+        if hud {
+            for line in hud_lines(engine) {
+                println!("{}", line);
+            }
+        }
+
+        let rendered_prompt = render_prompt(prompt, engine);
+        print!("{}", rendered_prompt);
         let _ = io::stdout().flush();
 
         let mut input = String::new();
-        if stdin.read_line(&mut input).is_err() {
-            println!("Failed to read input.");
-            continue;
+        match input_source.read_line(&mut input) {
+            Ok(0) => {
+                // End of input (e.g. piped commands exhausted or Ctrl-D)
+                println!();
+                break;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("Failed to read input: {}", err);
+                break;
+            }
         }
 
         let input = input.trim();
@@ -46,25 +223,228 @@ fn main() {
             continue;
         }
 
-        match input.to_lowercase().as_str() {
+        let turn = engine.game.turn_count;
+        let (output, typed) = match input.to_lowercase().as_str() {
             "quit" | "q" | "exit" => break,
-            _ => {
-                for line in engine.send(input) {
-                    println!("{}", line);
-                }
+            "scores" | "highscores" => (score_lines(engine), false),
+            "hud" => {
+                hud = !hud;
+                (vec![format!("HUD {}.", if hud { "on" } else { "off" })], false)
             }
+            "help" | "?" => (vec![help_text(&[engine.game.grammar().as_slice(), CLI_COMMANDS].concat())], false),
+            _ => (engine.send(input), true),
+        };
+        print_lines(&mut io::stdout(), &output, if typed { typewriter } else { None });
+        if let Some(file) = transcript.as_deref_mut()
+            && let Err(err) = file.record(turn, &rendered_prompt, input, &output)
+        {
+            eprintln!("Could not write transcript: {}; no longer recording it.", err);
+            transcript = None;
+        }
+        if typed
+            && let Some(path) = replay_path
+            && let Some(replay) = engine.game.recording_so_far()
+            && let Err(err) = replay.write(path)
+        {
+            eprintln!("Could not write replay '{}': {}; no longer saving it each turn.", path, err);
+            replay_path = None;
         }
     }
 }
 
-fn parse_args(mut args: impl Iterator<Item = String>) -> String {
+/// The two-line header shown before each prompt in HUD mode: where the
+/// player is and the ways out, then health and gold.
+fn hud_lines(engine: &Engine) -> [String; 2] {
+    let game = &engine.game;
+    let room = game.get_current_room().map(|r| r.name.as_str()).unwrap_or("Nowhere");
+    let exits = game.visible_exits();
+    let exits = if exits.is_empty() { "none".to_string() } else { exits.join(" ") };
+    [
+        format!("[{}]  Exits: {}", room, exits),
+        format!("[HP {}/{}]  Gold: {}  Turn: {}",
+            game.player.current_health, game.player.hardiness, game.player.gold, game.turn_count),
+    ]
+}
+
+/// The typewriter rate to use: `requested`, but only when the player is at
+/// a terminal, so piped scripts and captured output stay instant.
+fn typewriter_rate(requested: Option<u32>, interactive: bool) -> Option<u32> {
+    requested.filter(|&cps| interactive && cps > 0)
+}
+
+/// Print engine output. With a typewriter rate, each character waits its
+/// turn, and any key press prints the rest at once.
+fn print_lines(out: &mut impl Write, lines: &[String], typewriter: Option<u32>) {
+    let Some(cps) = typewriter.filter(|_| enable_raw_mode().is_ok()) else {
+        for line in lines {
+            let _ = writeln!(out, "{}", line);
+        }
+        return;
+    };
+    let delay = Duration::from_secs(1) / cps;
+    let mut skipped = false;
+    for line in lines {
+        for ch in line.chars() {
+            // Raw mode leaves newlines alone, so return the carriage too
+            let _ = if ch == '\n' { write!(out, "\r\n") } else { write!(out, "{}", ch) };
+            if !skipped {
+                let _ = out.flush();
+                skipped = event::poll(delay).unwrap_or(false) && matches!(event::read(), Ok(Event::Key(_)));
+            }
+        }
+        let _ = write!(out, "\r\n");
+    }
+    let _ = out.flush();
+    let _ = disable_raw_mode();
+}
+
+/// Fill in the `{room}`, `{health}`, `{gold}` and `{turn}` tokens of a
+/// prompt template. With no current room, `{room}` is left empty.
+fn render_prompt(template: &str, engine: &Engine) -> String {
+    let game = &engine.game;
+    let room = game.get_current_room().map(|r| r.name.as_str()).unwrap_or("");
+    template
+        .replace("{room}", room)
+        .replace("{health}", &game.player.current_health.to_string())
+        .replace("{gold}", &game.player.gold.to_string())
+        .replace("{turn}", &game.turn_count.to_string())
+}
+
+/// Append the finished playthrough to the hall of fame.
+fn record_result(engine: &Engine) {
+    let won = engine.game.player.current_health > 0;
+    let result = HallOfFame::load(HALL_OF_FAME_FILE)
+        .and_then(|mut hall| hall.record(ScoreEntry::from_game(&engine.game, won)));
+    match result {
+        Ok(()) => println!("Final score: {}", engine.game.compute_score()),
+        Err(err) => eprintln!("Could not record score: {}", err),
+    }
+}
+
+/// The best results for the current adventure, ready to print.
+fn score_lines(engine: &Engine) -> Vec<String> {
+    let hall = match HallOfFame::load(HALL_OF_FAME_FILE) {
+        Ok(hall) => hall,
+        Err(err) => return vec![format!("Could not read hall of fame: {}", err)],
+    };
+    let top = hall.top(&engine.game.adventure_title, 10);
+    if top.is_empty() {
+        return vec!["No scores recorded for this adventure yet.".to_string()];
+    }
+    let mut lines = vec![format!("Hall of Fame — {}", engine.game.adventure_title)];
+    for (rank, entry) in top.iter().enumerate() {
+        lines.push(format!(
+            "{:>2}. {:<16} {:>6}  {:>4} turns  {}{}",
+            rank + 1,
+            entry.player_name,
+            entry.score,
+            entry.turns,
+            entry.date,
+            if entry.won { "  (won)" } else { "" }
+        ));
+    }
+    lines
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Options {
+    let mut args = args.peekable();
     let mut adventure_path: Option<String> = None;
+    let mut record_path: Option<String> = None;
+    let mut transcript_path: Option<String> = None;
+    let mut log_level = Level::Warn;
+    let mut bundle_path: Option<String> = None;
+    let mut hud = false;
+    let mut adventures_dir: Option<String> = None;
+    let mut lenient = false;
+    let mut prompt: Option<String> = None;
+    let mut typewriter: Option<u32> = None;
+    let mut debug = false;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--help" | "-h" => {
                 print_usage_and_exit();
             }
+            "--list" | "-l" => {
+                list_adventures(&args.next().unwrap_or_else(|| ".".to_string()));
+            }
+            "--record" => {
+                if let Some(path) = args.next() {
+                    record_path = Some(path);
+                } else {
+                    eprintln!("--record requires a path argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--transcript" => {
+                if let Some(path) = args.next() {
+                    transcript_path = Some(path);
+                } else {
+                    eprintln!("--transcript requires a path argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--replay" => {
+                if let Some(path) = args.next() {
+                    replay_and_exit(&path);
+                } else {
+                    eprintln!("--replay requires a path argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--new-template" => {
+                match (args.next(), args.next()) {
+                    (Some(kind), Some(path)) => new_template_and_exit(&kind, &path),
+                    _ => {
+                        eprintln!("--new-template requires a template kind and a path.");
+                        print_usage_and_exit();
+                    }
+                }
+            }
+            "--adventures-dir" => {
+                if let Some(dir) = args.next() {
+                    adventures_dir = Some(dir);
+                } else {
+                    eprintln!("--adventures-dir requires a path argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--hud" => hud = true,
+            "--typewriter" => {
+                let rate = args.next_if(|arg| arg.parse::<u32>().is_ok());
+                typewriter = Some(rate.map_or(DEFAULT_TYPEWRITER_CPS, |cps| cps.parse().expect("checked above")));
+            }
+            "--prompt" => {
+                if let Some(template) = args.next() {
+                    prompt = Some(template);
+                } else {
+                    eprintln!("--prompt requires a template argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--lenient" => lenient = true,
+            "--debug" => debug = true,
+            "--bundle" => {
+                if let Some(path) = args.next() {
+                    bundle_path = Some(path);
+                } else {
+                    eprintln!("--bundle requires a path argument.");
+                    print_usage_and_exit();
+                }
+            }
+            "--log-level" => {
+                match args.next().map(|level| level.parse::<Level>()) {
+                    Some(Ok(level)) => log_level = level,
+                    Some(Err(err)) => {
+                        eprintln!("--log-level: {}", err);
+                        print_usage_and_exit();
+                    }
+                    None => {
+                        eprintln!("--log-level requires a level argument.");
+                        print_usage_and_exit();
+                    }
+                }
+            }
             "--adventure" | "-a" => {
                 if let Some(path) = args.next() {
                     adventure_path = Some(path);
@@ -84,7 +464,105 @@ fn parse_args(mut args: impl Iterator<Item = String>) -> String {
         }
     }
 
-    adventure_path.unwrap_or_else(|| DEFAULT_ADVENTURE.to_string())
+    Options {
+        adventure_path: adventure_path.unwrap_or_else(|| DEFAULT_ADVENTURE.to_string()),
+        record_path,
+        transcript_path,
+        log_level,
+        bundle_path,
+        hud,
+        adventures_dir,
+        lenient,
+        prompt,
+        typewriter,
+        debug,
+    }
+}
+
+/// Write a starter adventure of the template `kind` to `path` and exit.
+fn new_template_and_exit(kind: &str, path: &str) -> ! {
+    let kind: TemplateKind = match kind.parse() {
+        Ok(kind) => kind,
+        Err(err) => {
+            eprintln!("--new-template: {}", err);
+            std::process::exit(1);
+        }
+    };
+    match Adventure::template(kind).save_engine_json_file(path) {
+        Ok(()) => {
+            println!("Wrote a new {} adventure to {}.", kind.name(), path);
+            std::process::exit(0)
+        }
+        Err(err) => {
+            eprintln!("Could not write '{}': {}", path, err);
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Expand a leading `~` to the home directory.
+fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(home).join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Where to look for an adventure that isn't found as given: the configured
+/// directory, if any, then `~/.sagacraft/adventures`.
+fn adventure_dirs(configured: Option<&str>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = configured.map(expand_tilde).into_iter().collect();
+    dirs.push(expand_tilde("~/.sagacraft/adventures"));
+    dirs
+}
+
+/// Find the adventure `path` refers to. It is tried as given (after `~`
+/// expansion), then, if relative, in each of `dirs`, where a bare name also
+/// matches `<name>.json`. On failure, returns every location tried.
+fn resolve_adventure_path(path: &str, dirs: &[PathBuf]) -> Result<PathBuf, Vec<PathBuf>> {
+    let given = expand_tilde(path);
+    let mut tried = vec![given.clone()];
+    if given.is_relative() {
+        for dir in dirs {
+            let candidate = dir.join(&given);
+            if given.extension().is_none() {
+                tried.push(candidate.with_extension("json"));
+            }
+            tried.push(candidate);
+        }
+    }
+    tried.iter().find(|p| p.is_file()).cloned().ok_or(tried)
+}
+
+/// Print the adventures found in `dir` and exit.
+fn list_adventures(dir: &str) -> ! {
+    let adventures = match catalog(dir) {
+        Ok(adventures) => adventures,
+        Err(err) => {
+            eprintln!("Could not list adventures in '{}': {}", dir, err);
+            std::process::exit(1);
+        }
+    };
+    if adventures.is_empty() {
+        println!("No adventures found in '{}'.", dir);
+    }
+    for meta in adventures {
+        let mut heading = meta.title.clone();
+        if let Some(version) = &meta.version {
+            heading.push_str(&format!(" v{}", version));
+        }
+        if let Some(author) = &meta.author {
+            heading.push_str(&format!(" by {}", author));
+        }
+        println!("{}", heading);
+        println!("  {}", meta.path.display());
+        if !meta.description.is_empty() {
+            println!("  {}", meta.description);
+        }
+    }
+    std::process::exit(0)
 }
 
 fn print_usage_and_exit() -> ! {
@@ -92,11 +570,125 @@ fn print_usage_and_exit() -> ! {
     println!("Usage:");
     println!("  sagacraft_player [<adventure.json>]");
     println!("  sagacraft_player --adventure <path>");
+    println!("  sagacraft_player --list [<dir>]");
+    println!("  sagacraft_player --replay <replay.json>");
     println!();
     println!("Options:");
-    println!("  -a, --adventure <path>    Adventure JSON file to load (default: {})", DEFAULT_ADVENTURE);
+    println!("  -a, --adventure <path>    Adventure JSON file or .saga bundle to load (default: {})", DEFAULT_ADVENTURE);
+    println!("      --adventures-dir <dir> Where to look for adventures not found as given (or ${})", ADVENTURES_DIR_VAR);
+    println!("      --bundle <out.saga>   Package the adventure and its locale file as a bundle, then exit");
+    println!("  -l, --list [<dir>]        List the adventures in a directory (default: .)");
+    println!("      --record <path>       Record inputs and the RNG seed to a replay file");
+    println!("      --replay <path>       Re-run a replay file and report the first divergence");
+    println!("      --transcript <path>   Append each command and its output to a text file as you play");
+    println!("      --new-template <kind> <path>  Write a starter adventure (dungeon, town or mystery), then exit");
+    println!("      --hud                 Show room, exits, health and gold before each prompt");
+    println!("      --prompt <template>   Prompt text; {{room}}, {{health}}, {{gold}} and {{turn}} are filled in each turn");
+    println!("      --typewriter [cps]    Print game text a character at a time (default {} per second); any key skips", DEFAULT_TYPEWRITER_CPS);
+    println!("      --lenient             Skip malformed rooms, items and monsters and list them, instead of failing");
+    println!("      --debug               Allow the systems, enable and disable debugging commands");
+    println!("      --log-level <level>   Engine diagnostics to show: error, warn, info, debug (default: warn)");
     println!("  -h, --help                Show this help");
     std::process::exit(0)
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loop_terminates_on_empty_input() {
+        let mut engine = Engine::new("");
+        run(&mut engine, io::empty(), false, DEFAULT_PROMPT, None, None, None);
+        assert!(!engine.is_over());
+    }
+
+    #[test]
+    fn transcript_records_each_command_with_its_turn_and_output() {
+        let path = std::env::temp_dir().join(format!("sagacraft_transcript_{}.txt", std::process::id()));
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{"title": "Mill Road", "rooms": [
+            {"id": 1, "name": "Crossroads", "exits": {"north": 2}},
+            {"id": 2, "name": "Mill", "exits": {"south": 1}}
+        ]}"#).unwrap();
+
+        let mut transcript = Transcript::open(&path, &engine.game.adventure_title).unwrap();
+        run(&mut engine, io::Cursor::new("north\nhud\nsouth\n"), false, DEFAULT_PROMPT, None, Some(&mut transcript), None);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(text.starts_with("=== Mill Road — "));
+        let lines: Vec<&str> = text.lines().collect();
+        for expected in ["[0] > north", "Mill", "[1] > hud", "HUD on.", "[1] > south", "Crossroads"] {
+            assert!(lines.contains(&expected), "missing {:?} in:\n{}", expected, text);
+        }
+    }
+
+    #[test]
+    fn recorded_session_is_saved_each_turn() {
+        let path = std::env::temp_dir().join(format!("sagacraft_record_{}.json", std::process::id()));
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{"rooms": [
+            {"id": 1, "name": "Crossroads", "exits": {"north": 2}},
+            {"id": 2, "name": "Mill", "exits": {"south": 1}}
+        ]}"#).unwrap();
+        engine.game.start_recording(7);
+
+        run(&mut engine, io::Cursor::new("north\nhud\n"), false, DEFAULT_PROMPT, None, None, path.to_str());
+        let replay = Replay::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(replay.turns.iter().map(|t| t.input.as_str()).collect::<Vec<_>>(), ["north"]);
+        assert!(engine.game.recording_so_far().is_some(), "saving each turn should not stop the recording");
+    }
+
+    #[test]
+    fn bare_adventure_name_resolves_in_the_configured_directory() {
+        let dir = std::env::temp_dir().join(format!("sagacraft_adventures_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("caves.json"), "{}").unwrap();
+
+        let dirs = [dir.clone()];
+        assert_eq!(resolve_adventure_path("caves", &dirs), Ok(dir.join("caves.json")));
+        let tried = resolve_adventure_path("missing", &dirs).unwrap_err();
+        assert_eq!(tried, [PathBuf::from("missing"), dir.join("missing.json"), dir.join("missing")]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn hud_shows_the_current_room_and_its_exits() {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{"rooms": [
+            {"id": 1, "name": "Crossroads", "exits": {"north": 2, "east": 3}},
+            {"id": 2, "name": "Farm"}, {"id": 3, "name": "Mill"}
+        ]}"#).unwrap();
+
+        let [location, vitals] = hud_lines(&engine);
+        assert_eq!(location, "[Crossroads]  Exits: north east");
+        assert!(vitals.starts_with("[HP 12/12]  Gold: 200"));
+    }
+
+    #[test]
+    fn scripted_sessions_ignore_the_typewriter() {
+        let rate = typewriter_rate(Some(1), false);
+        assert_eq!(rate, None);
+
+        let started = std::time::Instant::now();
+        let mut out = Vec::new();
+        print_lines(&mut out, &["The gate creaks open.".to_string(), "A crow calls.".to_string()], rate);
+        assert_eq!(String::from_utf8(out).unwrap(), "The gate creaks open.\nA crow calls.\n");
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn prompt_template_shows_the_current_room() {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{"rooms": [{"id": 1, "name": "Crossroads"}]}"#).unwrap();
+
+        assert_eq!(render_prompt("[{room} {health}hp {gold}g t{turn}] > ", &engine), "[Crossroads 12hp 200g t0] > ");
+        engine.game.player.current_room = 99;
+        assert_eq!(render_prompt("{room}> ", &engine), "> ");
+    }
+}
@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sagacraft_rs::AdventureGame;
+use serde_json::json;
+
+/// A generated world of `rooms` rooms in a ring, with an item and a monster in each.
+fn large_adventure(rooms: i32) -> String {
+    let room = |id: i32| json!({
+        "id": id,
+        "name": format!("Room {id}"),
+        "description": "A featureless stretch of corridor.",
+        "exits": { "north": id % rooms + 1, "south": (id + rooms - 2) % rooms + 1 },
+    });
+    let item = |id: i32| json!({
+        "id": id, "name": format!("Pebble {id}"), "description": "A pebble.",
+        "type": "normal", "weight": 1, "value": 1, "location": id,
+    });
+    let monster = |id: i32| json!({
+        "id": id, "name": format!("Rat {id}"), "description": "A rat.",
+        "room_id": id, "hardiness": 3, "agility": 5, "friendliness": "hostile",
+    });
+    json!({
+        "title": "Benchmark",
+        "start_room": 1,
+        "rooms": (1..=rooms).map(room).collect::<Vec<_>>(),
+        "items": (1..=rooms).map(item).collect::<Vec<_>>(),
+        "monsters": (1..=rooms).map(monster).collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
+fn bench_load(c: &mut Criterion) {
+    let json = large_adventure(5_000);
+    c.bench_function("load 5000-room adventure", |b| {
+        b.iter(|| {
+            let mut game = AdventureGame::new(String::new());
+            game.load_adventure_str(black_box(&json)).unwrap();
+            game
+        })
+    });
+}
+
+criterion_group!(benches, bench_load);
+criterion_main!(benches);
@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::fs;
 use std::path::Path;
 
+use crate::yaml::{self, YamlError, YAML_EXTENSIONS};
+
 #[derive(Debug)]
 pub enum AdventureError {
     Io(std::io::Error),
     Json(serde_json::Error),
+    Yaml(YamlError),
     Validation(String),
 }
 
@@ -15,6 +18,7 @@ impl std::fmt::Display for AdventureError {
         match self {
             AdventureError::Io(e) => write!(f, "io error: {e}"),
             AdventureError::Json(e) => write!(f, "json error: {e}"),
+            AdventureError::Yaml(e) => write!(f, "yaml error: {e}"),
             AdventureError::Validation(msg) => write!(f, "validation error: {msg}"),
         }
     }
@@ -25,6 +29,7 @@ impl std::error::Error for AdventureError {
         match self {
             AdventureError::Io(e) => Some(e),
             AdventureError::Json(e) => Some(e),
+            AdventureError::Yaml(e) => Some(e),
             AdventureError::Validation(_) => None,
         }
     }
@@ -42,6 +47,19 @@ impl From<serde_json::Error> for AdventureError {
     }
 }
 
+impl From<YamlError> for AdventureError {
+    fn from(value: YamlError) -> Self {
+        AdventureError::Yaml(value)
+    }
+}
+
+/// Whether `path` names a YAML file rather than a JSON one.
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| YAML_EXTENSIONS.iter().any(|yaml| ext.eq_ignore_ascii_case(yaml)))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AdventureItem {
     pub id: String,
@@ -49,15 +67,80 @@ pub struct AdventureItem {
     pub description: String,
 }
 
+/// Something barring one of a room's exits until the player uses its key on it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdventureLock {
+    /// What the player calls it: "door", "gate", ...
+    pub name: String,
+    /// Id of the item that unlocks it.
+    pub key: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AdventureRoom {
     pub id: String,
     pub title: String,
     pub description: String,
     #[serde(default)]
-    pub exits: HashMap<String, String>,
+    pub exits: BTreeMap<String, String>,
     #[serde(default)]
     pub items: Vec<AdventureItem>,
+    /// direction -> the lock on that exit.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub locks: BTreeMap<String, AdventureLock>,
+}
+
+/// Starter shapes offered to new authors by [`Adventure::template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// A straight run of rooms from an entrance to a final chamber.
+    Dungeon,
+    /// A town square with shops and houses leading off it.
+    Town,
+    /// A small manor with a locked study and the key hidden elsewhere.
+    Mystery,
+}
+
+impl TemplateKind {
+    pub const ALL: [TemplateKind; 3] = [TemplateKind::Dungeon, TemplateKind::Town, TemplateKind::Mystery];
+
+    /// Lowercase name, as accepted by [`TemplateKind::from_str`](std::str::FromStr).
+    pub fn name(self) -> &'static str {
+        match self {
+            TemplateKind::Dungeon => "dungeon",
+            TemplateKind::Town => "town",
+            TemplateKind::Mystery => "mystery",
+        }
+    }
+}
+
+impl std::str::FromStr for TemplateKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TemplateKind::ALL.into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown template '{}' (expected dungeon, town or mystery)", s))
+    }
+}
+
+fn room(id: &str, title: &str, description: &str, exits: &[(&str, &str)]) -> AdventureRoom {
+    AdventureRoom {
+        id: id.to_string(),
+        title: title.to_string(),
+        description: description.to_string(),
+        exits: exits.iter().map(|&(dir, dest)| (dir.to_string(), dest.to_string())).collect(),
+        items: vec![],
+        locks: BTreeMap::new(),
+    }
+}
+
+fn item(id: &str, name: &str, description: &str) -> AdventureItem {
+    AdventureItem {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -134,9 +217,86 @@ impl Adventure {
             }
         }
 
+        let item_ids: HashSet<&str> = self.rooms.iter()
+            .flat_map(|r| &r.items)
+            .chain(&self.player_start_inventory)
+            .map(|i| i.id.as_str())
+            .collect();
+        for room in &self.rooms {
+            for (dir, lock) in &room.locks {
+                if !room.exits.contains_key(dir) {
+                    return Err(AdventureError::Validation(format!(
+                        "room '{}' locks exit '{}', which it doesn't have",
+                        room.id, dir
+                    )));
+                }
+                if !item_ids.contains(lock.key.as_str()) {
+                    return Err(AdventureError::Validation(format!(
+                        "room '{}' exit '{}' is locked by unknown item '{}'",
+                        room.id, dir, lock.key
+                    )));
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Design problems that don't stop the adventure loading, unlike
+    /// [`Adventure::validate`]: exits with no way back, rooms without exits,
+    /// and rooms that can't be reached from `start_room`.
+    pub fn lint(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let by_id: BTreeMap<&str, &AdventureRoom> = self.rooms.iter().map(|r| (r.id.as_str(), r)).collect();
+        for room in &self.rooms {
+            if room.exits.is_empty() {
+                warnings.push(format!("room '{}' has no exits", room.id));
+            }
+            for (dir, dest) in &room.exits {
+                let returns = by_id.get(dest.as_str()).is_none_or(|d| d.exits.values().any(|back| *back == room.id));
+                if !returns {
+                    warnings.push(format!("room '{}' exit '{}' to '{}' is one-way", room.id, dir, dest));
+                }
+            }
+        }
+
+        let mut reached: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        if by_id.contains_key(self.start_room.as_str()) {
+            reached.insert(&self.start_room);
+            queue.push_back(&self.start_room);
+        }
+        while let Some(id) = queue.pop_front() {
+            for dest in by_id[id].exits.values() {
+                if by_id.contains_key(dest.as_str()) && reached.insert(dest) {
+                    queue.push_back(dest);
+                }
+            }
+        }
+        for room in self.rooms.iter().filter(|r| !reached.contains(r.id.as_str())) {
+            warnings.push(format!("room '{}' can't be reached from '{}'", room.id, self.start_room));
+        }
+        warnings
+    }
+
+    /// Make sure `start_room` names an existing room. If it doesn't, a
+    /// `strict` check fails; otherwise the lowest room id becomes the start
+    /// room and is returned. With no rooms at all, `start_room` is cleared.
+    pub fn ensure_start_room(&mut self, strict: bool) -> Result<Option<String>, AdventureError> {
+        if self.rooms.iter().any(|r| r.id == self.start_room) {
+            return Ok(None);
+        }
+        if strict {
+            return Err(AdventureError::Validation(format!(
+                "start_room does not exist: {}",
+                self.start_room
+            )));
+        }
+        let lowest = self.rooms.iter().map(|r| r.id.clone()).min();
+        self.start_room = lowest.clone().unwrap_or_default();
+        Ok(lowest)
+    }
+
     pub fn load_json_file(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
         let s = fs::read_to_string(path)?;
         let adv: Adventure = serde_json::from_str(&s)?;
@@ -151,11 +311,192 @@ impl Adventure {
         Ok(())
     }
 
+    pub fn load_yaml_file(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        let s = fs::read_to_string(path)?;
+        let adv: Adventure = serde_json::from_value(yaml::from_str(&s)?)?;
+        adv.validate()?;
+        Ok(adv)
+    }
+
+    pub fn save_yaml_file(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        self.validate()?;
+        fs::write(path, yaml::to_string(&serde_json::to_value(self)?))?;
+        Ok(())
+    }
+
+    /// Load `path` as YAML if it ends in `.yaml` or `.yml`, JSON otherwise.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        if is_yaml(path.as_ref()) { Self::load_yaml_file(path) } else { Self::load_json_file(path) }
+    }
+
+    /// Save to `path` as YAML if it ends in `.yaml` or `.yml`, JSON otherwise.
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        if is_yaml(path.as_ref()) { self.save_yaml_file(path) } else { self.save_json_file(path) }
+    }
+
+    /// Append `other`'s rooms and items, renaming each id to `id_prefix`
+    /// followed by the old id (`"hall"` becomes `"cave_hall"` under
+    /// `"cave_"`) and pointing its exits at the renamed rooms. Its starting
+    /// inventory is added to this one's; `self.id`, `title` and `start_room`
+    /// are kept. Nothing links the two yet: add an exit to reach the new rooms.
+    ///
+    /// Fails, leaving `self` unchanged, if a renamed id is already taken.
+    pub fn merge(&mut self, other: Adventure, id_prefix: &str) -> Result<(), AdventureError> {
+        let prefixed = |id: &str| format!("{id_prefix}{id}");
+
+        let room_ids: HashSet<&str> = self.rooms.iter().map(|r| r.id.as_str()).collect();
+        let item_ids: HashSet<&str> = self.rooms.iter()
+            .flat_map(|r| &r.items)
+            .chain(&self.player_start_inventory)
+            .map(|i| i.id.as_str())
+            .collect();
+        if let Some(taken) = other.rooms.iter().map(|r| prefixed(&r.id)).find(|id| room_ids.contains(id.as_str())) {
+            return Err(AdventureError::Validation(format!("merged room id already exists: {taken}")));
+        }
+        let taken = other.rooms.iter()
+            .flat_map(|r| &r.items)
+            .chain(&other.player_start_inventory)
+            .map(|i| prefixed(&i.id))
+            .find(|id| item_ids.contains(id.as_str()));
+        if let Some(taken) = taken {
+            return Err(AdventureError::Validation(format!("merged item id already exists: {taken}")));
+        }
+
+        let rename_item = |item: AdventureItem| AdventureItem { id: prefixed(&item.id), ..item };
+        self.rooms.extend(other.rooms.into_iter().map(|room| AdventureRoom {
+            id: prefixed(&room.id),
+            exits: room.exits.into_iter().map(|(dir, dest)| (dir, prefixed(&dest))).collect(),
+            items: room.items.into_iter().map(rename_item).collect(),
+            locks: room.locks.into_iter()
+                .map(|(dir, lock)| (dir, AdventureLock { key: prefixed(&lock.key), ..lock }))
+                .collect(),
+            ..room
+        }));
+        self.player_start_inventory.extend(other.player_start_inventory.into_iter().map(rename_item));
+        Ok(())
+    }
+
+    /// Starter content for a common adventure shape. Every template validates.
+    pub fn template(kind: TemplateKind) -> Self {
+        match kind {
+            TemplateKind::Dungeon => {
+                let mut rooms = vec![
+                    room("entrance", "Dungeon Entrance", "Worn steps lead down into the dark.", &[("down", "hall")]),
+                    room("hall", "Guard Hall", "Rusted weapon racks line the walls.", &[("up", "entrance"), ("north", "crypt")]),
+                    room("crypt", "Crypt", "Stone coffins, some of them open.", &[("south", "hall"), ("north", "vault")]),
+                    room("vault", "Treasure Vault", "The end of the delve. Something glitters.", &[("south", "crypt")]),
+                ];
+                rooms[3].items.push(item("treasure", "Jeweled Crown", "The prize at the bottom of the dungeon."));
+                Self {
+                    id: "dungeon".to_string(),
+                    title: "Dungeon Crawl".to_string(),
+                    start_room: "entrance".to_string(),
+                    rooms,
+                    player_start_inventory: vec![item("torch", "Torch", "A pitch-soaked torch.")],
+                }
+            }
+            TemplateKind::Town => Self {
+                id: "town".to_string(),
+                title: "Market Town".to_string(),
+                start_room: "square".to_string(),
+                rooms: vec![
+                    room("square", "Town Square", "A fountain splashes at the heart of town.", &[
+                        ("north", "inn"), ("east", "smithy"), ("south", "gate"), ("west", "market"),
+                    ]),
+                    room("inn", "The Sleeping Fox", "A warm inn full of gossip.", &[("south", "square")]),
+                    room("smithy", "Smithy", "The forge roars; the smith nods at you.", &[("west", "square")]),
+                    room("market", "Market", "Stalls of every kind.", &[("east", "square")]),
+                    room("gate", "Town Gate", "The road leads away from town.", &[("north", "square")]),
+                ],
+                player_start_inventory: vec![],
+            },
+            TemplateKind::Mystery => {
+                let mut rooms = vec![
+                    room("foyer", "Manor Foyer", "Dust sheets cover the furniture.", &[("north", "hallway")]),
+                    room("hallway", "Hallway", "Portraits watch you pass. The study door is locked.", &[
+                        ("south", "foyer"), ("east", "library"), ("north", "study"),
+                    ]),
+                    room("library", "Library", "Shelves of unread books.", &[("west", "hallway")]),
+                    room("study", "Locked Study", "Where the mystery is solved.", &[("south", "hallway")]),
+                ];
+                rooms[1].locks.insert("north".to_string(), AdventureLock {
+                    name: "study door".to_string(),
+                    key: "study_key".to_string(),
+                });
+                rooms[2].items.push(item("study_key", "Study Key", "A small key tucked inside a hollow book."));
+                rooms[3].items.push(item("letter", "Sealed Letter", "The answer to the mystery."));
+                Self {
+                    id: "mystery".to_string(),
+                    title: "Manor Mystery".to_string(),
+                    start_room: "foyer".to_string(),
+                    rooms,
+                    player_start_inventory: vec![],
+                }
+            }
+        }
+    }
+
+    /// The adventure in the engine's file format (numeric ids, as read by
+    /// [`crate::AdventureGame::load_adventure`]). Rooms are numbered from 1
+    /// in order, then room items and the starting inventory from 1. Locks
+    /// become barriers keyed to their item.
+    pub fn to_engine_json(&self) -> serde_json::Value {
+        let room_ids: BTreeMap<&str, usize> = self.rooms.iter()
+            .enumerate()
+            .map(|(i, room)| (room.id.as_str(), i + 1))
+            .collect();
+        let placed: Vec<(&AdventureItem, usize)> = self.rooms.iter()
+            .flat_map(|room| room.items.iter().map(|item| (item, room_ids[room.id.as_str()])))
+            .chain(self.player_start_inventory.iter().map(|item| (item, 0)))
+            .collect();
+        let mut item_ids: BTreeMap<&str, usize> = BTreeMap::new();
+        for (i, (item, _)) in placed.iter().enumerate() {
+            item_ids.entry(item.id.as_str()).or_insert(i + 1);
+        }
+        let rooms: Vec<serde_json::Value> = self.rooms.iter().map(|room| serde_json::json!({
+            "id": room_ids[room.id.as_str()],
+            "name": room.title,
+            "description": room.description,
+            "exits": room.exits.iter()
+                .filter_map(|(dir, dest)| room_ids.get(dest.as_str()).map(|id| (dir.clone(), *id)))
+                .collect::<BTreeMap<String, usize>>(),
+            "barriers": room.locks.iter()
+                .filter_map(|(dir, lock)| item_ids.get(lock.key.as_str())
+                    .map(|key| (dir.clone(), serde_json::json!({"name": lock.name, "key": key}))))
+                .collect::<BTreeMap<String, serde_json::Value>>(),
+        })).collect();
+        let items: Vec<serde_json::Value> = placed.into_iter().enumerate().map(|(i, (item, location))| serde_json::json!({
+            "id": i + 1,
+            "name": item.name,
+            "description": item.description,
+            "type": "normal",
+            "value": 0,
+            "weight": 1,
+            "location": location,
+        })).collect();
+        serde_json::json!({
+            "title": self.title,
+            "intro": "",
+            "start_room": room_ids.get(self.start_room.as_str()).copied().unwrap_or(1),
+            "rooms": rooms,
+            "items": items,
+            "monsters": [],
+            "quests": [],
+        })
+    }
+
+    /// Write [`Adventure::to_engine_json`] to `path`, for the player to load.
+    pub fn save_engine_json_file(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        self.validate()?;
+        fs::write(path, serde_json::to_string_pretty(&self.to_engine_json())?)?;
+        Ok(())
+    }
+
     pub fn demo() -> Self {
-        let mut village_exits = HashMap::new();
+        let mut village_exits = BTreeMap::new();
         village_exits.insert("north".to_string(), "forest".to_string());
 
-        let mut forest_exits = HashMap::new();
+        let mut forest_exits = BTreeMap::new();
         forest_exits.insert("south".to_string(), "village".to_string());
 
         Self {
@@ -175,6 +516,7 @@ impl Adventure {
                         name: "Ancient Key".to_string(),
                         description: "A tarnished key that seems to hum faintly.".to_string(),
                     }],
+                    locks: BTreeMap::new(),
                 },
                 AdventureRoom {
                     id: "forest".to_string(),
@@ -183,6 +525,7 @@ impl Adventure {
                         .to_string(),
                     exits: forest_exits,
                     items: vec![],
+                    locks: BTreeMap::new(),
                 },
             ],
             player_start_inventory: vec![],
@@ -193,12 +536,121 @@ impl Adventure {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game_state::AdventureGame;
 
     #[test]
     fn demo_validates() {
         Adventure::demo().validate().unwrap();
     }
 
+    #[test]
+    fn lint_flags_one_way_exits_and_unreachable_rooms() {
+        assert!(Adventure::demo().lint().is_empty(), "{:?}", Adventure::demo().lint());
+
+        let mut adventure = Adventure {
+            id: "lint".to_string(),
+            title: "Lint".to_string(),
+            start_room: "hall".to_string(),
+            rooms: vec![
+                room("hall", "Hall", "", &[("down", "pit")]),
+                room("pit", "Pit", "", &[]),
+                room("attic", "Attic", "", &[("down", "hall")]),
+            ],
+            player_start_inventory: vec![],
+        };
+        adventure.validate().unwrap();
+        assert_eq!(adventure.lint(), [
+            "room 'hall' exit 'down' to 'pit' is one-way",
+            "room 'pit' has no exits",
+            "room 'attic' exit 'down' to 'hall' is one-way",
+            "room 'attic' can't be reached from 'hall'",
+        ]);
+
+        adventure.rooms[0].exits.insert("up".to_string(), "attic".to_string());
+        adventure.rooms[1].exits.insert("up".to_string(), "hall".to_string());
+        assert!(adventure.lint().is_empty(), "{:?}", adventure.lint());
+    }
+
+    #[test]
+    fn demo_round_trips_through_yaml() {
+        let path = std::env::temp_dir().join(format!("sagacraft_demo_{}.yml", std::process::id()));
+        let demo = Adventure::demo();
+        demo.save_file(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let loaded = Adventure::load_file(&path);
+        let _ = fs::remove_file(&path);
+        assert!(text.starts_with("id: demo"), "{text}");
+        assert_eq!(loaded.unwrap(), demo);
+    }
+
+    #[test]
+    fn every_template_validates_and_loads() {
+        for kind in TemplateKind::ALL {
+            let adventure = Adventure::template(kind);
+            adventure.validate().unwrap_or_else(|err| panic!("{}: {}", kind.name(), err));
+            assert_eq!(kind.name().parse::<TemplateKind>(), Ok(kind));
+
+            let mut game = AdventureGame::new(String::new());
+            game.load_adventure_str(&adventure.to_engine_json().to_string()).unwrap();
+            assert_eq!(game.rooms.len(), adventure.rooms.len());
+            assert!(game.load_warnings.is_empty(), "{}: {:?}", kind.name(), game.load_warnings);
+        }
+    }
+
+    #[test]
+    fn mystery_study_stays_locked_until_the_key_is_used() {
+        let adventure = Adventure::template(TemplateKind::Mystery);
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(&adventure.to_engine_json().to_string()).unwrap();
+        game.add_system(Box::new(crate::systems::BasicWorldSystem));
+        game.add_system(Box::new(crate::systems::InventorySystem));
+
+        game.process_command("north");
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 2);
+        game.process_command("east");
+        game.process_command("take study key");
+        game.process_command("west");
+        game.process_command("use study key on study door");
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 4);
+    }
+
+    #[test]
+    fn demo_merges_into_itself_under_a_prefix() {
+        let mut adventure = Adventure::demo();
+        adventure.merge(Adventure::demo(), "copy_").unwrap();
+        adventure.validate().unwrap();
+
+        let demo = Adventure::demo();
+        assert_eq!(adventure.rooms.len(), demo.rooms.len() * 2);
+        assert_eq!(adventure.start_room, demo.start_room);
+        let copy = adventure.rooms.iter().find(|r| r.id == format!("copy_{}", demo.rooms[0].id)).unwrap();
+        assert!(copy.exits.values().all(|dest| dest.starts_with("copy_")));
+
+        let err = adventure.merge(Adventure::demo(), "copy_").unwrap_err();
+        assert!(err.to_string().contains("merged room id already exists: copy_"), "{err}");
+        assert_eq!(adventure.rooms.len(), demo.rooms.len() * 2);
+    }
+
+    #[test]
+    fn serialization_is_byte_identical_across_instances() {
+        let first = serde_json::to_string_pretty(&Adventure::demo()).unwrap();
+        let second = serde_json::to_string_pretty(&Adventure::demo()).unwrap();
+        assert_eq!(first, second);
+
+        let room_json = || {
+            let mut game = AdventureGame::new(String::new());
+            game.load_adventure_str(r#"{"rooms": [
+                {"id": 1, "exits": {"north": 2, "east": 3, "south": 4, "west": 5}},
+                {"id": 2}, {"id": 3}, {"id": 4}, {"id": 5}
+            ]}"#).unwrap();
+            serde_json::to_string(&game.rooms[&1]).unwrap()
+        };
+        assert_eq!(room_json(), room_json());
+        assert!(room_json().contains(r#""exits":{"east":3,"north":2,"south":4,"west":5}"#));
+    }
+
     #[test]
     fn validate_requires_start_room() {
         let mut adv = Adventure::demo();
@@ -0,0 +1,182 @@
+//! `.saga` bundles: an adventure, the mods layered on it, its locale strings
+//! and default settings in one JSON file, for sharing a playable adventure.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::adventure::AdventureError;
+use crate::game_state::AdventureGame;
+use crate::settings::GameSettings;
+
+/// File extension that marks a bundle rather than a plain adventure file.
+pub const BUNDLE_EXTENSION: &str = "saga";
+
+/// Bumped when the bundle layout changes incompatibly.
+const BUNDLE_FORMAT: u32 = 1;
+
+fn default_format() -> u32 { BUNDLE_FORMAT }
+
+/// A named fragment of adventure JSON layered over the base adventure.
+/// Arrays (`rooms`, `items`, ...) are merged by `id`: an entry with an
+/// existing id replaces it, others are appended. Other keys overwrite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub content: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    #[serde(default = "default_format")]
+    pub format: u32,
+    /// The adventure file exactly as authored.
+    pub adventure: Value,
+    /// Applied in order on top of `adventure`.
+    #[serde(default)]
+    pub mods: Vec<ModDefinition>,
+    /// Message overrides, replacing the adventure's locale file.
+    #[serde(default)]
+    pub locale: BTreeMap<String, String>,
+    /// Replaces the adventure's own `settings` when present.
+    #[serde(default)]
+    pub settings: Option<GameSettings>,
+}
+
+impl Bundle {
+    /// Bundle the adventure at `path`, embedding its locale file if it names one.
+    pub fn from_adventure_file(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        let path = path.as_ref();
+        let adventure: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let locale = match adventure.pointer("/settings/locale").and_then(Value::as_str) {
+            Some(file) => {
+                let base = path.parent().unwrap_or(Path::new(""));
+                serde_json::from_str(&fs::read_to_string(base.join(file))?)?
+            }
+            None => BTreeMap::new(),
+        };
+        Ok(Self { format: BUNDLE_FORMAT, adventure, mods: Vec::new(), locale, settings: None })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        let bundle: Bundle = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if bundle.format > BUNDLE_FORMAT {
+            return Err(AdventureError::Validation(format!(
+                "bundle format {} is newer than this engine supports ({})",
+                bundle.format, BUNDLE_FORMAT
+            )));
+        }
+        Ok(bundle)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The adventure JSON with every mod and the bundled settings applied.
+    /// The locale file reference is dropped; [`Bundle::locale`] replaces it.
+    pub fn merged_adventure(&self) -> Value {
+        let mut adventure = self.adventure.clone();
+        for m in &self.mods {
+            merge(&mut adventure, &m.content);
+        }
+        if let Some(obj) = adventure.as_object_mut() {
+            if let Some(settings) = &self.settings {
+                obj.insert("settings".to_string(), serde_json::to_value(settings).unwrap_or_default());
+            }
+            if let Some(settings) = obj.get_mut("settings").and_then(Value::as_object_mut) {
+                settings.remove("locale");
+            }
+        }
+        adventure
+    }
+}
+
+/// Layer `overlay` onto `base`; see [`ModDefinition`] for the rules.
+fn merge(base: &mut Value, overlay: &Value) {
+    let (Some(base), Some(overlay)) = (base.as_object_mut(), overlay.as_object()) else {
+        return;
+    };
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(Value::Array(existing)), Value::Array(additions)) => {
+                for entry in additions {
+                    let id = entry.get("id");
+                    match existing.iter_mut().find(|e| id.is_some() && e.get("id") == id) {
+                        Some(slot) => *slot = entry.clone(),
+                        None => existing.push(entry.clone()),
+                    }
+                }
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+impl AdventureGame {
+    /// Load a `.saga` bundle: the merged adventure, then its locale strings.
+    /// Returns the opening banner like [`AdventureGame::load_adventure`].
+    pub fn load_bundle(&mut self, path: impl AsRef<Path>) -> Result<String, Box<dyn std::error::Error>> {
+        self.load_bundle_with(path, false)
+    }
+
+    pub(crate) fn load_bundle_with(&mut self, path: impl AsRef<Path>, lenient: bool) -> Result<String, Box<dyn std::error::Error>> {
+        let bundle = Bundle::load(path)?;
+        let banner = self.load_json(&bundle.merged_adventure().to_string(), lenient)?;
+        for (key, text) in bundle.locale {
+            self.message_table.set(key, text);
+        }
+        Ok(banner)
+    }
+
+    /// Write the adventure this game was loaded from, with its locale file,
+    /// as a bundle at `path`.
+    pub fn save_bundle(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        Bundle::from_adventure_file(&self.adventure_file)?.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_with_a_mod_round_trips_and_loads() {
+        let path = std::env::temp_dir().join(format!("sagacraft_bundle_{}.saga", std::process::id()));
+        let bundle = Bundle {
+            format: BUNDLE_FORMAT,
+            adventure: serde_json::json!({
+                "title": "Keep",
+                "rooms": [{"id": 1, "name": "Gate", "exits": {}}]
+            }),
+            mods: vec![ModDefinition {
+                name: "Cellar".to_string(),
+                description: String::new(),
+                content: serde_json::json!({
+                    "rooms": [
+                        {"id": 1, "name": "Gate", "exits": {"down": 2}},
+                        {"id": 2, "name": "Cellar", "exits": {"up": 1}}
+                    ]
+                }),
+            }],
+            locale: BTreeMap::from([("cant_go".to_string(), "The way is shut.".to_string())]),
+            settings: None,
+        };
+        bundle.save(&path).unwrap();
+        assert_eq!(Bundle::load(&path).unwrap(), bundle);
+
+        let mut game = AdventureGame::new(path.to_string_lossy().into_owned());
+        game.load_adventure().unwrap();
+        assert_eq!(game.rooms.len(), 2);
+        assert_eq!(game.rooms[&1].exits.get("down"), Some(&2));
+        assert_eq!(game.messages("cant_go", &[]), "The way is shut.");
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::adventure::AdventureError;
+use crate::bundle::{Bundle, BUNDLE_EXTENSION};
+use crate::raw::{strip_json5, JSON5_EXTENSION};
+
+/// Descriptive header of an adventure file. Every field is optional in the
+/// JSON; only `title` is needed for a file to count as an adventure.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdventureMeta {
+    /// File the metadata was read from. Filled in by [`read_meta`].
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub title: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub recommended_level: Option<i32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Read just the metadata header of the adventure at `path`. Rooms, items and
+/// the rest of the file are skipped rather than built into a game.
+pub fn read_meta(path: impl AsRef<Path>) -> Result<AdventureMeta, AdventureError> {
+    let path = path.as_ref();
+    let mut meta: AdventureMeta = if path.extension().is_some_and(|ext| ext == BUNDLE_EXTENSION) {
+        serde_json::from_value(Bundle::load(path)?.adventure)?
+    } else if path.extension().is_some_and(|ext| ext == JSON5_EXTENSION) {
+        serde_json::from_str(&strip_json5(&fs::read_to_string(path)?))?
+    } else {
+        serde_json::from_str(&fs::read_to_string(path)?)?
+    };
+    meta.path = path.to_path_buf();
+    Ok(meta)
+}
+
+/// Metadata of every adventure and bundle in `dir`, sorted by title. JSON
+/// files that aren't adventures (saves, the hall of fame, ...) are left out.
+pub fn catalog(dir: impl AsRef<Path>) -> Result<Vec<AdventureMeta>, AdventureError> {
+    let mut adventures = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json" || ext == JSON5_EXTENSION || ext == BUNDLE_EXTENSION)
+            && let Ok(meta) = read_meta(&path)
+        {
+            adventures.push(meta);
+        }
+    }
+    adventures.sort_by(|a, b| a.title.cmp(&b.title));
+    Ok(adventures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_reports_the_demo_adventure() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let adventures = catalog(&root).unwrap();
+        let demo = adventures.iter()
+            .find(|m| m.path.ends_with("shattered_realms_demo.json"))
+            .expect("demo adventure listed");
+        assert_eq!(demo.title, "The Shattered Realms: Chronicles of the Eternal Flame");
+        assert_eq!(demo.author.as_deref(), Some("SagaCraft Team"));
+        assert!(demo.tags.contains(&"fantasy".to_string()));
+    }
+
+    #[test]
+    fn every_shipped_adventure_loads() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let adventures = catalog(&root).unwrap();
+        assert!(adventures.iter().any(|m| m.path.ends_with("demo_adventure.json")));
+        for adventure in adventures {
+            let mut engine = crate::engine::Engine::new(adventure.path.to_string_lossy());
+            engine.start().unwrap_or_else(|err| panic!("{}: {}", adventure.path.display(), err));
+            assert!(!engine.game.rooms.is_empty(), "{}", adventure.path.display());
+            assert!(engine.game.load_warnings.is_empty(), "{}: {:?}", adventure.path.display(), engine.game.load_warnings);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! The player command grammar. Each system declares the commands it handles
+//! as a table of [`CommandSpec`]s and dispatches through [`resolve`], so the
+//! table is the single source for parsing, `help` and autocompletion.
+
+use serde::Serialize;
+
+/// What a command expects after the verb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgShape {
+    None,
+    /// A required name, e.g. `take <item>`; the string is the placeholder.
+    Target(&'static str),
+    /// An optional name, e.g. `attack [monster]`.
+    OptionalTarget(&'static str),
+    Direction,
+    OptionalDirection,
+    /// Free text, e.g. `say <text>`.
+    Text,
+    /// Two names joined by a word, e.g. `combine <item> with <item>`.
+    Pair(&'static str, &'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CommandSpec {
+    pub verb: &'static str,
+    pub aliases: &'static [&'static str],
+    pub args: ArgShape,
+    pub help: &'static str,
+    /// Only available, and only listed, when `gameplay.debug_commands` is on.
+    pub debug: bool,
+}
+
+impl CommandSpec {
+    pub const fn new(verb: &'static str, aliases: &'static [&'static str], args: ArgShape, help: &'static str) -> Self {
+        Self { verb, aliases, args, help, debug: false }
+    }
+
+    /// The same command, marked as a debugging aid; see [`CommandSpec::debug`].
+    pub const fn debug_only(self) -> Self {
+        Self { debug: true, ..self }
+    }
+
+    /// Whether `word` is this command's verb or one of its aliases.
+    pub fn matches(&self, word: &str) -> bool {
+        self.verb == word || self.aliases.contains(&word)
+    }
+
+    /// The verb, aliases and argument placeholder, e.g. `take / get <item>`.
+    pub fn usage(&self) -> String {
+        let mut usage = std::iter::once(self.verb)
+            .chain(self.aliases.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        match self.args {
+            ArgShape::None => {}
+            ArgShape::Target(name) => usage.push_str(&format!(" <{}>", name)),
+            ArgShape::OptionalTarget(name) => usage.push_str(&format!(" [{}]", name)),
+            ArgShape::Direction => usage.push_str(" <direction>"),
+            ArgShape::OptionalDirection => usage.push_str(" [direction]"),
+            ArgShape::Text => usage.push_str(" <text>"),
+            ArgShape::Pair(name, joiner) => usage.push_str(&format!(" <{name}> {joiner} <{name}>")),
+        }
+        usage
+    }
+}
+
+/// The canonical verb `word` stands for in `specs`, if any.
+pub fn resolve(specs: &[CommandSpec], word: &str) -> Option<&'static str> {
+    specs.iter().find(|spec| spec.matches(word)).map(|spec| spec.verb)
+}
+
+/// Render `specs` as the `help` listing.
+pub fn help_text(specs: &[CommandSpec]) -> String {
+    let width = specs.iter().map(|spec| spec.usage().len()).max().unwrap_or(0) + 2;
+    let mut lines = vec!["Commands:".to_string()];
+    lines.extend(specs.iter().map(|spec| format!("  {:<width$}{}", spec.usage(), spec.help)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+
+    #[test]
+    fn every_verb_in_the_grammar_is_handled() {
+        let mut engine = Engine::new("");
+        let words: Vec<&str> = engine.game.grammar().iter()
+            .flat_map(|spec| std::iter::once(spec.verb).chain(spec.aliases.iter().copied()))
+            .collect();
+        assert!(words.contains(&"look") && words.contains(&"x"));
+
+        let unknown = engine.game.messages("unknown_command", &[("command", "")]);
+        for word in words {
+            let out = engine.send(word).join("\n");
+            assert!(!out.starts_with(&unknown), "'{}' is in the grammar but not handled", word);
+        }
+        assert!(engine.send("dance").join("\n").starts_with(&unknown));
+    }
+}
@@ -0,0 +1,55 @@
+//! The directions exits are named by, in the order every front-end lists them.
+
+use std::cmp::Ordering;
+
+/// Canonical exit order: the compass points, the diagonals, then up/down and
+/// in/out. Exits with other names sort after these, alphabetically.
+pub const DIRECTION_ORDER: &[&str] = &[
+    "north", "south", "east", "west",
+    "northeast", "northwest", "southeast", "southwest",
+    "up", "down", "in", "out",
+];
+
+/// The direction that leads back through an exit taken in `dir`.
+pub fn canonical_reverse(dir: &str) -> Option<&'static str> {
+    Some(match dir {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        "west" => "east",
+        "northeast" => "southwest",
+        "southwest" => "northeast",
+        "northwest" => "southeast",
+        "southeast" => "northwest",
+        "up" => "down",
+        "down" => "up",
+        "in" => "out",
+        "out" => "in",
+        _ => return None,
+    })
+}
+
+/// Order two direction names by [`DIRECTION_ORDER`], for sorting exits.
+pub fn direction_cmp(a: &str, b: &str) -> Ordering {
+    let rank = |dir: &str| DIRECTION_ORDER.iter().position(|&d| d == dir).unwrap_or(DIRECTION_ORDER.len());
+    rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_direction_reverses_and_sorts_canonically() {
+        for dir in DIRECTION_ORDER {
+            let back = canonical_reverse(dir).unwrap();
+            assert!(DIRECTION_ORDER.contains(&back));
+            assert_eq!(canonical_reverse(back), Some(*dir));
+        }
+        assert_eq!(canonical_reverse("sideways"), None);
+
+        let mut dirs = vec!["out", "portal", "down", "west", "ladder", "north", "southeast", "east"];
+        dirs.sort_by(|a, b| direction_cmp(a, b));
+        assert_eq!(dirs, ["north", "east", "west", "southeast", "down", "out", "ladder", "portal"]);
+    }
+}
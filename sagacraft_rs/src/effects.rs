@@ -0,0 +1,82 @@
+//! Scripted room events ("effects") declared in the adventure JSON's
+//! `"effects"` array and run by [`crate::systems::EffectSystem`].
+//!
+//! ```json
+//! {
+//!   "trigger": { "on_enter_room": 3 },
+//!   "condition": { "not_flag": "bell_rung", "agility_check": 12 },
+//!   "actions": [{ "print": "A bell tolls in the distance." }, { "set_flag": "bell_rung" }],
+//!   "once": true
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_state::{AdventureGame, Stat};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// The player enters the room with this id.
+    OnEnterRoom(i32),
+    /// The player picks up the item with this id.
+    OnTakeItem(i32),
+    /// Every turn that passes.
+    OnTurn,
+}
+
+/// Extra requirements for an effect to fire. Every field that is set must hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Condition {
+    pub flag: Option<String>,
+    pub not_flag: Option<String>,
+    /// The player is in this room.
+    pub room: Option<i32>,
+    /// The player carries this item.
+    pub has_item: Option<i32>,
+    /// The player passes an [`AdventureGame::ability_check`] of this difficulty.
+    pub hardiness_check: Option<i32>,
+    pub agility_check: Option<i32>,
+    pub charisma_check: Option<i32>,
+}
+
+impl Condition {
+    /// Checks are only rolled once every other requirement holds, so an
+    /// effect that can't fire anyway doesn't consume random numbers.
+    pub fn holds(&self, game: &mut AdventureGame) -> bool {
+        self.flag.as_ref().is_none_or(|f| game.flags.contains(f))
+            && self.not_flag.as_ref().is_none_or(|f| !game.flags.contains(f))
+            && self.room.is_none_or(|r| game.player.current_room == r)
+            && self.has_item.is_none_or(|i| game.player.inventory.contains(&i))
+            && self.hardiness_check.is_none_or(|dc| game.ability_check(Stat::Hardiness, dc))
+            && self.agility_check.is_none_or(|dc| game.ability_check(Stat::Agility, dc))
+            && self.charisma_check.is_none_or(|dc| game.ability_check(Stat::Charisma, dc))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Show a line of text.
+    Print(String),
+    SetFlag(String),
+    ClearFlag(String),
+    /// Bring a monster (back) to life in a room; the player's room if `room` is absent.
+    Spawn { monster: i32, room: Option<i32> },
+    /// Move the player to another room.
+    Teleport(i32),
+    /// Hurt the player.
+    Damage(i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Effect {
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub condition: Condition,
+    pub actions: Vec<Action>,
+    /// Fire only the first time the trigger and condition match.
+    #[serde(default)]
+    pub once: bool,
+}
@@ -1,8 +1,8 @@
 use crate::game_state::AdventureGame;
-use crate::systems::{BasicWorldSystem, CombatSystem, InventorySystem};
+use crate::systems::{AutosaveSystem, BasicWorldSystem, ClockSystem, CombatSystem, DialogueSystem, EffectSystem, InventorySystem, MetaSystem, ScoreSystem};
 use crate::systems::quests::QuestSystem;
 
-/// High-level convenience wrapper that creates an `AdventureGame` with all four
+/// High-level convenience wrapper that creates an `AdventureGame` with all the
 /// built-in systems pre-registered.
 ///
 /// # Example
@@ -28,7 +28,13 @@ impl Engine {
         game.add_system(Box::new(BasicWorldSystem));
         game.add_system(Box::new(InventorySystem));
         game.add_system(Box::new(CombatSystem));
+        game.add_system(Box::new(DialogueSystem));
         game.add_system(Box::new(QuestSystem::new()));
+        game.add_system(Box::new(ScoreSystem));
+        game.add_system(Box::new(AutosaveSystem));
+        game.add_system(Box::new(ClockSystem::default()));
+        game.add_system(Box::new(EffectSystem));
+        game.add_system(Box::new(MetaSystem));
         Self { game, intro_text: String::new() }
     }
 
@@ -39,6 +45,14 @@ impl Engine {
         Ok(intro)
     }
 
+    /// Like [`Engine::start`], but malformed rooms, items and monsters are
+    /// skipped; see [`AdventureGame::load_lenient`].
+    pub fn start_lenient(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        let intro = self.game.load_lenient()?;
+        self.intro_text = intro.clone();
+        Ok(intro)
+    }
+
     /// Create an `Engine`, load the adventure from disk, and return it.
     /// Combines [`Engine::new`] and [`Engine::start`].
     pub fn load(adventure_path: impl Into<String>) -> Result<Self, Box<dyn std::error::Error>> {
@@ -0,0 +1,217 @@
+//! A bounded record of what happened during play, for analytics, tests and
+//! systems that look back over recent turns. Unlike [`crate::GameEvent`],
+//! which systems react to once and then discard, entries stay until the log
+//! fills up. Per-kind counts cover every event ever logged, so event
+//! frequencies survive the oldest entries being dropped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Entries kept before the oldest are dropped, unless
+/// `gameplay.event_log_capacity` says otherwise.
+pub const EVENT_LOG_CAPACITY: usize = 256;
+
+/// Something that happened, by id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoggedEvent {
+    Moved { from: i32, to: i32 },
+    Took { item_id: i32 },
+    Dropped { item_id: i32 },
+    Attacked { monster_id: i32, damage: i32 },
+    Killed { monster_id: i32 },
+    Looted { monster_id: i32, gold: i32 },
+    QuestProgressed { quest_id: String, current: i32, required: i32 },
+}
+
+impl LoggedEvent {
+    /// The event's kind, as written in its `type` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoggedEvent::Moved { .. } => "moved",
+            LoggedEvent::Took { .. } => "took",
+            LoggedEvent::Dropped { .. } => "dropped",
+            LoggedEvent::Attacked { .. } => "attacked",
+            LoggedEvent::Killed { .. } => "killed",
+            LoggedEvent::Looted { .. } => "looted",
+            LoggedEvent::QuestProgressed { .. } => "quest_progressed",
+        }
+    }
+}
+
+/// Whether the event kind `name` matches `pattern`: `*` matches every kind,
+/// a trailing `*` matches kinds starting with what comes before it
+/// (`quest_*`), and anything else must match exactly.
+pub fn kind_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// An event and the turn it happened on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub turn: i32,
+    pub event: LoggedEvent,
+}
+
+/// Ring buffer of the last `capacity` entries, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+    #[serde(default = "default_capacity")]
+    capacity: usize,
+    /// event kind -> times logged, including dropped entries.
+    #[serde(default)]
+    counts: BTreeMap<String, usize>,
+}
+
+fn default_capacity() -> usize {
+    EVENT_LOG_CAPACITY
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::with_capacity(EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    /// An empty log keeping at most `capacity` entries (at least one).
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: VecDeque::new(), capacity: capacity.max(1), counts: BTreeMap::new() }
+    }
+
+    /// Change how many entries are kept, dropping the oldest if there are
+    /// now too many.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn push(&mut self, turn: i32, event: LoggedEvent) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        *self.counts.entry(event.name().to_string()).or_insert(0) += 1;
+        self.entries.push_back(LogEntry { turn, event });
+    }
+
+    /// The last `n` entries, oldest first.
+    pub fn recent(&self, n: usize) -> Vec<&LogEntry> {
+        self.entries.iter().skip(self.entries.len().saturating_sub(n)).collect()
+    }
+
+    /// The last `limit` retained entries whose kind (see
+    /// [`LoggedEvent::name`]) matches `pattern`, oldest first. See
+    /// [`kind_matches`] for the patterns.
+    pub fn history(&self, pattern: &str, limit: usize) -> Vec<&LogEntry> {
+        let matching: Vec<&LogEntry> = self.entries.iter()
+            .filter(|entry| kind_matches(pattern, entry.event.name()))
+            .collect();
+        matching[matching.len().saturating_sub(limit)..].to_vec()
+    }
+
+    /// How many events of each kind have been logged, including any since
+    /// dropped from the buffer.
+    pub fn stats(&self) -> &BTreeMap<String, usize> {
+        &self.counts
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+    use crate::game_state::{Item, ItemType, Monster, MonsterStatus, Room};
+
+    #[test]
+    fn short_session_is_logged_in_order() {
+        let mut game = Engine::new("").game;
+        let mut hall = Room::new(1, "Hall".to_string(), String::new());
+        hall.exits.insert("north".to_string(), 2);
+        game.rooms.insert(1, hall);
+        game.rooms.insert(2, Room::new(2, "Den".to_string(), String::new()));
+        game.player.current_room = 1;
+        let mut coin = Item::new(1, "Coin".to_string(), String::new(), ItemType::Treasure, 1, 1);
+        coin.location = 1;
+        game.items.insert(1, coin);
+        let mut rat = Monster::new(1, "Rat".to_string(), String::new(), 2, 1, 1, MonsterStatus::Hostile, 100);
+        rat.gold = 3;
+        game.monsters.insert(1, rat);
+        game.seed_rng(1);
+
+        for input in ["take coin", "north", "drop coin", "attack rat"] {
+            game.process_command(input);
+        }
+
+        let events: Vec<(i32, LoggedEvent)> = game.recent_events(10).into_iter()
+            .map(|entry| (entry.turn, entry.event.clone()))
+            .collect();
+        assert_eq!(events, [
+            (0, LoggedEvent::Took { item_id: 1 }),
+            (1, LoggedEvent::Moved { from: 1, to: 2 }),
+            (2, LoggedEvent::Dropped { item_id: 1 }),
+            (3, LoggedEvent::Attacked { monster_id: 1, damage: 5 }),
+            (3, LoggedEvent::Killed { monster_id: 1 }),
+            (3, LoggedEvent::Looted { monster_id: 1, gold: 3 }),
+        ]);
+        assert_eq!(game.recent_events(1)[0].event, LoggedEvent::Looted { monster_id: 1, gold: 3 });
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped_when_full() {
+        let mut log = EventLog::default();
+        for turn in 0..EVENT_LOG_CAPACITY as i32 + 5 {
+            log.push(turn, LoggedEvent::Took { item_id: turn });
+        }
+        assert_eq!(log.len(), EVENT_LOG_CAPACITY);
+        assert_eq!(log.recent(EVENT_LOG_CAPACITY)[0].turn, 5);
+    }
+
+    #[test]
+    fn stats_count_events_the_buffer_has_dropped() {
+        let mut log = EventLog::with_capacity(3);
+        for turn in 0..4 {
+            log.push(turn, LoggedEvent::Took { item_id: turn });
+        }
+        log.push(4, LoggedEvent::Moved { from: 1, to: 2 });
+
+        let turns: Vec<i32> = log.recent(10).iter().map(|entry| entry.turn).collect();
+        assert_eq!(turns, [2, 3, 4]);
+        assert_eq!(log.stats().get("took"), Some(&4));
+        assert_eq!(log.stats().get("moved"), Some(&1));
+        let took: Vec<i32> = log.history("took", 1).iter().map(|entry| entry.turn).collect();
+        assert_eq!(took, [3]);
+        assert_eq!(log.history("took", 10).len(), 2);
+    }
+
+    #[test]
+    fn history_patterns_match_by_prefix() {
+        let mut log = EventLog::default();
+        log.push(0, LoggedEvent::QuestProgressed { quest_id: "rats".to_string(), current: 1, required: 3 });
+        log.push(1, LoggedEvent::Took { item_id: 1 });
+        log.push(2, LoggedEvent::QuestProgressed { quest_id: "rats".to_string(), current: 2, required: 3 });
+
+        let quests: Vec<i32> = log.history("quest_*", 10).iter().map(|entry| entry.turn).collect();
+        assert_eq!(quests, [0, 2]);
+        assert_eq!(log.history("*", 10).len(), 3);
+        assert!(log.history("quest", 10).is_empty());
+        assert!(!kind_matches("quest_*", "took"));
+    }
+}
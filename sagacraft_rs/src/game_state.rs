@@ -1,13 +1,74 @@
+use crate::adventure::{Adventure, AdventureError};
+use crate::command::CommandSpec;
+use crate::directions::direction_cmp;
+use crate::effects::Effect;
+use crate::event_log::{EventLog, LogEntry, LoggedEvent};
+use crate::messages::Messages;
+use crate::log::Logger;
+use crate::raw::{RawAdventure, SkippedEntity};
+use crate::replay::{Replay, ReplayTurn};
+use crate::output::{tagged, LineKind, OutputLine, StepResult};
+use crate::save::SaveState;
+use crate::settings::{DeathBehavior, GameSettings, InventoryMode, SETTINGS_ENV_PREFIX};
 use crate::systems::System;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
+/// Turns added to the clock each time the player respawns.
+const RESPAWN_TURN_PENALTY: i32 = 10;
+/// Score deducted per death when the adventure allows respawning.
+const DEATH_SCORE_PENALTY: i32 = 50;
+
+/// Faction merchants refuse to trade below this standing.
+pub const TRADE_STANDING: i32 = 0;
+/// Faction monsters turn hostile on sight at or below this standing.
+pub const HOSTILE_STANDING: i32 = -50;
+
+/// Width (in characters) of the health bar on the character sheet.
+const HEALTH_BAR_WIDTH: i32 = 10;
+
+/// Render health as a plain-text bar, e.g. `[#######---]`.
+fn health_bar(current: i32, max: i32) -> String {
+    let filled = if max > 0 { (current.clamp(0, max) * HEALTH_BAR_WIDTH) / max } else { 0 };
+    format!("[{}{}]", "#".repeat(filled as usize), "-".repeat((HEALTH_BAR_WIDTH - filled) as usize))
+}
+
+/// Split a namespaced target such as `monster:3` into its kind and id.
+pub(crate) fn split_target(target: &str) -> Option<(&str, i32)> {
+    let (kind, id) = target.split_once(':')?;
+    matches!(kind, "room" | "item" | "monster").then_some(())?;
+    Some((kind, id.trim().parse().ok()?))
+}
+
 /// Case-insensitive substring match for item/monster names.
 pub(crate) fn name_matches(name: &str, query: &str) -> bool {
     name.to_lowercase().contains(&query.to_lowercase())
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Split "sword, shield and lamp" into the names it lists, unless the whole
+/// text already matches one of `names` ("Bread and Butter").
+pub(crate) fn item_list<'a>(text: &str, mut names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let text = text.trim();
+    if !text.is_empty() && names.any(|name| name_matches(name, text)) {
+        return vec![text.to_string()];
+    }
+    text.split(',')
+        .flat_map(|part| part.split(" and "))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether an [`item_list`] asks for everything.
+fn is_all(names: &[String]) -> bool {
+    matches!(names, [name] if name.eq_ignore_ascii_case("all") || name.eq_ignore_ascii_case("everything"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ItemType {
     Weapon,
@@ -20,6 +81,52 @@ pub enum ItemType {
     Normal,
 }
 
+impl ItemType {
+    /// Heading for this type in a grouped inventory listing.
+    pub fn heading(&self) -> &'static str {
+        match self {
+            ItemType::Weapon => "Weapons",
+            ItemType::Armor => "Armor",
+            ItemType::Treasure => "Treasure",
+            ItemType::Readable => "Reading",
+            ItemType::Edible => "Food",
+            ItemType::Drinkable => "Drink",
+            ItemType::Container => "Containers",
+            ItemType::Normal => "Other",
+        }
+    }
+}
+
+/// Order of the inventory listing, chosen with `inventory sort <key>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InventorySort {
+    /// The order items were picked up in.
+    #[default]
+    None,
+    Name,
+    Type,
+    /// Most valuable first.
+    Value,
+    /// Heaviest first.
+    Weight,
+}
+
+impl std::str::FromStr for InventorySort {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(InventorySort::None),
+            "name" => Ok(InventorySort::Name),
+            "type" => Ok(InventorySort::Type),
+            "value" => Ok(InventorySort::Value),
+            "weight" => Ok(InventorySort::Weight),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MonsterStatus {
@@ -28,6 +135,51 @@ pub enum MonsterStatus {
     Hostile,
 }
 
+/// Coarse time of day, used to gate exits and monsters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+/// A player attribute tested by [`AdventureGame::ability_check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stat {
+    Hardiness,
+    Agility,
+    Charisma,
+}
+
+impl Stat {
+    pub fn name(self) -> &'static str {
+        match self {
+            Stat::Hardiness => "hardiness",
+            Stat::Agility => "agility",
+            Stat::Charisma => "charisma",
+        }
+    }
+}
+
+/// Gold charged per point of durability restored by `repair`.
+pub const REPAIR_COST_PER_POINT: i32 = 2;
+
+/// Hours of the day (24h clock) that count as daytime.
+const DAYTIME_HOURS: std::ops::Range<i32> = 6..20;
+
+/// [`Item::location`] of an item that is inside a container.
+pub const IN_CONTAINER: i32 = -2;
+
+/// Chance that moving out of a room the player can't see in gets them nowhere.
+const DARK_STUMBLE_CHANCE: f64 = 0.5;
+
+/// Agility check difficulty for disarming a trap without a tool.
+const TRAP_DISARM_DIFFICULTY: i32 = 12;
+
+/// Turns `undo` can go back.
+pub const UNDO_LIMIT: usize = 20;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub id: i32,
@@ -44,7 +196,31 @@ pub struct Item {
     pub armor_value: i32,
     pub is_takeable: bool,
     pub is_wearable: bool,
-    pub location: i32, // 0=inventory, -1=worn, room_id or monster_id
+    pub location: i32, // 0=inventory, -1=worn, -2=in a container, room_id or monster_id
+    /// Minimum player hardiness needed to equip this item, if any.
+    pub min_hardiness: Option<i32>,
+    /// Minimum player level needed to equip this item, if any.
+    pub min_level: Option<i32>,
+    /// Hidden items don't show up in the room, or in the container holding
+    /// them, until it is searched.
+    pub hidden: bool,
+    /// Uses left before the item breaks; `None` means it never wears out.
+    /// Weapons wear on every hit they land, armor on every hit it takes.
+    pub durability: Option<i32>,
+    /// Durability when new, restored by repairing.
+    pub max_durability: Option<i32>,
+    /// Carrying this lets the player see in dark rooms.
+    #[serde(default)]
+    pub is_light_source: bool,
+    /// Carrying this disarms traps before they go off.
+    #[serde(default)]
+    pub disarms_traps: bool,
+    /// Ids of the items inside, for containers.
+    #[serde(default)]
+    pub contents: Vec<i32>,
+    /// Whether a container is open, showing its contents.
+    #[serde(default)]
+    pub is_open: bool,
 }
 
 impl Item {
@@ -72,15 +248,32 @@ impl Item {
             is_takeable: true,
             is_wearable: false,
             location: 0,
+            min_hardiness: None,
+            min_level: None,
+            hidden: false,
+            durability: None,
+            max_durability: None,
+            is_light_source: false,
+            disarms_traps: false,
+            contents: Vec::new(),
+            is_open: false,
         }
     }
 
     pub fn get_damage(&self) -> i32 {
+        self.roll_damage(&mut rand::thread_rng())
+    }
+
+    /// Whether the item has worn out and can't be used until repaired.
+    pub fn is_broken(&self) -> bool {
+        self.durability.is_some_and(|d| d <= 0)
+    }
+
+    /// Roll the weapon's damage dice with the given RNG (e.g. the game's seeded one).
+    pub fn roll_damage(&self, rng: &mut impl Rng) -> i32 {
         if !self.is_weapon {
             return 0;
         }
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
         (0..self.weapon_dice)
             .map(|_| rng.gen_range(1..=self.weapon_sides))
             .sum()
@@ -102,6 +295,34 @@ pub struct Monster {
     pub gold: i32,
     pub is_dead: bool,
     pub current_health: i32,
+    /// Special abilities such as "poison", "regenerate" or "flee".
+    /// Unknown names are ignored by the combat system.
+    pub abilities: Vec<String>,
+    /// Times of day the monster is around; empty means always.
+    pub available_times: Vec<TimeOfDay>,
+    /// Monsters of the same faction defend each other when one is attacked.
+    pub faction: Option<String>,
+    /// Lines the monster calls out when the player walks in or a fight
+    /// starts; one is picked at random each time.
+    pub barks: Vec<String>,
+    /// What the monster says when talked to, by topic.
+    pub dialogue: Vec<DialogueLine>,
+}
+
+/// One thing a monster can say: its `greeting` when talked to, or its
+/// answer when the player says something mentioning `topic`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DialogueLine {
+    #[serde(default = "greeting_topic")]
+    pub topic: String,
+    pub text: String,
+}
+
+/// Topic of the line a monster opens a conversation with.
+pub const GREETING_TOPIC: &str = "greeting";
+
+fn greeting_topic() -> String {
+    GREETING_TOPIC.to_string()
 }
 
 impl Monster {
@@ -130,8 +351,55 @@ impl Monster {
             gold: 0,
             is_dead: false,
             current_health: hardiness,
+            abilities: Vec::new(),
+            available_times: Vec::new(),
+            faction: None,
+            barks: Vec::new(),
+            dialogue: Vec::new(),
         }
     }
+
+    pub fn has_ability(&self, ability: &str) -> bool {
+        self.abilities.iter().any(|a| a.eq_ignore_ascii_case(ability))
+    }
+
+    /// The monster's line on `topic`, if it has one.
+    pub fn line_on(&self, topic: &str) -> Option<&str> {
+        self.dialogue.iter()
+            .find(|line| line.topic.eq_ignore_ascii_case(topic))
+            .map(|line| line.text.as_str())
+    }
+}
+
+/// How a [`Barrier`] gives way to its key item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BarrierKind {
+    /// A door, gate, ... that the key unlocks.
+    #[default]
+    Locked,
+    /// A web, brambles, ... that the key (a torch, say) burns away.
+    Flammable,
+    /// A door that opens for anyone carrying the key, without it having
+    /// to be used first. Using the key on it still unlocks it for good.
+    Keyed,
+}
+
+/// Something in the way of an exit until the right item is used on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Barrier {
+    /// What the player calls it: "door", "web", ...
+    pub name: String,
+    /// Id of the item that clears it.
+    pub key: i32,
+    #[serde(default)]
+    pub kind: BarrierKind,
+    /// Set once the key has been used; the exit is open from then on.
+    #[serde(default)]
+    pub cleared: bool,
+    /// Shown instead of the usual "bars the way" text when it stops the player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -139,8 +407,29 @@ pub struct Room {
     pub id: i32,
     pub name: String,
     pub description: String,
-    pub exits: HashMap<String, i32>, // direction -> room_id
+    pub exits: BTreeMap<String, i32>, // direction -> room_id
     pub is_dark: bool,
+    /// Dying after visiting this room respawns the player here.
+    pub is_checkpoint: bool,
+    /// Entering this room writes an autosave.
+    pub auto_save_on_enter: bool,
+    /// Exits that only become usable once the room has been searched.
+    pub hidden_exits: BTreeMap<String, i32>,
+    /// Whether the player has searched this room, revealing its hidden content.
+    pub searched: bool,
+    /// Outdoor rooms are dark at night.
+    pub is_outdoors: bool,
+    /// direction -> times of day the exit can be used; exits not listed are always open.
+    pub exit_times: BTreeMap<String, Vec<TimeOfDay>>,
+    /// Gold can be banked here.
+    pub is_safe_zone: bool,
+    /// direction -> what blocks that exit; see [`AdventureGame::use_item_on`].
+    pub barriers: BTreeMap<String, Barrier>,
+    /// A trap goes off the first time the player enters, dealing `trap_damage`.
+    pub has_trap: bool,
+    pub trap_damage: i32,
+    /// Whether the trap has gone off or been disarmed.
+    pub trap_sprung: bool,
 }
 
 impl Room {
@@ -149,14 +438,75 @@ impl Room {
             id,
             name,
             description,
-            exits: HashMap::new(),
+            exits: BTreeMap::new(),
             is_dark: false,
+            is_checkpoint: false,
+            auto_save_on_enter: false,
+            hidden_exits: BTreeMap::new(),
+            searched: false,
+            is_outdoors: false,
+            exit_times: BTreeMap::new(),
+            is_safe_zone: false,
+            barriers: BTreeMap::new(),
+            has_trap: false,
+            trap_damage: 0,
+            trap_sprung: false,
         }
     }
 
     pub fn get_exit(&self, direction: &str) -> Option<i32> {
         self.exits.get(&direction.to_lowercase()).copied()
     }
+
+    /// The room's exits as `(direction, room id)`, in
+    /// [`DIRECTION_ORDER`](crate::directions::DIRECTION_ORDER).
+    pub fn sorted_exits(&self) -> Vec<(&str, i32)> {
+        let mut exits: Vec<(&str, i32)> = self.exits.iter().map(|(dir, &to)| (dir.as_str(), to)).collect();
+        exits.sort_by(|a, b| direction_cmp(a.0, b.0));
+        exits
+    }
+
+    /// What still blocks the exit `direction`, if anything.
+    pub fn barrier(&self, direction: &str) -> Option<&Barrier> {
+        self.barriers.get(&direction.to_lowercase()).filter(|b| !b.cleared)
+    }
+}
+
+/// Tries to make sense of `use <item> on <target>`: returns the outcome if it
+/// recognises the pair, `None` to let the next handler try.
+type UseOnHandler = fn(&mut AdventureGame, i32, &str) -> Option<String>;
+
+/// Everything `use <item> on <target>` can do, in the order it is tried.
+const USE_ON_HANDLERS: &[UseOnHandler] = &[
+    AdventureGame::clear_barrier,
+    AdventureGame::heal_ally,
+];
+
+/// One of the current room's exits, for the look output and front-ends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExitInfo {
+    pub direction: String,
+    pub destination: i32,
+    /// What still blocks the exit ("oak door", ...), if anything.
+    pub barrier: Option<String>,
+    /// The exit hasn't been found by searching yet.
+    pub hidden: bool,
+}
+
+impl ExitInfo {
+    /// Whether a barrier stops the player using the exit for now.
+    pub fn is_locked(&self) -> bool {
+        self.barrier.is_some()
+    }
+}
+
+/// Result of looking up an inventory item by (partial) name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryMatch {
+    Unique(i32),
+    /// Several differently named items match; one id per name, sorted by name.
+    Ambiguous(Vec<i32>),
+    None,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -165,9 +515,12 @@ pub struct Player {
     pub hardiness: i32,
     pub agility: i32,
     pub charisma: i32,
-    pub weapon_ability: HashMap<i32, i32>, // weapon_type -> ability
+    pub weapon_ability: BTreeMap<i32, i32>, // weapon_type -> ability
     pub armor_expertise: i32,
     pub gold: i32,
+    /// Gold left at a bank; safe from weight limits and death.
+    #[serde(default)]
+    pub banked_gold: i32,
     pub current_room: i32,
     pub current_health: i32,
     pub inventory: Vec<i32>, // item IDs
@@ -175,11 +528,28 @@ pub struct Player {
     pub equipped_armor: Option<i32>,
     pub experience_points: i32,
     pub level: i32,
+    pub status_effects: BTreeMap<String, i32>, // effect name -> turns remaining
+    pub deaths: i32,
+    /// Direction of the exit the player last walked through, for `back`.
+    #[serde(default)]
+    pub last_exit_used: Option<String>,
+    /// faction -> standing; factions not listed are at 0.
+    #[serde(default)]
+    pub reputation: BTreeMap<String, i32>,
+    /// How `inventory` orders its listing.
+    #[serde(default)]
+    pub inventory_sort: InventorySort,
+    /// Whether `inventory` groups items under a heading per type.
+    #[serde(default)]
+    pub inventory_grouped: bool,
+    /// Quests turned in with `complete`.
+    #[serde(default)]
+    pub quests_completed: i32,
 }
 
 impl Player {
     pub fn new() -> Self {
-        let mut weapon_ability = HashMap::new();
+        let mut weapon_ability = BTreeMap::new();
         for i in 1..=5 {
             weapon_ability.insert(i, 5);
         }
@@ -191,6 +561,7 @@ impl Player {
             weapon_ability,
             armor_expertise: 0,
             gold: 200,
+            banked_gold: 0,
             current_room: 1,
             current_health: 12,
             inventory: Vec::new(),
@@ -198,6 +569,56 @@ impl Player {
             equipped_armor: None,
             experience_points: 0,
             level: 1,
+            status_effects: BTreeMap::new(),
+            deaths: 0,
+            last_exit_used: None,
+            reputation: BTreeMap::new(),
+            inventory_sort: InventorySort::None,
+            inventory_grouped: false,
+            quests_completed: 0,
+        }
+    }
+
+    /// Standing with `faction`.
+    pub fn standing(&self, faction: &str) -> i32 {
+        self.reputation.get(faction).copied().unwrap_or(0)
+    }
+
+    /// The player's score in `stat`.
+    pub fn stat(&self, stat: Stat) -> i32 {
+        match stat {
+            Stat::Hardiness => self.hardiness,
+            Stat::Agility => self.agility,
+            Stat::Charisma => self.charisma,
+        }
+    }
+
+    /// Bonus added to ability checks on `stat`: +1 for every two points
+    /// above 10, -1 for every two below.
+    pub fn stat_modifier(&self, stat: Stat) -> i32 {
+        (self.stat(stat) - 10).div_euclid(2)
+    }
+
+    /// Shift standing with `faction` by `change`.
+    pub fn adjust_standing(&mut self, faction: &str, change: i32) {
+        *self.reputation.entry(faction.to_string()).or_insert(0) += change;
+    }
+
+    /// Find a carried item by name. An exact (case-insensitive) name wins;
+    /// otherwise any item whose name contains `name` matches. Identical
+    /// items count as one match.
+    pub fn find_inventory_item(&self, name: &str, items: &HashMap<i32, Item>) -> InventoryMatch {
+        let carried: Vec<&Item> = self.inventory.iter().filter_map(|id| items.get(id)).collect();
+        if let Some(exact) = carried.iter().find(|i| i.name.eq_ignore_ascii_case(name)) {
+            return InventoryMatch::Unique(exact.id);
+        }
+        let mut matching: Vec<&Item> = carried.into_iter().filter(|i| name_matches(&i.name, name)).collect();
+        matching.sort_by(|a, b| a.name.cmp(&b.name));
+        matching.dedup_by(|a, b| a.name == b.name);
+        match matching.as_slice() {
+            [] => InventoryMatch::None,
+            [only] => InventoryMatch::Unique(only.id),
+            several => InventoryMatch::Ambiguous(several.iter().map(|i| i.id).collect()),
         }
     }
 }
@@ -211,12 +632,127 @@ impl Default for Player {
 /// Events emitted by systems so other systems can react (quest tracking, etc.).
 #[derive(Debug, Clone)]
 pub enum GameEvent {
-    MonsterKilled { monster_name: String, room_id: i32 },
+    MonsterKilled { monster_name: String, monster_id: i32, room_id: i32 },
     ItemCollected { item_name: String, item_id: i32 },
     RoomEntered { room_id: i32 },
     ItemUsed { item_name: String },
+    /// The player spoke with a monster that was willing to talk.
+    TalkedTo { monster_id: i32, monster_name: String },
+    /// An event defined by a custom system. Systems handling it in
+    /// [`System::on_publish`] may change `data`, so the ones after them
+    /// see the running result (total damage, say).
+    Custom { name: String, data: EventData },
+}
+
+/// Named values carried by a [`GameEvent::Custom`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventData(BTreeMap<String, serde_json::Value>);
+
+impl EventData {
+    pub fn set(&mut self, key: &str, value: impl Into<serde_json::Value>) {
+        self.0.insert(key.to_string(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.0.get(key)
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(serde_json::Value::as_i64)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(serde_json::Value::as_str)
+    }
+}
+
+/// A question the engine has put to the player; the next input answers it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingAction {
+    /// Re-run `command` with whichever of `choices` the player picks, by
+    /// number (1-based) or by name. The choice fills the `{}` in `command`
+    /// ("combine {} with rope"), or is appended if there isn't one.
+    Choose { command: String, choices: Vec<String> },
+    /// Run `command` if the player answers yes. While it runs,
+    /// [`AdventureGame::confirmed`] is true.
+    Confirm { command: String },
+}
+
+/// How an input line relates to a [`PendingAction`].
+enum Answer {
+    Run(String),
+    Cancel,
+    /// A number that doesn't pick any of the choices; the question stays open.
+    Invalid,
+    Unrelated,
+}
+
+/// List `choices` numbered from 1, the way [`PendingAction::Choose`] expects
+/// them to be picked: "1) Brass key, 2) Iron key".
+pub fn number_choices(choices: &[String]) -> String {
+    choices.iter()
+        .enumerate()
+        .map(|(i, choice)| format!("{}) {}", i + 1, choice))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl PendingAction {
+    fn answer(&self, input: &str) -> Answer {
+        let input = input.trim();
+        match self {
+            PendingAction::Choose { command, choices } => {
+                let picked = match input.parse::<usize>() {
+                    Ok(n) => match n.checked_sub(1).and_then(|i| choices.get(i)) {
+                        Some(choice) => Some(choice),
+                        None => return Answer::Invalid,
+                    },
+                    Err(_) => {
+                        let mut matching = choices.iter().filter(|c| name_matches(c, input));
+                        match (matching.next(), matching.next()) {
+                            (Some(only), None) if !input.is_empty() => Some(only),
+                            _ => None,
+                        }
+                    }
+                };
+                picked.map_or(Answer::Unrelated, |choice| Answer::Run(if command.contains("{}") {
+                    command.replacen("{}", choice, 1)
+                } else {
+                    format!("{} {}", command, choice)
+                }))
+            }
+            PendingAction::Confirm { command } => match input.to_lowercase().as_str() {
+                "y" | "yes" => Answer::Run(command.clone()),
+                "n" | "no" => Answer::Cancel,
+                _ => Answer::Unrelated,
+            },
+        }
+    }
+}
+
+/// Two items that can be combined into a third, e.g. rope + hook -> grappling hook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recipe {
+    /// Ingredient item ids, in either order.
+    pub inputs: [i32; 2],
+    /// Item id produced; it should start outside any room.
+    pub output: i32,
 }
 
+impl Recipe {
+    fn uses(&self, a: i32, b: i32) -> bool {
+        self.inputs == [a, b] || self.inputs == [b, a]
+    }
+}
+
+/// Callback receiving each command and the lines it produced.
+pub type OutputObserver = Box<dyn FnMut(&str, &[String]) + Send>;
+
+/// Handle for an output observer, used to remove it again. Ids are never
+/// reused within a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObserverId(u64);
+
 pub struct AdventureGame {
     pub adventure_file: String,
     pub rooms: HashMap<i32, Room>,
@@ -230,10 +766,64 @@ pub struct AdventureGame {
     pub systems: Vec<Box<dyn System>>,
     pub quests: Vec<serde_json::Value>,  // Quest definitions
     pub events: Vec<GameEvent>,           // Inter-system event bus
+    /// Scripted events from the adventure file; see [`crate::effects`].
+    pub effects: Vec<Effect>,
+    /// Indices into `effects` that have fired at least once.
+    pub fired_effects: BTreeSet<usize>,
+    /// Item combinations available to `combine`.
+    pub recipes: Vec<Recipe>,
+    /// Named story flags set and tested by effects.
+    pub flags: BTreeSet<String>,
+    /// Source of all gameplay randomness; seed it with [`AdventureGame::seed_rng`]
+    /// for reproducible runs.
+    pub rng: StdRng,
+    pub settings: GameSettings,
+    /// Non-fatal problems found while loading (dangling quest targets, ...).
+    pub load_warnings: Vec<String>,
+    /// Malformed entities left out by [`AdventureGame::load_lenient`].
+    pub skipped_entities: Vec<SkippedEntity>,
+    /// Where the player returns after dying: the start room or the last checkpoint entered.
+    pub respawn_room: i32,
+    /// Where checkpoint saves are written; defaults to `<adventure>.autosave.json`.
+    pub autosave_path: PathBuf,
+    /// Engine message text; see [`AdventureGame::messages`].
+    pub message_table: Messages,
+    /// Question awaiting the player's next input, if any. Input that doesn't
+    /// answer it drops the question and runs as a normal command.
+    pub pending: Option<PendingAction>,
+    confirming: bool,
+    /// Each registered system's name, commands and dependencies, kept outside
+    /// `systems` so they can be read while a command is being dispatched.
+    registry: Vec<(&'static str, &'static [CommandSpec], &'static [&'static str])>,
+    /// Each system's [`System::save_data`] as of its last hook, so saves
+    /// written mid-dispatch (autosaves) still see systems that are busy.
+    system_data: BTreeMap<String, serde_json::Value>,
+    /// Session being recorded; see [`AdventureGame::start_recording`].
+    pub(crate) recording: Option<Replay>,
+    output_observers: Vec<(ObserverId, OutputObserver)>,
+    next_observer_id: u64,
+    /// Where engine diagnostics (load warnings, autosave failures, ...) go.
+    pub logger: Logger,
+    /// What has happened so far; see [`AdventureGame::recent_events`].
+    pub event_log: EventLog,
+    /// Items used up, combined or given away, kept so that loading a save or
+    /// undoing can put back the ones that still existed then.
+    pub(crate) removed_items: BTreeMap<i32, Item>,
+    /// The state before each of the last [`UNDO_LIMIT`] commands that
+    /// changed anything, newest last.
+    undo_stack: VecDeque<SaveState>,
+    /// Set by [`AdventureGame::request_undo`]; the snapshot is put back once
+    /// the command finishes and every system is back in place.
+    undo_requested: bool,
 }
 
 impl AdventureGame {
     pub fn new(adventure_file: String) -> Self {
+        let autosave_path = if adventure_file.is_empty() {
+            PathBuf::from("autosave.json")
+        } else {
+            Path::new(&adventure_file).with_extension("autosave.json")
+        };
         Self {
             adventure_file,
             rooms: HashMap::new(),
@@ -247,98 +837,219 @@ impl AdventureGame {
             systems: Vec::new(),
             quests: Vec::new(),
             events: Vec::new(),
+            effects: Vec::new(),
+            recipes: Vec::new(),
+            fired_effects: BTreeSet::new(),
+            flags: BTreeSet::new(),
+            rng: StdRng::from_entropy(),
+            settings: GameSettings::default(),
+            load_warnings: Vec::new(),
+            skipped_entities: Vec::new(),
+            respawn_room: 1,
+            autosave_path,
+            message_table: Messages::default(),
+            pending: None,
+            confirming: false,
+            registry: Vec::new(),
+            system_data: BTreeMap::new(),
+            recording: None,
+            output_observers: Vec::new(),
+            next_observer_id: 0,
+            logger: Logger::default(),
+            event_log: EventLog::default(),
+            removed_items: BTreeMap::new(),
+            undo_stack: VecDeque::new(),
+            undo_requested: false,
         }
     }
 
+    /// Whether the command being run is one the player just confirmed with "yes".
+    pub fn confirmed(&self) -> bool {
+        self.confirming
+    }
+
+    /// Register a callback that sees every command and the output it produced,
+    /// e.g. to tee a session to a log. Replaces any previous observers.
+    pub fn set_output_observer(&mut self, observer: OutputObserver) -> ObserverId {
+        self.output_observers.clear();
+        self.add_output_observer(observer)
+    }
+
+    /// Register another output observer alongside any already registered.
+    /// Observers are called in the order they were added.
+    pub fn add_output_observer(&mut self, observer: OutputObserver) -> ObserverId {
+        let id = ObserverId(self.next_observer_id);
+        self.next_observer_id += 1;
+        self.output_observers.push((id, observer));
+        id
+    }
+
+    /// Stop calling the observer registered as `id`. Returns whether it was
+    /// still registered.
+    pub fn remove_output_observer(&mut self, id: ObserverId) -> bool {
+        let before = self.output_observers.len();
+        self.output_observers.retain(|(observer_id, _)| *observer_id != id);
+        self.output_observers.len() != before
+    }
+
+    /// Engine message `key` with `{placeholders}` filled from `args`, in the
+    /// adventure's locale if it provides one.
+    pub fn messages(&self, key: &str, args: &[(&str, &str)]) -> String {
+        self.message_table.format(key, args)
+    }
+
+    /// Reseed the game's RNG so combat rolls, flee attempts, etc. are reproducible.
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Roll a d20 plus the player's modifier for `stat` against `difficulty`.
+    /// Succeeds when the total meets or beats it. Uses the game's RNG, so
+    /// checks are reproducible after [`AdventureGame::seed_rng`].
+    pub fn ability_check(&mut self, stat: Stat, difficulty: i32) -> bool {
+        let roll = self.rng.gen_range(1..=20);
+        let modifier = self.player.stat_modifier(stat);
+        let success = roll + modifier >= difficulty;
+        self.logger.debug(&format!(
+            "{} check against {}: rolled {}{:+} ({})",
+            stat.name(), difficulty, roll, modifier, if success { "success" } else { "failure" }
+        ));
+        success
+    }
+
+    /// Load the adventure file, `.json5` file or `.saga` bundle named by `adventure_file`.
     pub fn load_adventure(&mut self) -> Result<String, Box<dyn std::error::Error>> {
-        let data: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&self.adventure_file)?)?;
-
-        self.adventure_title = data.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled Adventure").to_string();
-        self.adventure_intro = data.get("intro").and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-        // Load rooms
-        if let Some(rooms) = data.get("rooms").and_then(|v| v.as_array()) {
-            for room_data in rooms {
-                let room = Room {
-                    id: room_data.get("id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    name: room_data.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    description: room_data.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    exits: room_data.get("exits").and_then(|v| v.as_object())
-                        .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.as_i64().unwrap_or(0) as i32)).collect())
-                        .unwrap_or_default(),
-                    is_dark: room_data.get("is_dark").and_then(|v| v.as_bool()).unwrap_or(false),
-                };
-                self.rooms.insert(room.id, room);
-            }
-        }
-
-        // Load items
-        if let Some(items) = data.get("items").and_then(|v| v.as_array()) {
-            for item_data in items {
-                let item = Item {
-                    id: item_data.get("id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    name: item_data.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    description: item_data.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    item_type: match item_data.get("type").and_then(|v| v.as_str()) {
-                        Some("weapon") => ItemType::Weapon,
-                        Some("armor") => ItemType::Armor,
-                        Some("treasure") => ItemType::Treasure,
-                        Some("readable") => ItemType::Readable,
-                        Some("edible") => ItemType::Edible,
-                        Some("drinkable") => ItemType::Drinkable,
-                        Some("container") => ItemType::Container,
-                        _ => ItemType::Normal,
-                    },
-                    weight: item_data.get("weight").and_then(|v| v.as_i64()).unwrap_or(1) as i32,
-                    value: item_data.get("value").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    is_weapon: item_data.get("is_weapon").and_then(|v| v.as_bool()).unwrap_or(false),
-                    weapon_type: item_data.get("weapon_type").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    weapon_dice: item_data.get("weapon_dice").and_then(|v| v.as_i64()).unwrap_or(1) as i32,
-                    weapon_sides: item_data.get("weapon_sides").and_then(|v| v.as_i64()).unwrap_or(6) as i32,
-                    is_armor: item_data.get("is_armor").and_then(|v| v.as_bool()).unwrap_or(false),
-                    armor_value: item_data.get("armor_value").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    is_takeable: item_data.get("is_takeable").and_then(|v| v.as_bool()).unwrap_or(true),
-                    is_wearable: item_data.get("is_wearable").and_then(|v| v.as_bool()).unwrap_or(false),
-                    location: item_data.get("location").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                };
-                self.items.insert(item.id, item);
+        self.load_file(false)
+    }
+
+    /// Like [`AdventureGame::load_adventure`], but rooms, items and monsters
+    /// that are malformed are skipped, and listed in `skipped_entities`,
+    /// instead of failing the load. Lets authors playtest the rest of a
+    /// half-finished adventure.
+    pub fn load_lenient(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        self.load_file(true)
+    }
+
+    fn load_file(&mut self, lenient: bool) -> Result<String, Box<dyn std::error::Error>> {
+        if Path::new(&self.adventure_file).extension().is_some_and(|ext| ext == crate::bundle::BUNDLE_EXTENSION) {
+            return self.load_bundle_with(self.adventure_file.clone(), lenient);
+        }
+        let mut json = std::fs::read_to_string(&self.adventure_file)?;
+        if Path::new(&self.adventure_file).extension().is_some_and(|ext| ext == crate::raw::JSON5_EXTENSION) {
+            json = crate::raw::strip_json5(&json);
+        }
+        self.load_json(&json, lenient)
+    }
+
+    /// Load an adventure from JSON text; see [`AdventureGame::load_adventure`].
+    pub fn load_adventure_str(&mut self, json: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self.load_json(json, false)
+    }
+
+    /// Load an adventure from JSON text, skipping malformed entities; see
+    /// [`AdventureGame::load_lenient`].
+    pub fn load_adventure_str_lenient(&mut self, json: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self.load_json(json, true)
+    }
+
+    pub(crate) fn load_json(&mut self, json: &str, lenient: bool) -> Result<String, Box<dyn std::error::Error>> {
+        // Files saved by the editors name rooms by string id; play them the
+        // way the editors' own Play does.
+        let converted;
+        let json = match serde_json::from_str::<Adventure>(json) {
+            Ok(adventure) => {
+                converted = adventure.to_engine_json().to_string();
+                converted.as_str()
+            }
+            Err(_) => json,
+        };
+        let (data, key_warnings, skipped) = if lenient {
+            RawAdventure::parse_lenient(json)?
+        } else {
+            let (data, key_warnings) = RawAdventure::parse(json)?;
+            (data, key_warnings, Vec::new())
+        };
+
+        self.adventure_title = data.title;
+        self.adventure_intro = data.intro;
+        self.undo_stack.clear();
+        self.removed_items.clear();
+        self.rooms.extend(data.rooms.into_iter().map(|r| (r.id, Room::from(r))));
+        self.items.extend(data.items.into_iter().map(|i| (i.id, Item::from(i))));
+        // Whatever a container starts out holding isn't lying loose anywhere
+        let stowed: Vec<i32> = self.items.values().flat_map(|i| i.contents.clone()).collect();
+        for id in stowed {
+            if let Some(item) = self.items.get_mut(&id) {
+                item.location = IN_CONTAINER;
             }
         }
+        self.monsters.extend(data.monsters.into_iter().map(|m| (m.id, Monster::from(m))));
+        self.quests = data.quests;
+        self.effects = data.effects;
+        self.recipes = data.recipes;
 
-        // Load monsters
-        if let Some(monsters) = data.get("monsters").and_then(|v| v.as_array()) {
-            for mon_data in monsters {
-                let friendliness = match mon_data.get("friendliness").and_then(|v| v.as_str()) {
-                    Some("friendly") => MonsterStatus::Friendly,
-                    Some("hostile") => MonsterStatus::Hostile,
-                    _ => MonsterStatus::Neutral,
-                };
-                let monster = Monster::new(
-                    mon_data.get("id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
-                    mon_data.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    mon_data.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    mon_data.get("room_id").and_then(|v| v.as_i64()).unwrap_or(1) as i32,
-                    mon_data.get("hardiness").and_then(|v| v.as_i64()).unwrap_or(10) as i32,
-                    mon_data.get("agility").and_then(|v| v.as_i64()).unwrap_or(10) as i32,
-                    friendliness,
-                    mon_data.get("courage").and_then(|v| v.as_i64()).unwrap_or(100) as i32,
-                );
-                // Set additional fields
-                let mut monster = monster;
-                monster.weapon_id = mon_data.get("weapon_id").and_then(|v| v.as_i64()).map(|v| v as i32);
-                monster.armor_worn = mon_data.get("armor_worn").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                monster.gold = mon_data.get("gold").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                self.monsters.insert(monster.id, monster);
+        // Load engine settings (inventory mode, ...)
+        if let Some(settings) = data.settings {
+            self.settings = settings;
+            self.settings.apply_aliases();
+        }
+        let env_warnings = self.settings.apply_env_overrides(SETTINGS_ENV_PREFIX);
+        self.event_log.set_capacity(self.settings.gameplay.event_log_capacity);
+        if let Some(locale) = &self.settings.locale {
+            let base = Path::new(&self.adventure_file).parent().unwrap_or(Path::new(""));
+            self.message_table.load_overrides(base.join(locale))?;
+        }
+
+        // Set player starting position, falling back to the lowest room id
+        // rather than leaving the player in a void
+        let mut start_warning = None;
+        let mut start_room = data.start_room;
+        if !self.rooms.contains_key(&start_room)
+            && let Some(&lowest) = self.rooms.keys().min()
+        {
+            if self.settings.gameplay.strict_load {
+                return Err(Box::new(AdventureError::Validation(
+                    format!("start_room does not exist: {}", start_room)
+                )));
             }
+            start_warning = Some(format!("Start room {} does not exist; starting in room {} instead.", start_room, lowest));
+            start_room = lowest;
         }
+        self.player.current_room = start_room;
+        self.respawn_room = self.player.current_room;
 
-        // Load quests
-        if let Some(quests) = data.get("quests").and_then(|v| v.as_array()) {
-            self.quests = quests.clone();
+        let dangling = self.dangling_exits();
+        if self.settings.gameplay.strict_load && !dangling.is_empty() {
+            let list: Vec<String> = dangling.iter()
+                .map(|(room_id, direction, target)| format!("room {} {} -> {}", room_id, direction, target))
+                .collect();
+            return Err(Box::new(AdventureError::Validation(
+                format!("exits lead to missing rooms: {}", list.join(", "))
+            )));
         }
 
-        // Set player starting position
-        self.player.current_room = data.get("start_room").and_then(|v| v.as_i64()).unwrap_or(1) as i32;
+        self.load_warnings = key_warnings;
+        self.load_warnings.extend(env_warnings);
+        self.load_warnings.extend(start_warning);
+        for (room_id, direction, target) in dangling {
+            self.remove_exit(room_id, &direction, target);
+            self.load_warnings.push(format!(
+                "Room {} exit '{}' leads to missing room {}; removed.", room_id, direction, target
+            ));
+        }
+        self.load_warnings.extend(self.validate_quest_targets());
+        self.load_warnings.extend(self.validate_systems().err());
+        for warning in &self.load_warnings {
+            self.logger.warn(warning);
+        }
+        for entity in &skipped {
+            self.logger.warn(&format!("skipped {}", entity));
+        }
+        self.skipped_entities = skipped;
+        self.logger.info(&format!(
+            "Loaded '{}': {} rooms, {} items, {} monsters, {} quests",
+            self.adventure_title, self.rooms.len(), self.items.len(), self.monsters.len(), self.quests.len()
+        ));
 
         // Build and return the opening banner + intro text
         let mut header = format!("\n{:=^60}\n{:^60}\n{:=^60}\n",
@@ -352,69 +1063,308 @@ impl AdventureGame {
         Ok(header)
     }
 
+    /// Move the player to `room_id` as if the adventure had started there,
+    /// overriding `start_room` for this session only. Used to playtest an
+    /// encounter without walking to it.
+    pub fn start_in_room(&mut self, room_id: i32) -> Result<(), String> {
+        if !self.rooms.contains_key(&room_id) {
+            return Err(format!("Room {} does not exist.", room_id));
+        }
+        self.player.current_room = room_id;
+        self.player.last_exit_used = None;
+        self.respawn_room = room_id;
+        Ok(())
+    }
+
+    /// Exits, visible or hidden, that lead to rooms that don't exist, as
+    /// `(room_id, direction, target)` in room and direction order.
+    pub fn dangling_exits(&self) -> Vec<(i32, String, i32)> {
+        let mut room_ids: Vec<i32> = self.rooms.keys().copied().collect();
+        room_ids.sort();
+        let mut dangling = Vec::new();
+        for room_id in room_ids {
+            let room = &self.rooms[&room_id];
+            for (direction, target) in room.exits.iter().chain(&room.hidden_exits) {
+                if !self.rooms.contains_key(target) {
+                    dangling.push((room_id, direction.clone(), *target));
+                }
+            }
+        }
+        dangling
+    }
+
+    /// Remove the exit `direction` -> `target` from room `room_id`, along
+    /// with its opening times once no exit uses that direction.
+    fn remove_exit(&mut self, room_id: i32, direction: &str, target: i32) {
+        let Some(room) = self.rooms.get_mut(&room_id) else { return };
+        for exits in [&mut room.exits, &mut room.hidden_exits] {
+            if exits.get(direction) == Some(&target) {
+                exits.remove(direction);
+            }
+        }
+        if !room.exits.contains_key(direction) && !room.hidden_exits.contains_key(direction) {
+            room.exit_times.remove(direction);
+        }
+    }
+
+    /// Check that quest objective targets given as ids point at an entity of
+    /// the right kind. Targets may be namespaced (`monster:3`, `item:2`,
+    /// `room:1`); bare numeric ids are checked against the collection implied
+    /// by the objective type, and flagged when they match several kinds.
+    pub fn validate_quest_targets(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for quest in &self.quests {
+            let quest_id = quest.get("id").map(|v| v.to_string()).unwrap_or_default();
+            let Some(objectives) = quest.get("objectives").and_then(|v| v.as_array()) else {
+                continue;
+            };
+            for (index, objective) in objectives.iter().enumerate() {
+                let Some(target) = objective.get("target_id")
+                    .and_then(|v| v.as_str().map(str::to_string).or_else(|| v.as_i64().map(|n| n.to_string())))
+                else {
+                    continue;
+                };
+                let expected = match objective.get("type").and_then(|v| v.as_str()) {
+                    Some("kill_monster") | Some("talk_to_npc") => Some("monster"),
+                    Some("collect_item") => Some("item"),
+                    Some("reach_room") => Some("room"),
+                    _ => None,
+                };
+                let (kind, id) = match split_target(&target) {
+                    Some((kind, id)) => (Some(kind), id),
+                    None => match target.parse::<i32>() {
+                        Ok(id) => (None, id),
+                        Err(_) => continue, // matched by name at runtime
+                    },
+                };
+                let location = format!("Quest {} objective {}", quest_id, index + 1);
+                if let (Some(kind), Some(expected)) = (kind, expected)
+                    && kind != expected
+                {
+                    warnings.push(format!("{} targets {}:{} but its type expects a {}.", location, kind, id, expected));
+                    continue;
+                }
+                let found: Vec<&str> = [
+                    ("room", self.rooms.contains_key(&id)),
+                    ("item", self.items.contains_key(&id)),
+                    ("monster", self.monsters.contains_key(&id)),
+                ]
+                .into_iter()
+                .filter_map(|(k, exists)| exists.then_some(k))
+                .collect();
+                match kind.or(expected) {
+                    Some(wanted) if !found.contains(&wanted) => {
+                        let elsewhere = if found.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" (only a {} has that id)", found.join("/"))
+                        };
+                        warnings.push(format!("{} targets {} {}, which does not exist{}.", location, wanted, id, elsewhere));
+                    }
+                    None if found.len() > 1 => warnings.push(format!(
+                        "{} targets id {}, which is shared by a {}; namespace it, e.g. \"{}:{}\".",
+                        location, id, found.join(" and a "), found[0], id
+                    )),
+                    _ => {}
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Take item `id` out of the world for good (eaten, combined, given
+    /// away). Saves record it as gone.
+    pub(crate) fn discard_item(&mut self, id: i32) {
+        if let Some(item) = self.items.remove(&id) {
+            self.removed_items.insert(id, item);
+        }
+    }
+
     pub fn get_current_room(&self) -> Option<&Room> {
         self.rooms.get(&self.player.current_room)
     }
 
     pub fn get_items_in_room(&self, room_id: i32) -> Vec<&Item> {
         self.items.values()
-            .filter(|item| item.location == room_id)
+            .filter(|item| item.location == room_id && !item.hidden)
             .collect()
     }
 
     pub fn get_monsters_in_room(&self, room_id: i32) -> Vec<&Monster> {
         self.monsters.values()
-            .filter(|m| m.room_id == room_id && !m.is_dead)
+            .filter(|m| m.room_id == room_id && !m.is_dead && self.is_available(&m.available_times))
+            .collect()
+    }
+
+    /// Every item, wherever it is, for which `pred` holds, sorted by id.
+    pub fn find_items(&self, pred: impl Fn(&Item) -> bool) -> Vec<&Item> {
+        let mut found: Vec<&Item> = self.items.values().filter(|item| pred(item)).collect();
+        found.sort_by_key(|item| item.id);
+        found
+    }
+
+    /// Every monster, dead or alive, for which `pred` holds, sorted by id.
+    pub fn find_monsters(&self, pred: impl Fn(&Monster) -> bool) -> Vec<&Monster> {
+        let mut found: Vec<&Monster> = self.monsters.values().filter(|m| pred(m)).collect();
+        found.sort_by_key(|m| m.id);
+        found
+    }
+
+    /// Change item `id` in place. Returns whether there is such an item.
+    pub fn update_item(&mut self, id: i32, f: impl FnOnce(&mut Item)) -> bool {
+        self.items.get_mut(&id).map(f).is_some()
+    }
+
+    /// Change monster `id` in place. Returns whether there is such a monster.
+    pub fn update_monster(&mut self, id: i32, f: impl FnOnce(&mut Monster)) -> bool {
+        self.monsters.get_mut(&id).map(f).is_some()
+    }
+
+    /// Current hour (0-23) on the adventure clock.
+    pub fn hour(&self) -> i32 {
+        let clock = &self.settings.clock;
+        (clock.start_hour + self.turn_count * 24 / clock.day_length.max(1)).rem_euclid(24)
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        if DAYTIME_HOURS.contains(&self.hour()) { TimeOfDay::Day } else { TimeOfDay::Night }
+    }
+
+    /// The part of the day the clock is in, for descriptions: "morning",
+    /// "afternoon", "evening" or "night".
+    pub fn day_period(&self) -> &'static str {
+        match self.hour() {
+            5..=11 => "morning",
+            12..=16 => "afternoon",
+            17..=20 => "evening",
+            _ => "night",
+        }
+    }
+
+    /// Whether something limited to `times` is available now. An empty list means always.
+    pub fn is_available(&self, times: &[TimeOfDay]) -> bool {
+        times.is_empty() || times.contains(&self.time_of_day())
+    }
+
+    /// Whether `room` is too dark to see in: it is dark always, or because it
+    /// is outdoors at night, and the player carries no light source.
+    pub fn is_dark(&self, room: &Room) -> bool {
+        (room.is_dark || (room.is_outdoors && self.time_of_day() == TimeOfDay::Night)) && !self.has_light()
+    }
+
+    /// Whether anything in the player's inventory gives off light.
+    pub fn has_light(&self) -> bool {
+        self.player.inventory.iter()
+            .filter_map(|id| self.items.get(id))
+            .any(|item| item.is_light_source)
+    }
+
+    /// The barrier on `room`'s exit `direction` that stops the player right
+    /// now. A [`BarrierKind::Keyed`] one lets them by while they carry its key.
+    pub fn blocking_barrier<'a>(&self, room: &'a Room, direction: &str) -> Option<&'a Barrier> {
+        room.barrier(direction)
+            .filter(|b| !(b.kind == BarrierKind::Keyed && self.player.inventory.contains(&b.key)))
+    }
+
+    /// Where the exit `direction` from `room` leads, if it is usable at this time of day.
+    pub fn open_exit(&self, room: &Room, direction: &str) -> Option<i32> {
+        let direction = direction.to_lowercase();
+        let times = room.exit_times.get(&direction).map_or(&[][..], Vec::as_slice);
+        room.exits.get(&direction).copied()
+            .filter(|_| self.is_available(times) && self.blocking_barrier(room, &direction).is_none())
+    }
+
+    /// Every exit from the player's room open at this time of day, hidden
+    /// and barred ones included and flagged, in
+    /// [`DIRECTION_ORDER`](crate::directions::DIRECTION_ORDER). Empty in the dark.
+    pub fn available_exits(&self) -> Vec<ExitInfo> {
+        let Some(room) = self.get_current_room().filter(|room| !self.is_dark(room)) else {
+            return Vec::new();
+        };
+        let mut exits: Vec<ExitInfo> = room.exits.iter().map(|(dir, &to)| (dir, to, false))
+            .chain(room.hidden_exits.iter().map(|(dir, &to)| (dir, to, true)))
+            .filter(|(dir, _, _)| self.is_available(room.exit_times.get(*dir).map_or(&[][..], Vec::as_slice)))
+            .map(|(dir, to, hidden)| ExitInfo {
+                direction: dir.clone(),
+                destination: to,
+                barrier: self.blocking_barrier(room, dir).map(|b| b.name.clone()),
+                hidden,
+            })
+            .collect();
+        exits.sort_by(|a, b| direction_cmp(&a.direction, &b.direction));
+        exits
+    }
+
+    /// Directions the player can currently see and use from their room, in
+    /// [`DIRECTION_ORDER`](crate::directions::DIRECTION_ORDER). Empty in the dark.
+    pub fn visible_exits(&self) -> Vec<String> {
+        self.available_exits().into_iter()
+            .filter(|exit| !exit.hidden && !exit.is_locked())
+            .map(|exit| exit.direction)
             .collect()
     }
 
     pub fn look(&self) -> String {
-        let mut out = String::new();
+        self.look_lines().iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// The room description, tagged line by line for front-ends that style output.
+    pub fn look_lines(&self) -> Vec<OutputLine> {
+        let mut out = Vec::new();
 
         if let Some(room) = self.get_current_room() {
-            if room.is_dark {
-                return "It is pitch black. You can't see a thing.".to_string();
-            }
-
-            out.push('\n');
-            out.push_str(&room.name);
-            out.push('\n');
-            out.push_str(&"-".repeat(room.name.len()));
-            out.push('\n');
-            out.push_str(&room.description);
-
-            // Show exits
-            if !room.exits.is_empty() {
-                let mut exits: Vec<String> = room.exits.keys().cloned().collect();
-                exits.sort();
-                out.push_str(&format!("\n\nObvious exits: {}", exits.join(", ")));
+            if self.is_dark(room) {
+                return vec![OutputLine::new(LineKind::Description, self.messages("pitch_black", &[]))];
+            }
+
+            out.push(OutputLine::new(LineKind::System, ""));
+            out.push(OutputLine::new(LineKind::RoomTitle, room.name.clone()));
+            out.push(OutputLine::new(LineKind::RoomTitle, "-".repeat(room.name.len())));
+            out.push(OutputLine::new(LineKind::Description, room.description.clone()));
+            if self.settings.clock.show_time {
+                out.push(OutputLine::new(LineKind::Description, self.messages("time_of_day", &[("period", self.day_period())])));
+            }
+            out.push(OutputLine::new(LineKind::System, ""));
+
+            // Show exits, naming whatever bars the way
+            let exits: Vec<String> = self.available_exits().into_iter()
+                .filter(|exit| !exit.hidden)
+                .map(|exit| match exit.barrier {
+                    Some(barrier) => format!("{} ({})", exit.direction, barrier),
+                    None => exit.direction,
+                })
+                .collect();
+            if !exits.is_empty() {
+                out.push(OutputLine::new(LineKind::Exits, format!("Obvious exits: {}", exits.join(", "))));
             } else {
-                out.push_str("\n\nNo obvious exits.");
+                out.push(OutputLine::new(LineKind::Exits, "No obvious exits."));
             }
         } else {
-            out.push_str("You are in a void.");
+            out.push(OutputLine::new(LineKind::Description, "You are in a void."));
         }
 
         // Show items
         let items = self.get_items_in_room(self.player.current_room);
         if !items.is_empty() {
-            out.push_str("\n\nYou see:");
+            out.push(OutputLine::new(LineKind::System, ""));
+            out.push(OutputLine::new(LineKind::Item, "You see:"));
             for item in items {
-                out.push_str(&format!("\n  - {}", item.name));
+                out.push(OutputLine::new(LineKind::Item, format!("  - {}", item.name)));
             }
         }
 
         // Show monsters
         let monsters = self.get_monsters_in_room(self.player.current_room);
         if !monsters.is_empty() {
-            out.push_str("\n\nPresent:");
+            out.push(OutputLine::new(LineKind::System, ""));
+            out.push(OutputLine::new(LineKind::Description, "Present:"));
             for monster in monsters {
                 let status = match monster.friendliness {
                     MonsterStatus::Friendly => " (friendly)",
                     MonsterStatus::Hostile => " (hostile)",
                     MonsterStatus::Neutral => "",
                 };
-                out.push_str(&format!("\n  - {}{}", monster.name, status));
+                out.push(OutputLine::new(LineKind::Description, format!("  - {}{}", monster.name, status)));
             }
         }
 
@@ -422,91 +1372,499 @@ impl AdventureGame {
     }
 
     pub fn move_player(&mut self, direction: &str) -> Option<String> {
+        self.move_player_lines(direction)
+            .map(|lines| lines.into_iter().map(|line| line.text).collect::<Vec<_>>().join("\n"))
+    }
+
+    /// [`AdventureGame::move_player`], with the new room tagged line by line
+    /// as in [`AdventureGame::look_lines`].
+    pub fn move_player_lines(&mut self, direction: &str) -> Option<Vec<OutputLine>> {
         if let Some(room) = self.get_current_room()
-            && let Some(new_room_id) = room.get_exit(direction)
+            && let Some(new_room_id) = self.open_exit(room, direction)
             && self.rooms.contains_key(&new_room_id)
         {
-            self.player.current_room = new_room_id;
+            if self.is_dark(room) && self.rng.gen_bool(DARK_STUMBLE_CHANCE) {
+                self.turn_count += 1;
+                return Some(tagged(&self.messages("stumble_in_dark", &[]), LineKind::System));
+            }
+            self.log_event(LoggedEvent::Moved { from: self.player.current_room, to: new_room_id });
             self.turn_count += 1;
-            self.events.push(GameEvent::RoomEntered { room_id: new_room_id });
-            return Some(self.look());
+            self.enter_room(new_room_id);
+            self.player.last_exit_used = Some(direction.to_lowercase());
+            let mut lines = self.look_lines();
+            if let Some(trap) = self.spring_trap() {
+                lines.extend(tagged(&trap, LineKind::System));
+            }
+            return Some(lines);
         }
         None
     }
 
-    pub fn take_item(&mut self, item_name: &str) -> Result<String, String> {
-        const MAX_WEIGHT_PER_HARDINESS: i32 = 10;
-        let max_carry = self.player.hardiness * MAX_WEIGHT_PER_HARDINESS;
-        let current_weight: i32 = self.player.inventory.iter()
+    /// The first item the player carries that disarms traps, by id.
+    fn trap_tool(&self) -> Option<&Item> {
+        let mut tools: Vec<&Item> = self.player.inventory.iter()
             .filter_map(|id| self.items.get(id))
-            .map(|i| i.weight)
-            .sum();
+            .filter(|item| item.disarms_traps)
+            .collect();
+        tools.sort_by_key(|item| item.id);
+        tools.first().copied()
+    }
 
-        let matched = self.get_items_in_room(self.player.current_room)
-            .into_iter()
-            .find(|i| name_matches(&i.name, item_name) && i.is_takeable)
-            .map(|i| (i.id, i.name.clone(), i.weight));
+    /// Set off the current room's trap if it hasn't gone off yet, or disarm
+    /// it if the player carries a tool for that. Returns what happened.
+    pub fn spring_trap(&mut self) -> Option<String> {
+        let room = self.rooms.get_mut(&self.player.current_room)?;
+        if !room.has_trap || room.trap_sprung {
+            return None;
+        }
+        room.trap_sprung = true;
+        let damage = room.trap_damage;
+        if let Some(tool) = self.trap_tool() {
+            return Some(self.messages("trap_disarmed_with", &[("item", &tool.name)]));
+        }
+        Some(self.trap_hits(damage))
+    }
 
-        match matched {
-            None => Err("You can't take that.".to_string()),
-            Some((id, name, weight)) => {
-                if current_weight + weight > max_carry {
-                    return Err(format!(
-                        "Too heavy to carry! ({}/{} weight used, {} weighs {}.)",
-                        current_weight, max_carry, name, weight
-                    ));
-                }
-                self.player.inventory.push(id);
-                if let Some(item_ref) = self.items.get_mut(&id) {
-                    item_ref.location = 0;
-                }
-                self.events.push(GameEvent::ItemCollected { item_name: name.clone(), item_id: id });
-                self.turn_count += 1;
-                Ok(format!("Taken: {}.", name))
-            }
+    /// Take `damage` from a trap, dying if it is enough.
+    fn trap_hits(&mut self, damage: i32) -> String {
+        self.player.current_health -= damage;
+        let mut msg = self.messages("trap_sprung", &[
+            ("damage", &damage.to_string()),
+            ("health", &self.player.current_health.max(0).to_string()),
+            ("max", &self.player.hardiness.to_string()),
+        ]);
+        if self.player.current_health <= 0 {
+            msg.push('\n');
+            msg.push_str(&self.handle_player_death());
         }
+        msg
     }
 
-    /// Drop an item from inventory onto the floor. Returns the item name on success, or `None`.
-    pub fn drop_item(&mut self, item_name: &str) -> Option<String> {
-        let matched = self.player.inventory.iter().copied()
-            .find_map(|id| self.items.get(&id)
-                .filter(|i| name_matches(&i.name, item_name))
-                .map(|i| (id, i.name.clone())));
-        if let Some((item_id, name)) = matched {
-            self.player.inventory.retain(|&id| id != item_id);
-            if self.player.equipped_weapon == Some(item_id) { self.player.equipped_weapon = None; }
-            if self.player.equipped_armor == Some(item_id) { self.player.equipped_armor = None; }
-            if let Some(item_ref) = self.items.get_mut(&item_id) {
-                item_ref.location = self.player.current_room;
+    /// Try to disarm the trap in the room `direction` leads to. A trap tool
+    /// always works; otherwise it takes an agility check, and failing sets
+    /// the trap off on the player.
+    pub fn disarm_trap(&mut self, direction: &str) -> String {
+        let target = self.get_current_room()
+            .and_then(|room| self.open_exit(room, direction))
+            .filter(|id| self.rooms.get(id).is_some_and(|r| r.has_trap && !r.trap_sprung));
+        let Some(target) = target else {
+            return self.messages("no_trap", &[("direction", direction)]);
+        };
+        self.turn_count += 1;
+        let tool = self.trap_tool().map(|item| item.name.clone());
+        let disarmed = tool.is_some() || self.ability_check(Stat::Agility, TRAP_DISARM_DIFFICULTY);
+        let room = self.rooms.get_mut(&target).expect("checked above");
+        room.trap_sprung = true;
+        let damage = room.trap_damage;
+        match (disarmed, tool) {
+            (true, Some(tool)) => self.messages("trap_disarmed_with", &[("item", &tool)]),
+            (true, None) => self.messages("trap_disarmed", &[("direction", direction)]),
+            (false, _) => self.trap_hits(damage),
+        }
+    }
+
+    /// Why the player can't go `direction`, if a barrier is in the way.
+    pub fn barred_message(&self, direction: &str) -> Option<String> {
+        let barrier = self.blocking_barrier(self.get_current_room()?, direction)?;
+        Some(barrier.message.clone().unwrap_or_else(|| {
+            self.messages("exit_barred", &[("barrier", &barrier.name), ("direction", &direction.to_lowercase())])
+        }))
+    }
+
+    /// Search the current room, revealing hidden items and exits. Returns
+    /// the names of the items and directions of the exits found.
+    pub fn search_room(&mut self) -> (Vec<String>, Vec<String>) {
+        let room_id = self.player.current_room;
+        let mut found_items: Vec<String> = self.items.values_mut()
+            .filter(|item| item.location == room_id && item.hidden)
+            .map(|item| {
+                item.hidden = false;
+                item.name.clone()
+            })
+            .collect();
+        found_items.sort();
+
+        let mut found_exits = Vec::new();
+        if let Some(room) = self.rooms.get_mut(&room_id) {
+            room.searched = true;
+            for (direction, target) in std::mem::take(&mut room.hidden_exits) {
+                found_exits.push(direction.clone());
+                room.exits.insert(direction, target);
             }
-            self.turn_count += 1;
-            Some(name)
-        } else {
-            None
         }
+        found_exits.sort();
+        self.turn_count += 1;
+        (found_items, found_exits)
     }
 
-    /// Equip a weapon or wearable armor from inventory.
+    /// Search the open container called `name` in reach, revealing hidden
+    /// items inside it. Returns the names of the items found.
+    pub fn search_container(&mut self, name: &str) -> Result<Vec<String>, String> {
+        let id = self.reachable_container(name)?;
+        let container = &self.items[&id];
+        if !container.is_open {
+            return Err(self.messages("container_closed", &[("container", &container.name)]));
+        }
+        let mut found = Vec::new();
+        for item_id in container.contents.clone() {
+            if let Some(item) = self.items.get_mut(&item_id).filter(|item| item.hidden) {
+                item.hidden = false;
+                found.push(item.name.clone());
+            }
+        }
+        found.sort();
+        self.turn_count += 1;
+        Ok(found)
+    }
+
+    /// Pick up the item in the room named `item_name`. An exact
+    /// (case-insensitive) name wins; otherwise the shortest matching name
+    /// does, and identical items go lowest id first. Only differently named
+    /// items of the same length make the player choose.
+    pub fn take_item(&mut self, item_name: &str) -> Result<String, String> {
+        let mut candidates: Vec<(i32, String)> = self.get_items_in_room(self.player.current_room)
+            .into_iter()
+            .filter(|i| name_matches(&i.name, item_name) && i.is_takeable)
+            .map(|i| (i.id, i.name.clone()))
+            .collect();
+        if candidates.iter().any(|(_, name)| name.eq_ignore_ascii_case(item_name)) {
+            candidates.retain(|(_, name)| name.eq_ignore_ascii_case(item_name));
+        } else if let Some(shortest) = candidates.iter().map(|(_, name)| name.chars().count()).min() {
+            candidates.retain(|(_, name)| name.chars().count() == shortest);
+        }
+        candidates.sort_by_key(|(id, _)| *id);
+        let mut choices: Vec<String> = candidates.iter().map(|(_, name)| name.clone()).collect();
+        choices.sort();
+        choices.dedup();
+        if choices.len() > 1 {
+            let question = self.messages("which_one", &[("choices", &number_choices(&choices))]);
+            self.pending = Some(PendingAction::Choose { command: "take".to_string(), choices });
+            return Err(question);
+        }
+
+        match candidates.into_iter().next() {
+            None => Err(self.messages("cant_take", &[])),
+            Some((id, name)) => self.take_id(id, &name),
+        }
+    }
+
+    fn take_id(&mut self, id: i32, name: &str) -> Result<String, String> {
+        self.check_capacity(id)?;
+        self.player.inventory.push(id);
+        if let Some(item_ref) = self.items.get_mut(&id) {
+            item_ref.location = 0;
+        }
+        self.events.push(GameEvent::ItemCollected { item_name: name.to_string(), item_id: id });
+        self.log_event(LoggedEvent::Took { item_id: id });
+        self.turn_count += 1;
+        Ok(self.messages("taken", &[("item", name)]))
+    }
+
+    /// Take each item in `names`, or everything takeable in the room when
+    /// `names` is just "all", as a single turn. Returns one line per item.
+    pub fn take_items(&mut self, names: &[String]) -> Vec<String> {
+        let turn = self.turn_count;
+        let lines = if is_all(names) {
+            let mut takeable: Vec<(i32, String)> = self.get_items_in_room(self.player.current_room)
+                .into_iter()
+                .filter(|i| i.is_takeable)
+                .map(|i| (i.id, i.name.clone()))
+                .collect();
+            takeable.sort_by_key(|(id, _)| *id);
+            if takeable.is_empty() {
+                vec![self.messages("nothing_to_take", &[])]
+            } else {
+                takeable.iter().map(|(id, name)| self.take_id(*id, name).unwrap_or_else(|e| e)).collect()
+            }
+        } else {
+            names.iter().map(|name| self.take_item(name).unwrap_or_else(|e| e)).collect()
+        };
+        self.turn_count = self.turn_count.min(turn + 1);
+        lines
+    }
+
+    /// Drop each item in `names`, or the whole inventory when `names` is
+    /// just "all", as a single turn. Returns one line per item.
+    pub fn drop_items(&mut self, names: &[String]) -> Vec<String> {
+        let turn = self.turn_count;
+        let lines = if is_all(names) {
+            if self.player.inventory.is_empty() {
+                vec![self.messages("inventory_empty", &[])]
+            } else {
+                self.player.inventory.clone().into_iter()
+                    .map(|id| {
+                        let name = self.drop_id(id);
+                        self.messages("dropped", &[("item", &name)])
+                    })
+                    .collect()
+            }
+        } else {
+            names.iter()
+                .map(|name| match self.drop_item(name) {
+                    Ok(name) => self.messages("dropped", &[("item", &name)]),
+                    Err(err) => err,
+                })
+                .collect()
+        };
+        self.turn_count = self.turn_count.min(turn + 1);
+        lines
+    }
+
+    /// The container called `name` in the room or in the player's inventory.
+    fn reachable_container(&self, name: &str) -> Result<i32, String> {
+        let mut found: Vec<&Item> = self.get_items_in_room(self.player.current_room).into_iter()
+            .chain(self.player.inventory.iter().filter_map(|id| self.items.get(id)))
+            .filter(|i| i.item_type == ItemType::Container && name_matches(&i.name, name))
+            .collect();
+        found.sort_by_key(|i| i.id);
+        found.first().map(|i| i.id).ok_or_else(|| self.messages("no_container", &[("name", name)]))
+    }
+
+    /// What the player sees inside container `id`, if it is open.
+    fn describe_contents(&self, id: i32) -> String {
+        let Some(container) = self.items.get(&id) else {
+            return String::new();
+        };
+        if !container.is_open {
+            return self.messages("container_closed", &[("container", &container.name)]);
+        }
+        let names: Vec<&str> = container.contents.iter()
+            .filter_map(|id| self.items.get(id))
+            .filter(|i| !i.hidden)
+            .map(|i| i.name.as_str())
+            .collect();
+        if names.is_empty() {
+            self.messages("container_empty", &[("container", &container.name)])
+        } else {
+            self.messages("container_holds", &[("container", &container.name), ("items", &names.join(", "))])
+        }
+    }
+
+    /// What is inside a container in reach, if it is open.
+    pub fn look_in(&self, name: &str) -> Result<String, String> {
+        Ok(self.describe_contents(self.reachable_container(name)?))
+    }
+
+    /// Open a container in reach, showing what is inside.
+    pub fn open_container(&mut self, name: &str) -> Result<String, String> {
+        let id = self.reachable_container(name)?;
+        let container = self.items.get_mut(&id).expect("reachable_container found it");
+        let container_name = container.name.clone();
+        if container.is_open {
+            return Err(self.messages("already_open", &[("container", &container_name)]));
+        }
+        container.is_open = true;
+        self.turn_count += 1;
+        Ok(format!("{}\n{}", self.messages("opened", &[("container", &container_name)]), self.describe_contents(id)))
+    }
+
+    /// Close a container in reach, hiding its contents.
+    pub fn close_container(&mut self, name: &str) -> Result<String, String> {
+        let id = self.reachable_container(name)?;
+        let container = self.items.get_mut(&id).expect("reachable_container found it");
+        let container_name = container.name.clone();
+        if !container.is_open {
+            return Err(self.messages("already_closed", &[("container", &container_name)]));
+        }
+        container.is_open = false;
+        self.turn_count += 1;
+        Ok(self.messages("closed", &[("container", &container_name)]))
+    }
+
+    /// Move a carried item into an open container in reach.
+    pub fn put_in_container(&mut self, item_name: &str, container_name: &str) -> Result<String, String> {
+        let container_id = self.reachable_container(container_name)?;
+        let Some(item_id) = self.inventory_item(item_name, &format!("put {{}} in {}", container_name))? else {
+            return Err(self.messages("dont_have_that", &[]));
+        };
+        let container = &self.items[&container_id];
+        if !container.is_open {
+            return Err(self.messages("container_closed", &[("container", &container.name)]));
+        }
+        if item_id == container_id {
+            return Err(self.messages("cant_put_in_itself", &[]));
+        }
+        let container_name = container.name.clone();
+        self.player.inventory.retain(|&id| id != item_id);
+        if self.player.equipped_weapon == Some(item_id) { self.player.equipped_weapon = None; }
+        if self.player.equipped_armor == Some(item_id) { self.player.equipped_armor = None; }
+        let item = self.items.get_mut(&item_id).expect("carried items exist");
+        item.location = IN_CONTAINER;
+        let item_name = item.name.clone();
+        self.items.get_mut(&container_id).expect("checked above").contents.push(item_id);
+        self.log_event(LoggedEvent::Dropped { item_id });
+        self.turn_count += 1;
+        Ok(self.messages("put_in", &[("item", &item_name), ("container", &container_name)]))
+    }
+
+    /// Take an item out of an open container in reach.
+    pub fn take_from_container(&mut self, item_name: &str, container_name: &str) -> Result<String, String> {
+        let container_id = self.reachable_container(container_name)?;
+        let container = &self.items[&container_id];
+        if !container.is_open {
+            return Err(self.messages("container_closed", &[("container", &container.name)]));
+        }
+        let mut inside: Vec<&Item> = container.contents.iter()
+            .filter_map(|id| self.items.get(id))
+            .filter(|i| !i.hidden && name_matches(&i.name, item_name))
+            .collect();
+        inside.sort_by_key(|i| i.id);
+        let Some((item_id, name)) = inside.first().map(|i| (i.id, i.name.clone())) else {
+            return Err(self.messages("not_in_container", &[("item", item_name), ("container", &container.name)]));
+        };
+        self.check_capacity(item_id)?;
+        self.items.get_mut(&container_id).expect("checked above").contents.retain(|&id| id != item_id);
+        self.items.get_mut(&item_id).expect("contents exist").location = 0;
+        self.player.inventory.push(item_id);
+        self.events.push(GameEvent::ItemCollected { item_name: name.clone(), item_id });
+        self.log_event(LoggedEvent::Took { item_id });
+        self.turn_count += 1;
+        Ok(self.messages("taken", &[("item", &name)]))
+    }
+
+    /// Look up a carried item for `command`. When several items match, asks
+    /// the player which one (see [`PendingAction`]) and returns the question as the error.
+    fn inventory_item(&mut self, item_name: &str, command: &str) -> Result<Option<i32>, String> {
+        match self.player.find_inventory_item(item_name, &self.items) {
+            InventoryMatch::Unique(id) => Ok(Some(id)),
+            InventoryMatch::None => Ok(None),
+            InventoryMatch::Ambiguous(ids) => {
+                let choices: Vec<String> = ids.iter().filter_map(|id| self.items.get(id)).map(|i| i.name.clone()).collect();
+                let question = self.messages("which_one", &[("choices", &number_choices(&choices))]);
+                self.pending = Some(PendingAction::Choose { command: command.to_string(), choices });
+                Err(question)
+            }
+        }
+    }
+
+    /// Combine two carried items using the adventure's recipes, consuming
+    /// both and adding the result to the inventory. Returns the result's name.
+    pub fn combine_items(&mut self, first: &str, second: &str) -> Result<String, String> {
+        let Some(a) = self.inventory_item(first, &format!("combine {{}} with {}", second))? else {
+            return Err(self.messages("dont_have_item", &[("item", first)]));
+        };
+        let Some(b) = self.inventory_item(second, &format!("combine {} with {{}}", first))?.filter(|&b| b != a) else {
+            return Err(self.messages("dont_have_item", &[("item", second)]));
+        };
+        let output = self.recipes.iter()
+            .find(|recipe| recipe.uses(a, b))
+            .map(|recipe| recipe.output)
+            .filter(|id| self.items.contains_key(id))
+            .ok_or_else(|| self.messages("nothing_happens", &[]))?;
+
+        for id in [a, b] {
+            self.player.inventory.retain(|&i| i != id);
+            if self.player.equipped_weapon == Some(id) { self.player.equipped_weapon = None; }
+            if self.player.equipped_armor == Some(id) { self.player.equipped_armor = None; }
+            self.discard_item(id);
+        }
+        self.player.inventory.push(output);
+        let item = self.items.get_mut(&output).expect("recipe output checked above");
+        item.location = 0;
+        item.hidden = false;
+        let name = item.name.clone();
+        self.events.push(GameEvent::ItemCollected { item_name: name.clone(), item_id: output });
+        self.turn_count += 1;
+        Ok(name)
+    }
+
+    /// Drop an item from inventory onto the floor. Returns the item name on success.
+    pub fn drop_item(&mut self, item_name: &str) -> Result<String, String> {
+        let matched = self.inventory_item(item_name, "drop")?
+            .and_then(|id| self.items.get(&id))
+            .map(|i| (i.id, i.name.clone()));
+        match matched {
+            Some((item_id, _)) => Ok(self.drop_id(item_id)),
+            None => Err(self.messages("dont_have_that", &[])),
+        }
+    }
+
+    /// Drop inventory item `item_id` in the current room, returning its name.
+    fn drop_id(&mut self, item_id: i32) -> String {
+        self.player.inventory.retain(|&id| id != item_id);
+        if self.player.equipped_weapon == Some(item_id) { self.player.equipped_weapon = None; }
+        if self.player.equipped_armor == Some(item_id) { self.player.equipped_armor = None; }
+        let name = match self.items.get_mut(&item_id) {
+            Some(item_ref) => {
+                item_ref.location = self.player.current_room;
+                item_ref.name.clone()
+            }
+            None => String::new(),
+        };
+        self.log_event(LoggedEvent::Dropped { item_id });
+        self.turn_count += 1;
+        name
+    }
+
+    /// Equip a weapon or wearable armor from inventory.
     pub fn equip_item(&mut self, item_name: &str) -> Result<String, String> {
-        let matched = self.player.inventory.iter().copied().find_map(|id| {
-            self.items.get(&id)
-                .filter(|i| name_matches(&i.name, item_name)
-                    && (i.is_weapon || i.is_wearable || i.is_armor))
-                .map(|i| (i.id, i.name.clone(), i.is_weapon))
-        });
+        let matched = self.inventory_item(item_name, "equip")?
+            .filter(|id| self.items.get(id).is_some_and(|i| i.is_weapon || i.is_wearable || i.is_armor));
         match matched {
             None => Err(format!("You don't have a weapon or armor called '{}'.", item_name)),
-            Some((id, name, is_weapon)) => {
-                if is_weapon {
-                    self.player.equipped_weapon = Some(id);
-                    Ok(format!("You wield the {}.", name))
-                } else {
-                    self.player.equipped_armor = Some(id);
-                    Ok(format!("You wear the {}.", name))
-                }
+            Some(id) => self.equip_id(id),
+        }
+    }
+
+    /// Put on a piece of armor from inventory. Anything that isn't armor is refused.
+    pub fn wear_item(&mut self, item_name: &str) -> Result<String, String> {
+        let matched = self.inventory_item(item_name, "wear")?.and_then(|id| self.items.get(&id));
+        match matched {
+            None => Err(format!("You don't have '{}'.", item_name)),
+            Some(item) if !item.is_armor => Err(format!("You can't wear the {}.", item.name)),
+            Some(item) => self.equip_id(item.id),
+        }
+    }
+
+    /// Take off worn armor by name, returning it to the inventory.
+    pub fn remove_item(&mut self, item_name: &str) -> Result<String, String> {
+        let worn = self.player.equipped_armor
+            .and_then(|id| self.items.get(&id))
+            .filter(|i| name_matches(&i.name, item_name))
+            .map(|i| i.id);
+        match worn {
+            None => Err(format!("You aren't wearing '{}'.", item_name)),
+            Some(_) => self.unequip_slot("armor"),
+        }
+    }
+
+    /// Equip the inventory item `id` into its slot, swapping out what was there.
+    /// Worn armor is marked with location -1.
+    fn equip_id(&mut self, id: i32) -> Result<String, String> {
+        let Some(item) = self.items.get(&id) else {
+            return Err("You don't have that.".to_string());
+        };
+        let (name, is_weapon) = (item.name.clone(), item.is_weapon);
+        if item.is_broken() {
+            return Err(format!("The {} is broken.", name));
+        }
+
+        let too_weak = item.min_hardiness.is_some_and(|min| self.player.hardiness < min);
+        let too_green = item.min_level.is_some_and(|min| self.player.level < min);
+        if too_weak || too_green {
+            return Err(if is_weapon {
+                "You aren't strong enough to wield that.".to_string()
+            } else {
+                "You aren't strong enough to wear that.".to_string()
+            });
+        }
+
+        let slot = if is_weapon { &mut self.player.equipped_weapon } else { &mut self.player.equipped_armor };
+        let previous = slot.replace(id).filter(|&prev| prev != id);
+        if !is_weapon {
+            if let Some(prev) = previous.and_then(|prev| self.items.get_mut(&prev)) {
+                prev.location = 0;
+            }
+            if let Some(item) = self.items.get_mut(&id) {
+                item.location = -1;
             }
         }
+        let previous = previous.and_then(|prev| self.items.get(&prev)).map(|prev| prev.name.clone());
+        let verb = if is_weapon { "wield" } else { "wear" };
+        Ok(match previous {
+            Some(prev) => format!("You put away the {} and {} the {}.", prev, verb, name),
+            None => format!("You {} the {}.", verb, name),
+        })
     }
 
     /// Unequip by slot name: "weapon" or "armor".
@@ -520,10 +1878,19 @@ impl AdventureGame {
                 }
             }
             "armor" => {
-                if self.player.equipped_armor.take().is_some() {
-                    Ok("Armor removed.".to_string())
-                } else {
-                    Err("No armor equipped.".to_string())
+                match self.player.equipped_armor.take() {
+                    Some(id) => {
+                        let item = self.items.get_mut(&id);
+                        let name = item.map(|i| {
+                            i.location = 0;
+                            i.name.clone()
+                        });
+                        Ok(match name {
+                            Some(name) => format!("You take off the {}.", name),
+                            None => "Armor removed.".to_string(),
+                        })
+                    }
+                    None => Err("No armor equipped.".to_string()),
                 }
             }
             _ => Err("Specify 'weapon' or 'armor'.".to_string()),
@@ -532,11 +1899,9 @@ impl AdventureGame {
 
     /// Use a consumable or readable item from inventory.
     pub fn use_item(&mut self, item_name: &str) -> Result<String, String> {
-        let matched = self.player.inventory.iter().copied().find_map(|id| {
-            self.items.get(&id)
-                .filter(|i| name_matches(&i.name, item_name))
-                .map(|i| (i.id, i.name.clone(), i.item_type.clone(), i.description.clone(), i.value))
-        });
+        let matched = self.inventory_item(item_name, "use")?
+            .and_then(|id| self.items.get(&id))
+            .map(|i| (i.id, i.name.clone(), i.item_type.clone(), i.description.clone(), i.value));
         match matched {
             None => Err(format!("You don't have '{}'.", item_name)),
             Some((id, name, item_type, description, value)) => {
@@ -547,7 +1912,7 @@ impl AdventureGame {
                         self.player.current_health = after;
                         self.player.inventory.retain(|&i| i != id);
                         // Remove consumed item from the world entirely
-                        self.items.remove(&id);
+                        self.discard_item(id);
                         self.events.push(GameEvent::ItemUsed { item_name: name.clone() });
                         self.turn_count += 1;
                         format!("You consume the {}. Health: {}/{}.", name, after, self.player.hardiness)
@@ -564,14 +1929,73 @@ impl AdventureGame {
         }
     }
 
+    /// Use a carried item on something else: a barrier on one of the room's
+    /// exits, a friendly monster, ... Each handler in the use-on table is
+    /// tried in turn; pairs none of them handle don't work.
+    pub fn use_item_on(&mut self, item_name: &str, target: &str) -> Result<String, String> {
+        let Some(item_id) = self.inventory_item(item_name, &format!("use {{}} on {}", target))? else {
+            return Err(self.messages("dont_have_item", &[("item", item_name)]));
+        };
+        USE_ON_HANDLERS.iter()
+            .find_map(|handler| handler(self, item_id, target))
+            .ok_or_else(|| self.messages("doesnt_work", &[]))
+    }
+
+    /// Key on the barrier it opens: "use key on door", "use torch on north web".
+    fn clear_barrier(&mut self, item_id: i32, target: &str) -> Option<String> {
+        let target = target.to_lowercase();
+        let room = self.rooms.get_mut(&self.player.current_room)?;
+        let (direction, barrier) = room.barriers.iter_mut().find(|(direction, barrier)| {
+            !barrier.cleared
+                && barrier.key == item_id
+                && (target == **direction
+                    || name_matches(&barrier.name, &target)
+                    // "north door"
+                    || target.strip_prefix(direction.as_str())
+                        .and_then(|rest| rest.strip_prefix(' '))
+                        .is_some_and(|rest| name_matches(&barrier.name, rest)))
+        })?;
+        barrier.cleared = true;
+        let (direction, name, kind) = (direction.clone(), barrier.name.clone(), barrier.kind);
+        let item = self.items.get(&item_id).map(|i| i.name.clone()).unwrap_or_default();
+        self.turn_count += 1;
+        let key = match kind {
+            BarrierKind::Locked | BarrierKind::Keyed => "unlocked",
+            BarrierKind::Flammable => "burned_away",
+        };
+        Some(self.messages(key, &[("item", &item), ("barrier", &name), ("direction", &direction)]))
+    }
+
+    /// Food or drink on a friendly monster heals it as it would the player.
+    fn heal_ally(&mut self, item_id: i32, target: &str) -> Option<String> {
+        let item = self.items.get(&item_id)
+            .filter(|i| matches!(i.item_type, ItemType::Edible | ItemType::Drinkable))?;
+        let (item_name, heal) = (item.name.clone(), item.value.clamp(1, 20));
+        let ally_id = self.get_monsters_in_room(self.player.current_room).iter()
+            .find(|m| m.friendliness != MonsterStatus::Hostile && name_matches(&m.name, target))
+            .map(|m| m.id)?;
+        let ally = self.monsters.get_mut(&ally_id)?;
+        ally.current_health = (ally.current_health + heal).min(ally.hardiness);
+        let (ally_name, health, hardiness) = (ally.name.clone(), ally.current_health, ally.hardiness);
+        self.player.inventory.retain(|&i| i != item_id);
+        self.discard_item(item_id);
+        self.events.push(GameEvent::ItemUsed { item_name: item_name.clone() });
+        self.turn_count += 1;
+        Some(self.messages("healed_ally", &[
+            ("item", &item_name),
+            ("monster", &ally_name),
+            ("health", &health.to_string()),
+            ("max", &hardiness.to_string()),
+        ]))
+    }
+
     /// Return details about an item in inventory or current room.
     pub fn examine_item(&self, item_name: &str) -> Option<String> {
-        let in_inventory = self.player.inventory.iter().copied()
-            .find_map(|id| self.items.get(&id)
-                .filter(|i| name_matches(&i.name, item_name)));
         let in_room = self.get_items_in_room(self.player.current_room).into_iter()
             .find(|i| name_matches(&i.name, item_name));
-        let item = in_inventory.or(in_room)?;
+        let item = in_room.or_else(|| self.player.inventory.iter().copied()
+            .find_map(|id| self.items.get(&id)
+                .filter(|i| name_matches(&i.name, item_name))))?;
 
         let mut msg = format!("{}\n{}", item.name, item.description);
         if item.is_weapon {
@@ -580,24 +2004,547 @@ impl AdventureGame {
         if item.is_armor {
             msg.push_str(&format!("\nArmor value: {}", item.armor_value));
         }
+        if let (Some(left), Some(max)) = (item.durability, item.max_durability) {
+            msg.push_str(&format!("\nDurability: {}/{}", left, max));
+        }
         msg.push_str(&format!("\nWeight: {}  Value: {} gold", item.weight, item.value));
+        if item.item_type == ItemType::Container {
+            msg.push('\n');
+            msg.push_str(&self.describe_contents(item.id));
+        }
         Some(msg)
     }
 
-    /// (current carried weight, max carry weight)
+    /// Describe whatever `name` refers to: an item in the room, then one
+    /// being carried, then a monster present.
+    pub fn examine(&self, name: &str) -> Option<String> {
+        self.examine_item(name).or_else(|| {
+            let mut present = self.get_monsters_in_room(self.player.current_room);
+            present.sort_by_key(|m| m.id);
+            present.into_iter()
+                .find(|m| name_matches(&m.name, name))
+                .map(|m| format!("{}\n{}", m.name, m.description))
+        })
+    }
+
+    /// Check whether the item `item_id` fits under the active inventory limit.
+    fn check_capacity(&self, item_id: i32) -> Result<(), String> {
+        let Some(item) = self.items.get(&item_id) else {
+            return Ok(());
+        };
+        match self.settings.inventory.mode {
+            InventoryMode::Weight => {
+                let (current_weight, max_carry) = self.carry_weight();
+                if current_weight + item.weight > max_carry {
+                    return Err(self.messages("too_heavy", &[]));
+                }
+            }
+            InventoryMode::Slots => {
+                let (used, max) = self.slot_usage();
+                let stacks = self.player.inventory.iter()
+                    .filter_map(|id| self.items.get(id))
+                    .any(|i| i.name == item.name);
+                if !stacks && used >= max {
+                    return Err(format!("You have no free inventory slots ({}/{} used).", used, max));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// (used slots, max slots). Identical items share a slot.
+    pub fn slot_usage(&self) -> (usize, usize) {
+        let used = self.player.inventory.iter()
+            .filter_map(|id| self.items.get(id))
+            .map(|i| i.name.as_str())
+            .collect::<HashSet<_>>()
+            .len();
+        (used, self.settings.inventory.max_slots)
+    }
+
+    /// Carried items in the player's chosen [`InventorySort`] order; when
+    /// the listing is grouped, items are ordered by type first.
+    pub fn sorted_inventory(&self) -> Vec<&Item> {
+        let mut carried: Vec<&Item> = self.player.inventory.iter().filter_map(|id| self.items.get(id)).collect();
+        match self.player.inventory_sort {
+            InventorySort::None => {}
+            InventorySort::Name => carried.sort_by_key(|item| item.name.to_lowercase()),
+            InventorySort::Type => carried.sort_by(|a, b| a.item_type.cmp(&b.item_type).then_with(|| a.name.cmp(&b.name))),
+            InventorySort::Value => carried.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.name.cmp(&b.name))),
+            InventorySort::Weight => carried.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.name.cmp(&b.name))),
+        }
+        if self.player.inventory_grouped {
+            carried.sort_by(|a, b| a.item_type.cmp(&b.item_type));
+        }
+        carried
+    }
+
+    /// (current carried weight, max carry weight).
     pub fn carry_weight(&self) -> (i32, i32) {
-        let current: i32 = self.player.inventory.iter()
+        (self.current_carry_weight(), self.max_carry_weight())
+    }
+
+    /// Total weight of the items the player is carrying, plus their gold
+    /// when `economy.gold_weight` is set.
+    pub fn current_carry_weight(&self) -> i32 {
+        let items: i32 = self.player.inventory.iter()
             .filter_map(|id| self.items.get(id))
             .map(|i| i.weight)
             .sum();
-        (current, self.player.hardiness * 10)
+        items + self.gold_weight()
+    }
+
+    /// Most weight the player can carry: ten per point of hardiness.
+    pub fn max_carry_weight(&self) -> i32 {
+        self.player.hardiness * 10
+    }
+
+    /// Weight of the gold the player is carrying.
+    pub fn gold_weight(&self) -> i32 {
+        self.player.gold * self.settings.economy.gold_weight / 100
+    }
+
+    /// Move `amount` gold (all carried gold if `None`) into the bank.
+    /// Only possible in a safe zone. Returns the amount deposited.
+    pub fn deposit_gold(&mut self, amount: Option<i32>) -> Result<i32, String> {
+        if !self.get_current_room().is_some_and(|r| r.is_safe_zone) {
+            return Err(self.messages("no_bank_here", &[]));
+        }
+        let amount = amount.unwrap_or(self.player.gold);
+        if amount <= 0 || amount > self.player.gold {
+            return Err(self.messages("not_enough_gold", &[("gold", &self.player.gold.to_string())]));
+        }
+        self.player.gold -= amount;
+        self.player.banked_gold += amount;
+        Ok(amount)
+    }
+
+    /// Move `amount` gold (everything banked if `None`) back into the player's purse.
+    pub fn withdraw_gold(&mut self, amount: Option<i32>) -> Result<i32, String> {
+        if !self.get_current_room().is_some_and(|r| r.is_safe_zone) {
+            return Err(self.messages("no_bank_here", &[]));
+        }
+        let amount = amount.unwrap_or(self.player.banked_gold);
+        if amount <= 0 || amount > self.player.banked_gold {
+            return Err(self.messages("not_enough_banked", &[("gold", &self.player.banked_gold.to_string())]));
+        }
+        self.player.banked_gold -= amount;
+        self.player.gold += amount;
+        Ok(amount)
+    }
+
+    /// Wear down the equipped item `item_id` by one use. When it breaks it is
+    /// unequipped and the returned message says so.
+    pub fn wear_equipment(&mut self, item_id: i32) -> Option<String> {
+        let item = self.items.get_mut(&item_id)?;
+        let left = item.durability.as_mut()?;
+        *left = (*left - 1).max(0);
+        if *left > 0 {
+            return None;
+        }
+        let name = item.name.clone();
+        if self.player.equipped_weapon == Some(item_id) {
+            self.player.equipped_weapon = None;
+        }
+        if self.player.equipped_armor == Some(item_id) {
+            self.player.equipped_armor = None;
+            item.location = 0;
+        }
+        Some(format!("Your {} breaks!", name))
+    }
+
+    /// Restore a carried item's durability for [`REPAIR_COST_PER_POINT`] gold
+    /// per point. Needs a safe zone or a smith (a monster with the "smith"
+    /// ability) willing to trade. Returns the item's name and the gold spent.
+    pub fn repair_item(&mut self, item_name: &str) -> Result<(String, i32), String> {
+        let safe = self.get_current_room().is_some_and(|r| r.is_safe_zone);
+        let smith = self.get_monsters_in_room(self.player.current_room).iter()
+            .any(|m| m.has_ability("smith") && self.will_trade_with(m.id));
+        if !safe && !smith {
+            return Err(self.messages("no_smith_here", &[]));
+        }
+        let Some(id) = self.inventory_item(item_name, "repair")? else {
+            return Err(self.messages("dont_have_item", &[("item", item_name)]));
+        };
+        let item = &self.items[&id];
+        let name = item.name.clone();
+        let missing = match (item.durability, item.max_durability) {
+            (Some(left), Some(max)) if left < max => max - left,
+            _ => return Err(self.messages("not_damaged", &[("item", &name)])),
+        };
+        let cost = missing * REPAIR_COST_PER_POINT;
+        if cost > self.player.gold {
+            return Err(self.messages("repair_cost", &[
+                ("item", &name),
+                ("cost", &cost.to_string()),
+                ("gold", &self.player.gold.to_string()),
+            ]));
+        }
+        self.player.gold -= cost;
+        let item = self.items.get_mut(&id).expect("looked up above");
+        item.durability = item.max_durability;
+        self.turn_count += 1;
+        Ok((name, cost))
+    }
+
+    /// The player's full character sheet, one line per entry, for any UI to render.
+    pub fn character_sheet(&self) -> Vec<String> {
+        let p = &self.player;
+        let weapon = p.equipped_weapon
+            .and_then(|id| self.items.get(&id))
+            .map(|w| format!("{} ({}d{})", w.name, w.weapon_dice, w.weapon_sides))
+            .unwrap_or_else(|| "none".to_string());
+        let armor = p.equipped_armor
+            .and_then(|id| self.items.get(&id))
+            .map(|a| format!("{} (armor {})", a.name, a.armor_value))
+            .unwrap_or_else(|| "none".to_string());
+        let (carry_cur, carry_max) = self.carry_weight();
+        let location = self.get_current_room()
+            .map(|r| r.name.clone())
+            .unwrap_or_else(|| format!("Room {}", p.current_room));
+        let mut effects: Vec<String> = p.status_effects.iter()
+            .map(|(name, turns)| format!("{} ({} turns)", name, turns))
+            .collect();
+        effects.sort();
+
+        vec![
+            format!("Name:      {}", p.name),
+            format!("Level:     {}  (XP {}/{})", p.level, p.experience_points, p.level * crate::systems::combat::XP_PER_LEVEL),
+            format!("Health:    {} {}/{}", health_bar(p.current_health, p.hardiness), p.current_health, p.hardiness),
+            format!("Hardiness: {}  Agility: {}  Charisma: {}", p.hardiness, p.agility, p.charisma),
+            format!("Gold:      {}  (banked {})", p.gold, p.banked_gold),
+            format!("Weapon:    {}", weapon),
+            format!("Armor:     {}", armor),
+            format!("Carrying:  {}/{} weight", carry_cur, carry_max),
+            format!("Effects:   {}", if effects.is_empty() { "none".to_string() } else { effects.join(", ") }),
+            format!("Location:  {}", location),
+        ]
+    }
+
+    /// Whether monster `monster_id` is willing to trade with the player: it
+    /// must not be hostile, and the player's standing with its faction (if
+    /// any) must be at least [`TRADE_STANDING`].
+    pub fn will_trade_with(&self, monster_id: i32) -> bool {
+        self.monsters.get(&monster_id).is_some_and(|m| {
+            m.friendliness != MonsterStatus::Hostile
+                && m.faction.as_deref().is_none_or(|f| self.player.standing(f) >= TRADE_STANDING)
+        })
+    }
+
+    /// A random line from monster `monster_id`'s barks, ready to print.
+    /// `None` if it has nothing to say.
+    pub fn bark(&mut self, monster_id: i32) -> Option<String> {
+        let monster = self.monsters.get(&monster_id)?;
+        if monster.barks.is_empty() {
+            return None;
+        }
+        let line = &monster.barks[self.rng.gen_range(0..monster.barks.len())];
+        Some(self.message_table.format("bark", &[("monster", &monster.name), ("line", line)]))
+    }
+
+    /// Record `event` in the event log against the current turn.
+    pub fn log_event(&mut self, event: LoggedEvent) {
+        self.event_log.push(self.turn_count, event);
+    }
+
+    /// The last `n` logged events, oldest first.
+    pub fn recent_events(&self, n: usize) -> Vec<&LogEntry> {
+        self.event_log.recent(n)
+    }
+
+    /// Combined value of the treasures that count towards the score: those
+    /// lying in the trophy room, or those carried if the adventure has none.
+    pub fn treasure_score(&self) -> i32 {
+        let scoring_location = self.settings.score.trophy_room.unwrap_or(0);
+        self.items.values()
+            .filter(|item| item.item_type == ItemType::Treasure && item.location == scoring_location)
+            .map(|item| item.value)
+            .sum()
+    }
+
+    /// The player's score, also recorded in the hall of fame: treasure value
+    /// (see [`AdventureGame::treasure_score`]) plus points for each completed
+    /// quest, less a penalty for every death.
+    pub fn compute_score(&self) -> i32 {
+        self.treasure_score()
+            + self.player.quests_completed * self.settings.score.quest_points
+            - self.player.deaths * DEATH_SCORE_PENALTY
+    }
+
+    /// The best score the adventure allows: every treasure delivered and every quest completed.
+    pub fn max_score(&self) -> i32 {
+        let treasure: i32 = self.items.values()
+            .filter(|item| item.item_type == ItemType::Treasure)
+            .map(|item| item.value)
+            .sum();
+        treasure + self.quests.len() as i32 * self.settings.score.quest_points
+    }
+
+    /// Place the player in `room_id`, announcing it to other systems and
+    /// remembering it as the respawn point if it is a checkpoint.
+    pub(crate) fn enter_room(&mut self, room_id: i32) {
+        self.player.current_room = room_id;
+        if self.rooms.get(&room_id).is_some_and(|r| r.is_checkpoint) {
+            self.respawn_room = room_id;
+        }
+        self.events.push(GameEvent::RoomEntered { room_id });
+    }
+
+    /// Apply the adventure's death behavior once the player's health hits zero.
+    /// Returns the text to show after the killing blow.
+    pub fn handle_player_death(&mut self) -> String {
+        let behavior = self.settings.gameplay.death;
+        if behavior == DeathBehavior::End {
+            self.game_over = true;
+            return "You have been slain!".to_string();
+        }
+
+        let mut msg = "You have been slain!".to_string();
+        if behavior == DeathBehavior::Drop && !self.player.inventory.is_empty() {
+            let death_room = self.player.current_room;
+            for id in std::mem::take(&mut self.player.inventory) {
+                if let Some(item) = self.items.get_mut(&id) {
+                    item.location = death_room;
+                }
+            }
+            self.player.equipped_weapon = None;
+            self.player.equipped_armor = None;
+            msg.push_str(" Your belongings fall where you lie.");
+        }
+
+        self.player.deaths += 1;
+        self.player.status_effects.clear();
+        self.player.current_health = (self.player.hardiness / 2).max(1);
+        self.turn_count += RESPAWN_TURN_PENALTY;
+        self.player.current_room = self.respawn_room;
+        self.player.last_exit_used = None;
+        let room_name = self.get_current_room().map(|r| r.name.clone()).unwrap_or_default();
+        msg.push_str(&format!(
+            "\nYou awaken in {} with {}/{} health. (-{} score)",
+            room_name, self.player.current_health, self.player.hardiness, DEATH_SCORE_PENALTY
+        ));
+        msg
     }
 
     pub fn add_system(&mut self, system: Box<dyn System>) {
+        self.registry.push((system.name(), system.commands(), system.dependencies()));
         self.systems.push(system);
     }
 
+    /// Every command the enabled systems handle, in registration order.
+    pub fn grammar(&self) -> Vec<CommandSpec> {
+        self.registry.iter()
+            .filter(|(name, _, _)| self.system_enabled(name))
+            .flat_map(|(_, commands, _)| commands.iter().cloned())
+            .filter(|spec| !spec.debug || self.settings.gameplay.debug_commands)
+            .collect()
+    }
+
+    /// The registered systems by name, in registration order, with whether each is on.
+    pub fn system_states(&self) -> Vec<(&'static str, bool)> {
+        self.registry.iter().map(|&(name, _, _)| (name, self.system_enabled(name))).collect()
+    }
+
+    /// Whether the system called `name` takes part in play; see `settings.systems`.
+    pub fn system_enabled(&self, name: &str) -> bool {
+        self.settings.systems.get(name).copied().unwrap_or(true)
+    }
+
+    /// Switch the registered system called `name` (any case) on or off.
+    /// Switching a system on also switches on everything it depends on (see
+    /// [`System::dependencies`]); switching it off also switches off
+    /// everything that depends on it. Returns the registered names of `name`
+    /// and the other systems that changed, with `name` last.
+    pub fn set_system_enabled(&mut self, name: &str, enabled: bool) -> Result<Vec<&'static str>, String> {
+        let found = self.registry.iter()
+            .map(|&(registered, _, _)| registered)
+            .find(|registered| registered.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("There is no system called '{}'.", name))?;
+        let mut changed = if enabled {
+            self.dependency_order(found)?
+        } else {
+            let mut dependents: Vec<&'static str> = self.registry.iter()
+                .map(|&(registered, _, _)| registered)
+                .filter(|&registered| registered != found
+                    && self.dependency_order(registered).is_ok_and(|order| order.contains(&found)))
+                .collect();
+            dependents.push(found);
+            dependents
+        };
+        changed.retain(|&system| system == found || self.system_enabled(system) != enabled);
+        for &system in &changed {
+            self.settings.systems.insert(system.to_string(), enabled);
+        }
+        Ok(changed)
+    }
+
+    /// The registered system `name` and everything it depends on, directly or
+    /// not, ordered so each system comes after its dependencies. Siblings keep
+    /// the order they are declared in.
+    pub fn dependency_order(&self, name: &str) -> Result<Vec<&'static str>, String> {
+        let mut order = Vec::new();
+        self.visit_dependencies(name, &mut Vec::new(), &mut order)?;
+        Ok(order)
+    }
+
+    /// Check the dependencies of every registered system: each must name a
+    /// registered system, and none may lead back to itself. The error names
+    /// the missing dependency or the systems in the cycle.
+    pub fn validate_systems(&self) -> Result<(), String> {
+        let mut order = Vec::new();
+        for &(name, _, _) in &self.registry {
+            self.visit_dependencies(name, &mut Vec::new(), &mut order)?;
+        }
+        Ok(())
+    }
+
+    fn visit_dependencies(&self, name: &str, path: &mut Vec<&'static str>, order: &mut Vec<&'static str>) -> Result<(), String> {
+        if order.contains(&name) {
+            return Ok(());
+        }
+        if let Some(at) = path.iter().position(|&on_path| on_path == name) {
+            let mut cycle = path[at..].to_vec();
+            cycle.push(path[at]);
+            return Err(format!("Systems depend on each other in a cycle: {}.", cycle.join(" -> ")));
+        }
+        let &(registered, _, dependencies) = self.registry.iter()
+            .find(|(registered, _, _)| *registered == name)
+            .ok_or_else(|| format!("There is no system called '{}'.", name))?;
+        path.push(registered);
+        for &dependency in dependencies {
+            if !self.registry.iter().any(|(registered, _, _)| *registered == dependency) {
+                return Err(format!("System '{}' depends on '{}', which is not registered.", registered, dependency));
+            }
+            self.visit_dependencies(dependency, path, order)?;
+        }
+        path.pop();
+        order.push(registered);
+        Ok(())
+    }
+
+    /// Every system's saved state, keyed by system name.
+    pub fn system_save_data(&self) -> BTreeMap<String, serde_json::Value> {
+        let mut data = self.system_data.clone();
+        data.extend(self.systems.iter().filter_map(|system| Some((system.name().to_string(), system.save_data()?))));
+        data
+    }
+
+    /// Hand saved state back to the systems it came from. Entries for systems
+    /// that aren't registered are ignored.
+    pub fn restore_system_data(&mut self, data: &BTreeMap<String, serde_json::Value>) -> Result<(), String> {
+        for system in &mut self.systems {
+            if let Some(saved) = data.get(system.name()) {
+                system.load_data(saved).map_err(|err| format!("{}: {}", system.name(), err))?;
+            }
+        }
+        self.system_data = data.clone();
+        Ok(())
+    }
+
+    /// Ask for the game to go back to how it was before the last command
+    /// that changed anything. The state is restored once the current command
+    /// finishes. Returns `false` if there is nothing to go back to.
+    pub fn request_undo(&mut self) -> bool {
+        self.undo_requested = !self.undo_stack.is_empty();
+        self.undo_requested
+    }
+
+    /// Pass `event` through every enabled system's [`System::on_publish`],
+    /// in registry order, and return it as the last one left it. Events
+    /// pushed to `events` during a command go through the same chain before
+    /// [`System::on_events`] sees them.
+    pub fn publish(&mut self, mut event: GameEvent) -> GameEvent {
+        let mut systems = std::mem::take(&mut self.systems);
+        self.publish_to(&mut systems, &mut event);
+        self.systems = systems;
+        event
+    }
+
+    fn publish_to(&mut self, systems: &mut [Box<dyn System>], event: &mut GameEvent) {
+        for system in systems {
+            if !self.system_enabled(system.name()) {
+                continue;
+            }
+            system.on_publish(event, self);
+            self.remember_system_data(system.as_ref());
+        }
+    }
+
+    fn remember_system_data(&mut self, system: &dyn System) {
+        if let Some(data) = system.save_data() {
+            self.system_data.insert(system.name().to_string(), data);
+        }
+    }
+
+    /// Run one command and return its output as plain lines.
+    /// See [`AdventureGame::step`] for output tagged by kind.
     pub fn process_command(&mut self, command: &str) -> Vec<String> {
+        self.step(command).to_plain_lines()
+    }
+
+    /// Run one command through the registered systems. If a question is
+    /// pending and `command` answers it, the answer is run instead.
+    pub fn step(&mut self, command: &str) -> StepResult {
+        self.logger.debug(&format!("turn {}: {}", self.turn_count, command));
+        let before = SaveState::capture(self);
+        let pending = self.pending.take();
+        let (answer, confirming) = match &pending {
+            Some(pending) => (pending.answer(command), matches!(pending, PendingAction::Confirm { .. })),
+            None => (Answer::Unrelated, false),
+        };
+        let result = match answer {
+            Answer::Run(resolved) => {
+                self.confirming = confirming;
+                let result = self.run_command(&resolved);
+                self.confirming = false;
+                result
+            }
+            Answer::Cancel => StepResult {
+                lines: vec![OutputLine::new(LineKind::System, self.messages("never_mind", &[]))],
+                game_over: self.game_over,
+                turn: self.turn_count,
+            },
+            Answer::Invalid => {
+                self.pending = pending;
+                StepResult {
+                    lines: vec![OutputLine::new(LineKind::Error, self.messages("not_an_option", &[]))],
+                    game_over: self.game_over,
+                    turn: self.turn_count,
+                }
+            }
+            Answer::Unrelated => self.run_command(command),
+        };
+        if std::mem::take(&mut self.undo_requested) {
+            if let Some(snapshot) = self.undo_stack.pop_back()
+                && let Err(err) = snapshot.apply(self)
+            {
+                self.logger.warn(&format!("undo failed: {}", err));
+            }
+        } else {
+            // Systems settle their own state on first use (quests load lazily),
+            // so only a change to the game itself makes an undo point.
+            let mut after = SaveState::capture(self);
+            after.systems.clone_from(&before.systems);
+            if after != before {
+                if self.undo_stack.len() >= UNDO_LIMIT {
+                    self.undo_stack.pop_front();
+                }
+                self.undo_stack.push_back(before);
+            }
+        }
+        if let Some(replay) = self.recording.as_mut() {
+            replay.turns.push(ReplayTurn { input: command.to_string(), output: result.to_plain_lines() });
+        }
+        if !self.output_observers.is_empty() {
+            let lines = result.to_plain_lines();
+            for (_, observer) in &mut self.output_observers {
+                observer(command, &lines);
+            }
+        }
+        result
+    }
+
+    fn run_command(&mut self, command: &str) -> StepResult {
         let parts: Vec<&str> = command.split_whitespace().collect();
         // Lowercase the verb so "Look", "ATTACK", etc. work regardless of caller.
         let cmd_lower = parts.first().unwrap_or(&"").to_lowercase();
@@ -605,33 +2552,68 @@ impl AdventureGame {
         let args: Vec<&str> = parts.iter().skip(1).cloned().collect();
 
         let mut systems = std::mem::take(&mut self.systems);
-        let mut results: Vec<String> = Vec::new();
+        let mut lines: Vec<OutputLine> = Vec::new();
+        let turn_before = self.turn_count;
 
         // Primary handler: first system that claims the command.
         for system in &mut systems {
-            if let Some(output) = system.on_command(cmd, &args, self) {
-                results.push(output);
+            if !self.system_enabled(system.name()) {
+                continue;
+            }
+            let reply = system.on_command_reply(cmd, &args, self);
+            self.remember_system_data(system.as_ref());
+            if let Some(reply) = reply {
+                lines.extend(reply.into_lines(system.output_kind()));
                 break;
             }
         }
 
+        // Turn pass: if the command consumed a turn, let systems advance
+        // per-turn state (status effects, regeneration, ...).
+        if self.turn_count != turn_before {
+            for system in &mut systems {
+                if !self.system_enabled(system.name()) {
+                    continue;
+                }
+                let output = system.on_turn(self);
+                self.remember_system_data(system.as_ref());
+                if let Some(output) = output {
+                    lines.extend(tagged(&output, system.output_kind()));
+                }
+            }
+        }
+
+        if self.turn_count != turn_before && !self.game_over
+            && self.settings.gameplay.turn_limit.is_some_and(|limit| self.turn_count > limit)
+        {
+            self.game_over = true;
+            lines.push(OutputLine::new(LineKind::System, self.messages("out_of_time", &[])));
+        }
+
         // Observer pass: systems react to pending game events via on_events().
         if !self.events.is_empty() {
-            let events = std::mem::take(&mut self.events);
+            let mut events = std::mem::take(&mut self.events);
+            for event in &mut events {
+                self.publish_to(&mut systems, event);
+            }
             for system in &mut systems {
-                if let Some(side) = system.on_events(&events, self) {
-                    results.push(side);
+                if !self.system_enabled(system.name()) {
+                    continue;
+                }
+                let side = system.on_events(&events, self);
+                self.remember_system_data(system.as_ref());
+                if let Some(side) = side {
+                    lines.extend(tagged(&side, system.output_kind()));
                 }
             }
             // events is dropped here; self.events is already empty from the take()
         }
 
         self.systems = systems;
-        if results.is_empty() {
-            vec![format!("Unknown command: {}", command)]
-        } else {
-            results
+        if lines.is_empty() {
+            lines.push(OutputLine::new(LineKind::Error, self.messages("unknown_command", &[("command", command)])));
         }
+        StepResult { lines, game_over: self.game_over, turn: self.turn_count }
     }
 }
 
@@ -640,3 +2622,615 @@ impl Default for AdventureGame {
         Self::new(String::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::BasicWorldSystem;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn output_observer_sees_look_output() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Cellar".to_string(), "Damp and dark.".to_string()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        let seen: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink = Arc::clone(&seen);
+        game.set_output_observer(Box::new(move |cmd, lines| {
+            let mut sink = sink.lock().unwrap();
+            sink.push(cmd.to_string());
+            sink.extend_from_slice(lines);
+        }));
+
+        let output = game.process_command("look");
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], "look");
+        assert_eq!(seen[1..], output[..]);
+        assert!(output.join("\n").contains("Cellar"));
+    }
+
+    #[test]
+    fn removed_output_observer_is_not_called() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Cellar".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        let calls: Arc<Mutex<Vec<&str>>> = Arc::default();
+        let first = Arc::clone(&calls);
+        let kept = game.add_output_observer(Box::new(move |_, _| first.lock().unwrap().push("first")));
+        let second = Arc::clone(&calls);
+        let removed = game.add_output_observer(Box::new(move |_, _| second.lock().unwrap().push("second")));
+        assert_ne!(kept, removed);
+
+        assert!(game.remove_output_observer(removed));
+        assert!(!game.remove_output_observer(removed));
+        game.process_command("look");
+        assert_eq!(*calls.lock().unwrap(), ["first"]);
+    }
+
+    #[test]
+    fn missing_start_room_falls_back_to_the_lowest_room_unless_strict() {
+        let json = r#"{"start_room": 9, "rooms": [{"id": 4, "name": "Yard"}, {"id": 2, "name": "Gate"}]}"#;
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(json).unwrap();
+        assert_eq!(game.player.current_room, 2);
+        assert_eq!(game.load_warnings, ["Start room 9 does not exist; starting in room 2 instead."]);
+
+        let strict = json.replace("\"rooms\"", "\"settings\": {\"gameplay\": {\"strict_load\": true}}, \"rooms\"");
+        let err = AdventureGame::new(String::new()).load_adventure_str(&strict).unwrap_err();
+        assert!(err.to_string().contains("start_room does not exist: 9"));
+    }
+
+    #[test]
+    fn start_in_room_overrides_the_start_room() {
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(r#"{
+            "start_room": 1,
+            "rooms": [
+                {"id": 1, "name": "Gate"},
+                {"id": 4, "name": "Throne Room"}
+            ]
+        }"#).unwrap();
+
+        assert!(game.start_in_room(9).is_err());
+        assert_eq!(game.player.current_room, 1);
+
+        game.start_in_room(4).unwrap();
+        assert_eq!(game.player.current_room, 4);
+        assert_eq!(game.respawn_room, 4);
+    }
+
+    fn key_room() -> AdventureGame {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Vault".to_string(), String::new()));
+        game.player.current_room = 1;
+        for (id, name) in [(1, "Brass key"), (2, "Steel key")] {
+            let mut key = Item::new(id, name.to_string(), String::new(), ItemType::Normal, 1, 1);
+            key.location = 1;
+            game.items.insert(id, key);
+        }
+        game.add_system(Box::new(crate::systems::InventorySystem));
+        game
+    }
+
+    #[test]
+    fn ambiguous_take_is_resolved_by_the_next_input() {
+        let mut game = key_room();
+        assert_eq!(game.process_command("take key"), ["Which do you mean: 1) Brass key, 2) Steel key?"]);
+        assert!(game.pending.is_some());
+
+        assert_eq!(game.process_command("brass"), ["Taken: Brass key."]);
+        assert_eq!(game.player.inventory, [1]);
+        assert!(game.pending.is_none());
+    }
+
+    #[test]
+    fn take_prefers_an_exact_name_then_the_shortest() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Vault".to_string(), String::new()));
+        game.player.current_room = 1;
+        for (id, name) in [(1, "Skeleton key"), (2, "key"), (3, "Skeleton key ring"), (4, "Coin"), (5, "Coin")] {
+            let mut item = Item::new(id, name.to_string(), String::new(), ItemType::Normal, 1, 1);
+            item.location = 1;
+            game.items.insert(id, item);
+        }
+        game.add_system(Box::new(crate::systems::InventorySystem));
+
+        assert_eq!(game.process_command("take key"), ["Taken: key."]);
+        assert_eq!(game.process_command("take skel"), ["Taken: Skeleton key."]);
+        game.process_command("take coin");
+        assert_eq!(game.player.inventory, [2, 1, 4]);
+    }
+
+    #[test]
+    fn input_that_does_not_answer_drops_the_question() {
+        let mut game = key_room();
+        game.process_command("take key");
+        assert_eq!(game.process_command("inventory"), ["Your inventory is empty."]);
+        assert!(game.pending.is_none());
+
+        game.process_command("take key");
+        assert_eq!(game.process_command("2"), ["Taken: Steel key."]);
+    }
+
+    /// `key_room` with both keys already carried, plus `extra` lying in the vault.
+    fn carrying_both_keys(extra: Item) -> AdventureGame {
+        let mut game = key_room();
+        for id in [1, 2] {
+            game.items.get_mut(&id).unwrap().location = 0;
+        }
+        game.player.inventory = vec![1, 2];
+        game.items.insert(extra.id, extra);
+        game
+    }
+
+    #[test]
+    fn ambiguous_combine_keeps_the_other_ingredient() {
+        let mut ring = Item::new(3, "Key ring".to_string(), String::new(), ItemType::Normal, 1, 1);
+        ring.location = 0;
+        let mut game = carrying_both_keys(ring);
+        let mut wire = Item::new(4, "Wire".to_string(), String::new(), ItemType::Normal, 1, 1);
+        wire.location = 0;
+        game.items.insert(4, wire);
+        game.player.inventory.push(4);
+        game.recipes.push(Recipe { inputs: [2, 4], output: 3 });
+
+        assert_eq!(game.process_command("combine key with wire"), ["Which do you mean: 1) Brass key, 2) Steel key?"]);
+        game.process_command("2");
+        assert_eq!(game.player.inventory, [1, 3]);
+    }
+
+    #[test]
+    fn ambiguous_put_keeps_the_container() {
+        let mut box_ = Item::new(3, "Strongbox".to_string(), String::new(), ItemType::Container, 1, 1);
+        box_.location = 1;
+        box_.is_open = true;
+        let mut game = carrying_both_keys(box_);
+
+        assert_eq!(game.process_command("put key in strongbox"), ["Which do you mean: 1) Brass key, 2) Steel key?"]);
+        game.process_command("steel");
+        assert_eq!(game.items[&3].contents, [2]);
+        assert_eq!(game.player.inventory, [1]);
+    }
+
+    #[test]
+    fn ambiguous_use_on_keeps_the_target() {
+        let mut game = carrying_both_keys(Item::new(3, "Pebble".to_string(), String::new(), ItemType::Normal, 1, 1));
+        game.rooms.get_mut(&1).unwrap().exits.insert("north".to_string(), 1);
+        game.rooms.get_mut(&1).unwrap().barriers.insert("north".to_string(), Barrier {
+            name: "iron gate".to_string(), key: 2, kind: BarrierKind::Locked, cleared: false, message: None,
+        });
+
+        assert_eq!(game.process_command("use key on gate"), ["Which do you mean: 1) Brass key, 2) Steel key?"]);
+        game.process_command("2");
+        assert!(game.rooms[&1].barriers["north"].cleared);
+    }
+
+    #[test]
+    fn numbered_reply_picks_a_dialogue_option() {
+        struct Talk;
+        impl System for Talk {
+            fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
+                match command {
+                    "talk" => {
+                        let choices = vec!["Ask about the tower".to_string(), "Say goodbye".to_string()];
+                        let question = format!("The hermit waits. {}", number_choices(&choices));
+                        game.pending = Some(PendingAction::Choose { command: "reply".to_string(), choices });
+                        Some(question)
+                    }
+                    "reply" => Some(format!("You: {}", args.join(" "))),
+                    _ => None,
+                }
+            }
+        }
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Talk));
+
+        assert_eq!(game.process_command("talk"), ["The hermit waits. 1) Ask about the tower, 2) Say goodbye"]);
+        assert_eq!(game.process_command("3"), ["That isn't an option right now."]);
+        assert_eq!(game.process_command("2"), ["You: Say goodbye"]);
+        assert!(game.pending.is_none());
+        assert_eq!(game.process_command("2"), ["Unknown command: 2"]);
+    }
+
+    #[test]
+    fn find_inventory_item_reports_unique_ambiguous_and_missing() {
+        let mut game = key_room();
+        game.player.inventory = vec![1, 2];
+        let find = |name| game.player.find_inventory_item(name, &game.items);
+
+        assert_eq!(find("brass"), InventoryMatch::Unique(1));
+        assert_eq!(find("STEEL KEY"), InventoryMatch::Unique(2));
+        assert_eq!(find("key"), InventoryMatch::Ambiguous(vec![1, 2]));
+        assert_eq!(find("lantern"), InventoryMatch::None);
+    }
+
+    #[test]
+    fn ambiguous_drop_asks_which_item() {
+        let mut game = key_room();
+        game.player.inventory = vec![1, 2];
+        assert_eq!(game.process_command("drop key"), ["Which do you mean: 1) Brass key, 2) Steel key?"]);
+        assert_eq!(game.process_command("steel"), ["Dropped: Steel key."]);
+        assert_eq!(game.player.inventory, [1]);
+        assert_eq!(game.process_command("drop lantern"), ["You don't have that."]);
+    }
+
+    #[test]
+    fn confirmation_runs_the_command_only_on_yes() {
+        struct Jump;
+        impl System for Jump {
+            fn on_command(&mut self, command: &str, _args: &[&str], game: &mut AdventureGame) -> Option<String> {
+                if command != "jump" {
+                    return None;
+                }
+                if game.confirmed() {
+                    return Some("You leap into the chasm.".to_string());
+                }
+                game.pending = Some(PendingAction::Confirm { command: "jump".to_string() });
+                Some("Really jump? (y/n)".to_string())
+            }
+        }
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Jump));
+
+        assert_eq!(game.process_command("jump"), ["Really jump? (y/n)"]);
+        assert_eq!(game.process_command("n"), ["Never mind."]);
+        game.process_command("jump");
+        assert_eq!(game.process_command("yes"), ["You leap into the chasm."]);
+    }
+
+    struct Needs(&'static str, &'static [&'static str]);
+
+    impl System for Needs {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn dependencies(&self) -> &'static [&'static str] {
+            self.1
+        }
+
+        fn on_command(&mut self, _command: &str, _args: &[&str], _game: &mut AdventureGame) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn enabling_a_system_enables_its_dependencies_first() {
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Needs("journal", &["maps", "ink"])));
+        game.add_system(Box::new(Needs("ink", &[])));
+        game.add_system(Box::new(Needs("maps", &["ink"])));
+        for name in ["journal", "ink", "maps"] {
+            game.set_system_enabled(name, false).unwrap();
+        }
+
+        assert_eq!(game.set_system_enabled("Journal", true), Ok(vec!["ink", "maps", "journal"]));
+        assert!(game.system_states().iter().all(|&(_, on)| on));
+    }
+
+    #[test]
+    fn disabling_a_system_disables_what_depends_on_it() {
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Needs("journal", &["maps", "ink"])));
+        game.add_system(Box::new(Needs("ink", &[])));
+        game.add_system(Box::new(Needs("maps", &["ink"])));
+        game.add_system(Box::new(Needs("compass", &[])));
+
+        assert_eq!(game.set_system_enabled("maps", false), Ok(vec!["journal", "maps"]));
+        assert!(game.system_enabled("ink"));
+        assert_eq!(game.set_system_enabled("ink", false), Ok(vec!["ink"]));
+        assert_eq!(game.system_states(), [("journal", false), ("ink", false), ("maps", false), ("compass", true)]);
+    }
+
+    #[test]
+    fn dependency_cycles_are_reported() {
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Needs("chicken", &["egg"])));
+        game.add_system(Box::new(Needs("egg", &["chicken"])));
+        game.set_system_enabled("egg", false).unwrap();
+
+        assert_eq!(game.set_system_enabled("egg", true),
+            Err("Systems depend on each other in a cycle: egg -> chicken -> egg.".to_string()));
+        assert!(!game.system_enabled("egg"));
+    }
+
+    #[test]
+    fn system_graph_validation_names_the_problem() {
+        assert_eq!(crate::engine::Engine::new("").game.validate_systems(), Ok(()));
+
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Needs("ink", &[])));
+        game.add_system(Box::new(Needs("maps", &["ink", "compass"])));
+        assert_eq!(game.validate_systems(),
+            Err("System 'maps' depends on 'compass', which is not registered.".to_string()));
+
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(Needs("ink", &[])));
+        game.add_system(Box::new(Needs("chicken", &["egg"])));
+        game.add_system(Box::new(Needs("egg", &["chicken"])));
+        assert_eq!(game.validate_systems(),
+            Err("Systems depend on each other in a cycle: chicken -> egg -> chicken.".to_string()));
+    }
+
+    #[test]
+    fn items_and_monsters_can_be_queried_and_edited() {
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(r#"{
+            "rooms": [{"id": 1, "name": "Vault"}],
+            "items": [
+                {"id": 3, "name": "Ruby", "location": 1, "value": 50},
+                {"id": 1, "name": "Pebble", "location": 1, "value": 1},
+                {"id": 2, "name": "Crown", "location": 0, "value": 200}
+            ],
+            "monsters": [{"id": 1, "name": "Dragon", "room_id": 1, "gold": 100}]
+        }"#).unwrap();
+
+        let valuable: Vec<&str> = game.find_items(|item| item.value > 10).iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(valuable, ["Crown", "Ruby"]);
+        assert_eq!(game.find_monsters(|m| m.gold > 0).len(), 1);
+
+        assert!(game.update_monster(1, |dragon| dragon.gold += 50));
+        assert_eq!(game.monsters[&1].gold, 150);
+        assert!(!game.update_item(9, |item| item.value = 0));
+    }
+
+    #[test]
+    fn quest_targeting_a_room_id_as_a_monster_is_reported() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(7, Room::new(7, "Lair".to_string(), String::new()));
+        game.quests.push(serde_json::json!({
+            "id": 1,
+            "title": "Slay the beast",
+            "objectives": [{"type": "kill_monster", "target_id": 7}]
+        }));
+
+        let warnings = game.validate_quest_targets();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("targets monster 7, which does not exist (only a room has that id)"));
+    }
+
+    const DANGLING_EXIT: &str = r#"{
+        "settings": {"gameplay": {"strict_load": STRICT}},
+        "rooms": [
+            {"id": 1, "name": "Hall", "exits": {"north": 2, "east": 9}},
+            {"id": 2, "name": "Study", "exits": {"south": 1}}
+        ]
+    }"#;
+
+    #[test]
+    fn dangling_exit_is_pruned_with_a_warning() {
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(&DANGLING_EXIT.replace("STRICT", "false")).unwrap();
+
+        assert!(!game.rooms[&1].exits.contains_key("east"));
+        assert_eq!(game.rooms[&1].exits.get("north"), Some(&2));
+        assert_eq!(game.load_warnings, ["Room 1 exit 'east' leads to missing room 9; removed."]);
+    }
+
+    #[test]
+    fn strict_load_refuses_a_dangling_exit() {
+        let mut game = AdventureGame::new(String::new());
+        let err = game.load_adventure_str(&DANGLING_EXIT.replace("STRICT", "true")).unwrap_err();
+        assert_eq!(err.to_string(), "validation error: exits lead to missing rooms: room 1 east -> 9");
+    }
+
+    #[test]
+    fn available_exits_are_sorted_and_flag_locked_and_hidden_ones() {
+        let mut game = AdventureGame::new(String::new());
+        let mut hall = Room::new(1, "Hall".to_string(), String::new());
+        for (dir, to) in [("west", 2), ("up", 3), ("north", 4), ("east", 5)] {
+            hall.exits.insert(dir.to_string(), to);
+        }
+        hall.hidden_exits.insert("down".to_string(), 6);
+        hall.barriers.insert("east".to_string(), Barrier {
+            name: "iron gate".to_string(), key: 1, kind: BarrierKind::Locked, cleared: false, message: None,
+        });
+        game.rooms.insert(1, hall);
+        game.player.current_room = 1;
+
+        assert_eq!(game.rooms[&1].sorted_exits(), [("north", 4), ("east", 5), ("west", 2), ("up", 3)]);
+        let exits = game.available_exits();
+        let summary: Vec<(&str, bool, bool)> = exits.iter()
+            .map(|e| (e.direction.as_str(), e.is_locked(), e.hidden))
+            .collect();
+        assert_eq!(summary, [
+            ("north", false, false),
+            ("east", true, false),
+            ("west", false, false),
+            ("up", false, false),
+            ("down", false, true),
+        ]);
+        assert_eq!(exits[1].barrier.as_deref(), Some("iron gate"));
+        assert_eq!(game.visible_exits(), ["north", "west", "up"]);
+        assert!(game.look().contains("Obvious exits: north, east (iron gate), west, up"));
+    }
+
+    #[test]
+    fn key_used_on_a_locked_door_opens_its_exit() {
+        let mut game = crate::engine::Engine::new("").game;
+        game.load_adventure_str(r#"{
+            "rooms": [
+                {"id": 1, "name": "Hall", "exits": {"north": 2},
+                 "barriers": {"north": {"name": "oak door", "key": 1}}},
+                {"id": 2, "name": "Vault"}
+            ],
+            "items": [
+                {"id": 1, "name": "Iron Key", "location": 0},
+                {"id": 2, "name": "Stick", "location": 0}
+            ]
+        }"#).unwrap();
+        game.player.inventory = vec![1, 2];
+
+        assert_eq!(game.process_command("north"), ["The oak door bars the way north."]);
+        assert_eq!(game.process_command("use stick on door"), ["That doesn't seem to work."]);
+        assert_eq!(game.process_command("use key on"), ["Use it on what?"]);
+        assert_eq!(game.process_command("use key on north door"),
+            ["You unlock the oak door with the Iron Key. The way north is open."]);
+        assert!(game.rooms[&1].barrier("north").is_none());
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 2);
+        assert_eq!(crate::save::SaveState::capture(&game).cleared_barriers, BTreeSet::from([(1, "north".to_string())]));
+    }
+
+    #[test]
+    fn keyed_door_lets_through_whoever_carries_the_key() {
+        let mut game = crate::engine::Engine::new("").game;
+        game.load_adventure_str(r#"{
+            "rooms": [
+                {"id": 1, "name": "Yard", "exits": {"east": 2}},
+                {"id": 2, "name": "Hall", "exits": {"west": 1, "north": 3},
+                 "barriers": {"north": {"name": "oak door", "key": 1, "kind": "keyed",
+                                        "message": "The oak door is locked. There must be a key somewhere."}}},
+                {"id": 3, "name": "Vault"}
+            ],
+            "items": [{"id": 1, "name": "Iron Key", "location": 2}]
+        }"#).unwrap();
+        game.player.current_room = 1;
+
+        game.process_command("east");
+        assert_eq!(game.player.current_room, 2);
+        assert_eq!(game.process_command("north"), ["The oak door is locked. There must be a key somewhere."]);
+        assert_eq!(game.player.current_room, 2);
+
+        game.process_command("take key");
+        assert!(game.look().contains("Obvious exits: north, west"));
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 3);
+        assert!(game.rooms[&2].barrier("north").is_some());
+    }
+
+    fn checkpoint_run(death: DeathBehavior) -> AdventureGame {
+        let mut game = AdventureGame::new(String::new());
+        let mut camp = Room::new(1, "Camp".to_string(), String::new());
+        camp.exits.insert("north".to_string(), 2);
+        let mut shrine = Room::new(2, "Shrine".to_string(), String::new());
+        shrine.is_checkpoint = true;
+        shrine.exits.insert("north".to_string(), 3);
+        game.rooms.insert(1, camp);
+        game.rooms.insert(2, shrine);
+        game.rooms.insert(3, Room::new(3, "Pit".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.settings.gameplay.death = death;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        let mut rope = Item::new(1, "Rope".to_string(), String::new(), ItemType::Normal, 1, 1);
+        rope.location = 0;
+        game.items.insert(1, rope);
+        game.player.inventory.push(1);
+
+        game.process_command("north");
+        game.process_command("north");
+        game.player.current_health = 0;
+        game
+    }
+
+    #[test]
+    fn respawn_returns_player_to_last_checkpoint() {
+        let mut game = checkpoint_run(DeathBehavior::Respawn);
+        let msg = game.handle_player_death();
+
+        assert!(!game.game_over);
+        assert_eq!(game.player.current_room, 2);
+        assert!(msg.contains("You awaken in Shrine"));
+        assert_eq!(game.player.current_health, game.player.hardiness / 2);
+        assert_eq!(game.player.inventory, vec![1]);
+    }
+
+    #[test]
+    fn drop_leaves_inventory_in_the_death_room() {
+        let mut game = checkpoint_run(DeathBehavior::Drop);
+        game.handle_player_death();
+
+        assert!(game.player.inventory.is_empty());
+        assert_eq!(game.items[&1].location, 3);
+        assert_eq!(game.player.current_room, 2);
+    }
+
+    #[test]
+    fn unknown_command_is_tagged_as_error() {
+        let mut game = AdventureGame::new(String::new());
+        game.add_system(Box::new(BasicWorldSystem));
+
+        let result = game.step("xyzzy");
+        assert_eq!(result.lines, vec![OutputLine::new(LineKind::Error, "Unknown command: xyzzy")]);
+        assert!(!result.game_over);
+    }
+
+    #[test]
+    fn look_output_is_split_by_kind() {
+        let mut game = AdventureGame::new(String::new());
+        let mut cellar = Room::new(1, "Cellar".to_string(), "Damp and dark.".to_string());
+        cellar.exits.insert("up".to_string(), 2);
+        game.rooms.insert(1, cellar);
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        let result = game.step("look");
+        assert!(result.lines.contains(&OutputLine::new(LineKind::RoomTitle, "Cellar")));
+        assert!(result.lines.contains(&OutputLine::new(LineKind::Exits, "Obvious exits: up")));
+        assert_eq!(result.to_plain_lines().join("\n"), game.look());
+    }
+
+    #[test]
+    fn moving_tags_the_new_room_and_failed_commands_are_errors() {
+        let mut game = AdventureGame::new(String::new());
+        let mut cellar = Room::new(1, "Cellar".to_string(), "Damp and dark.".to_string());
+        cellar.exits.insert("up".to_string(), 2);
+        game.rooms.insert(1, cellar);
+        game.rooms.insert(2, Room::new(2, "Kitchen".to_string(), "Pots hang from hooks.".to_string()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+        game.add_system(Box::new(crate::systems::InventorySystem));
+
+        let result = game.step("up");
+        assert!(result.lines.contains(&OutputLine::new(LineKind::RoomTitle, "Kitchen")));
+        assert!(result.lines.contains(&OutputLine::new(LineKind::Description, "Pots hang from hooks.")));
+
+        assert_eq!(game.step("up").lines, [OutputLine::new(LineKind::Error, "You can't go that way.")]);
+        let result = game.step("open chest");
+        assert!(result.has_kind(LineKind::Error) && !result.has_kind(LineKind::System));
+    }
+
+    #[test]
+    fn trivial_checks_pass_and_impossible_ones_fail() {
+        let mut game = AdventureGame::new(String::new());
+        game.player.agility = 8;
+        game.seed_rng(7);
+        for _ in 0..50 {
+            assert!(game.ability_check(Stat::Agility, 0));
+            assert!(!game.ability_check(Stat::Agility, 20));
+        }
+        assert_eq!(game.player.stat_modifier(Stat::Agility), -1);
+    }
+
+    #[test]
+    fn published_events_add_up_what_each_system_contributes() {
+        struct AddDamage(i64);
+        impl System for AddDamage {
+            fn on_command(&mut self, _command: &str, _args: &[&str], _game: &mut AdventureGame) -> Option<String> {
+                None
+            }
+
+            fn on_publish(&mut self, event: &mut GameEvent, _game: &mut AdventureGame) {
+                if let GameEvent::Custom { data, .. } = event {
+                    let damage = data.get_i64("damage").unwrap_or(0);
+                    data.set("damage", damage + self.0);
+                }
+            }
+        }
+        let mut game = AdventureGame::new(String::new());
+        game.systems = vec![Box::new(AddDamage(2)), Box::new(AddDamage(3))];
+        let mut data = EventData::default();
+        data.set("source", "trap");
+
+        let GameEvent::Custom { data, .. } = game.publish(GameEvent::Custom { name: "hit".to_string(), data }) else {
+            panic!("publish changed the kind of event");
+        };
+        assert_eq!(data.get_i64("damage"), Some(5));
+        assert_eq!(data.get_str("source"), Some("trap"));
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::adventure::AdventureError;
+use crate::game_state::AdventureGame;
+
+/// Default location of the hall-of-fame file, relative to the working directory.
+pub const HALL_OF_FAME_FILE: &str = "hall_of_fame.json";
+
+/// One finished playthrough.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub adventure_title: String,
+    pub player_name: String,
+    pub score: i32,
+    pub turns: i32,
+    pub date: String,
+    pub won: bool,
+}
+
+impl ScoreEntry {
+    /// Snapshot the final result of `game`.
+    pub fn from_game(game: &AdventureGame, won: bool) -> Self {
+        Self {
+            adventure_title: game.adventure_title.clone(),
+            player_name: game.player.name.clone(),
+            score: game.compute_score(),
+            turns: game.turn_count,
+            date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            won,
+        }
+    }
+}
+
+/// Persistent list of finished playthroughs across sessions.
+#[derive(Debug, Clone)]
+pub struct HallOfFame {
+    path: PathBuf,
+    pub entries: Vec<ScoreEntry>,
+}
+
+impl HallOfFame {
+    /// Load the hall of fame at `path`. A missing file is an empty hall.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Append a result and write the file back to disk.
+    pub fn record(&mut self, entry: ScoreEntry) -> Result<(), AdventureError> {
+        self.entries.push(entry);
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// The best `limit` results for one adventure, highest score first
+    /// (ties go to the quicker playthrough).
+    pub fn top(&self, adventure_title: &str, limit: usize) -> Vec<&ScoreEntry> {
+        let mut entries: Vec<&ScoreEntry> = self.entries.iter()
+            .filter(|e| e.adventure_title == adventure_title)
+            .collect();
+        entries.sort_by(|a, b| b.score.cmp(&a.score).then(a.turns.cmp(&b.turns)));
+        entries.truncate(limit);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(player: &str, score: i32) -> ScoreEntry {
+        ScoreEntry {
+            adventure_title: "Demo".to_string(),
+            player_name: player.to_string(),
+            score,
+            turns: 10,
+            date: "2026-01-01".to_string(),
+            won: false,
+        }
+    }
+
+    #[test]
+    fn recorded_results_read_back_sorted_by_score() {
+        let path = std::env::temp_dir().join(format!("sagacraft_hof_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut hall = HallOfFame::load(&path).unwrap();
+        hall.record(entry("Low", 40)).unwrap();
+        hall.record(entry("High", 90)).unwrap();
+
+        let reloaded = HallOfFame::load(&path).unwrap();
+        let names: Vec<&str> = reloaded.top("Demo", 10).iter().map(|e| e.player_name.as_str()).collect();
+        assert_eq!(names, ["High", "Low"]);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,9 +1,43 @@
 pub mod engine;
 pub mod adventure;
+pub mod bundle;
+pub mod catalog;
+pub mod command;
+pub mod directions;
+pub mod effects;
+pub mod event_log;
 pub mod game_state;
+pub mod hall_of_fame;
+pub mod log;
+pub mod messages;
+pub mod output;
+pub mod raw;
+pub mod replay;
+pub mod save;
+pub mod session;
+pub mod settings;
 pub mod systems;
+pub mod transcript;
+pub mod yaml;
 
-pub use adventure::{Adventure, AdventureError, AdventureItem, AdventureRoom};
+pub use adventure::{Adventure, AdventureError, AdventureItem, AdventureLock, AdventureRoom, TemplateKind};
 pub use engine::Engine;
-pub use game_state::{AdventureGame, GameEvent, Item, Monster, Player, Room, ItemType, MonsterStatus};
-pub use systems::{BasicWorldSystem, InventorySystem, CombatSystem, QuestSystem, System};
+pub use bundle::{Bundle, ModDefinition, BUNDLE_EXTENSION};
+pub use command::{ArgShape, CommandSpec};
+pub use directions::{canonical_reverse, direction_cmp, DIRECTION_ORDER};
+pub use effects::Effect;
+pub use event_log::{kind_matches, EventLog, LogEntry, LoggedEvent, EVENT_LOG_CAPACITY};
+pub use catalog::{catalog, read_meta, AdventureMeta};
+pub use settings::{ClockSettings, DeathBehavior, EconomySettings, GameSettings, GameplaySettings, InventoryMode, InventorySettings, ScoreSettings, UiSettings, SETTINGS_ENV_PREFIX};
+pub use log::{Level, LogSink, Logger};
+pub use messages::Messages;
+pub use output::{tagged, LineKind, OutputLine, Reply, StepResult};
+pub use raw::{retain_valid, SkippedEntity};
+pub use replay::{Replay, ReplayError, ReplayTurn};
+pub use save::{backup_path, restore_backup, MonsterState, SaveMeta, SaveState};
+pub use session::{SessionId, SessionManager};
+pub use transcript::Transcript;
+pub use yaml::YamlError;
+pub use hall_of_fame::{HallOfFame, ScoreEntry, HALL_OF_FAME_FILE};
+pub use game_state::{AdventureGame, Barrier, BarrierKind, DialogueLine, EventData, ExitInfo, GameEvent, InventoryMatch, InventorySort, Item, Monster, ObserverId, OutputObserver, PendingAction, number_choices, Player, Recipe, Room, ItemType, MonsterStatus, Stat, TimeOfDay};
+pub use systems::{AutosaveSystem, BasicWorldSystem, ClockSystem, DialogueSystem, EffectSystem, InventorySystem, CombatSystem, MetaSystem, QuestSystem, ScoreSystem, System};
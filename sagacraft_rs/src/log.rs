@@ -0,0 +1,115 @@
+//! Leveled engine diagnostics with a replaceable sink, so embedders can
+//! capture or silence them instead of having them written to stderr.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Error => "Error",
+            Level::Warn => "Warning",
+            Level::Info => "Info",
+            Level::Debug => "Debug",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            other => Err(format!("unknown log level '{}' (expected error, warn, info or debug)", other)),
+        }
+    }
+}
+
+/// Callback receiving each message at or above the logger's level.
+pub type LogSink = Box<dyn Fn(Level, &str) + Send>;
+
+/// Filters messages by level and hands the rest to a sink. Without a sink,
+/// messages go to stderr as `Level: message`.
+pub struct Logger {
+    pub level: Level,
+    sink: Option<LogSink>,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new(Level::Warn)
+    }
+}
+
+impl Logger {
+    pub fn new(level: Level) -> Self {
+        Self { level, sink: None }
+    }
+
+    /// Send messages to `sink` instead of stderr.
+    pub fn with_sink(level: Level, sink: LogSink) -> Self {
+        Self { level, sink: Some(sink) }
+    }
+
+    pub fn log(&self, level: Level, message: &str) {
+        if level > self.level {
+            return;
+        }
+        match &self.sink {
+            Some(sink) => sink(level, message),
+            None => eprintln!("{}: {}", level, message),
+        }
+    }
+
+    pub fn error(&self, message: &str) {
+        self.log(Level::Error, message);
+    }
+
+    pub fn warn(&self, message: &str) {
+        self.log(Level::Warn, message);
+    }
+
+    pub fn info(&self, message: &str) {
+        self.log(Level::Info, message);
+    }
+
+    pub fn debug(&self, message: &str) {
+        self.log(Level::Debug, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn sink_receives_messages_at_or_above_the_level() {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&captured);
+        let logger = Logger::with_sink(Level::Warn, Box::new(move |level, msg| {
+            sink.lock().unwrap().push(format!("{}: {}", level, msg));
+        }));
+
+        logger.warn("room 3: ignored unknown keys: colour");
+        logger.info("loaded");
+        logger.error("autosave failed");
+        assert_eq!(*captured.lock().unwrap(), [
+            "Warning: room 3: ignored unknown keys: colour",
+            "Error: autosave failed",
+        ]);
+        assert_eq!("DEBUG".parse::<Level>(), Ok(Level::Debug));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::adventure::AdventureError;
+
+/// Built-in English text for the engine's messages, keyed by message id.
+/// `{name}` placeholders are filled in by [`Messages::format`].
+const DEFAULT_MESSAGES: &[(&str, &str)] = &[
+    ("unknown_command", "Unknown command: {command}"),
+    ("go_where", "Go where?"),
+    ("cant_go", "You can't go that way."),
+    ("cant_go_direction", "You can't go {direction}."),
+    ("cant_retrace", "You can't retrace your steps."),
+    ("say_what", "Say what?"),
+    ("talk_to_whom", "Talk to whom?"),
+    ("no_one_to_talk_to", "There's no '{name}' here to talk to."),
+    ("talk_refused", "The {monster} glares at you and says nothing."),
+    ("talk_greeting", "The {monster} greets you."),
+    ("talk_line", "The {monster} says: \"{line}\""),
+    ("talk_threat", "The {monster} snarls: \"{line}\""),
+    ("talk_no_answer", "The {monster} has nothing to say about that."),
+    ("you_say", "You say: \"{text}\""),
+    ("turns_to_face", "{monster} turns to face you."),
+    ("inventory_empty", "Your inventory is empty."),
+    ("inventory_sorted", "Your inventory is now sorted by {key}."),
+    ("inventory_unsorted", "Your inventory is now listed in the order you picked things up."),
+    ("inventory_sort_keys", "Sort by name, type, value, weight or none."),
+    ("inventory_grouped", "Your inventory is now grouped by type."),
+    ("inventory_ungrouped", "Your inventory is no longer grouped by type."),
+    ("take_what", "Take what?"),
+    ("cant_take", "You can't take that."),
+    ("too_heavy", "It's too heavy; you're carrying too much."),
+    ("taken", "Taken: {item}."),
+    ("nothing_to_take", "There's nothing here to take."),
+    ("drop_what", "Drop what?"),
+    ("dropped", "Dropped: {item}."),
+    ("dont_have_that", "You don't have that."),
+    ("equip_what", "Equip what?"),
+    ("wear_what", "Wear what?"),
+    ("remove_what", "Remove what?"),
+    ("unequip_what", "Unequip what? Specify 'weapon' or 'armor'."),
+    ("use_what", "Use what?"),
+    ("combine_what", "Combine what with what?"),
+    ("dont_have_item", "You don't have any '{item}'."),
+    ("nothing_happens", "Nothing happens."),
+    ("combined", "You combine them into: {item}."),
+    ("examine_what", "Examine what?"),
+    ("nothing_special", "You see nothing special about that."),
+    ("which_one", "Which do you mean: {choices}?"),
+    ("never_mind", "Never mind."),
+    ("not_an_option", "That isn't an option right now."),
+    ("no_bank_here", "There is nowhere safe to keep gold here."),
+    ("not_enough_gold", "You only have {gold} gold."),
+    ("not_enough_banked", "You only have {gold} gold banked."),
+    ("deposited", "You deposit {gold} gold. ({banked} banked)"),
+    ("withdrew", "You withdraw {gold} gold. ({banked} banked)"),
+    ("bark", "The {monster} calls out: \"{line}\""),
+    ("repair_what", "Repair what?"),
+    ("no_smith_here", "There is no one here who can repair anything."),
+    ("not_damaged", "The {item} doesn't need repairing."),
+    ("repair_cost", "Repairing the {item} costs {cost} gold; you have {gold}."),
+    ("repaired", "You pay {cost} gold and the {item} is as good as new."),
+    ("use_on_what", "Use it on what?"),
+    ("doesnt_work", "That doesn't seem to work."),
+    ("unlocked", "You unlock the {barrier} with the {item}. The way {direction} is open."),
+    ("burned_away", "The {item} burns the {barrier} away. The way {direction} is clear."),
+    ("healed_ally", "You give the {item} to the {monster}. Its health: {health}/{max}."),
+    ("exit_barred", "The {barrier} bars the way {direction}."),
+    ("no_container", "There's no '{name}' here that holds anything."),
+    ("container_closed", "The {container} is closed."),
+    ("container_empty", "The {container} is empty."),
+    ("container_holds", "The {container} holds: {items}."),
+    ("already_open", "The {container} is already open."),
+    ("already_closed", "The {container} is already closed."),
+    ("opened", "You open the {container}."),
+    ("closed", "You close the {container}."),
+    ("put_what", "Put what in what?"),
+    ("put_in", "You put the {item} in the {container}."),
+    ("cant_put_in_itself", "You can't put something inside itself."),
+    ("not_in_container", "There's no '{item}' in the {container}."),
+    ("open_what", "Open what?"),
+    ("close_what", "Close what?"),
+    ("search_nothing", "You search carefully but find nothing new."),
+    ("search_found_item", "You find: {item}!"),
+    ("search_found_exit", "You discover a hidden way {direction}!"),
+    ("pitch_black", "It is pitch black. You can't see anything."),
+    ("stumble_in_dark", "You stumble about in the dark and get nowhere."),
+    ("time_of_day", "It is {period}."),
+    ("undone", "Reverted."),
+    ("nothing_to_undo", "There is nothing to undo."),
+    ("trap_sprung", "A trap goes off! You take {damage} damage. ({health}/{max} health)"),
+    ("trap_disarmed_with", "You spot a trap and disarm it with the {item}."),
+    ("trap_disarmed", "You find a trap {direction} and carefully disarm it."),
+    ("no_trap", "You find no trap {direction}."),
+    ("disarm_where", "Disarm which way?"),
+    ("out_of_time", "You have run out of time. The game is over."),
+    ("clock_time", "It is {hour}:00 ({period})."),
+    ("night_falls", "Night falls."),
+    ("sun_rises", "The sun rises."),
+    ("nothing_hostile", "There's nothing hostile here."),
+    ("attack_which", "Attack which one: {monsters}?"),
+    ("no_one_to_attack", "There's no {name} here to attack."),
+    ("wont_attack_friendly", "You can't bring yourself to attack the friendly {monster}."),
+    ("out_of_reach", "The {monster} is out of reach."),
+    ("hit", "You attack the {monster} for {damage} damage. It has {health} health remaining."),
+    ("hit_through_armor", "You attack the {monster} for {damage} damage ({absorbed} absorbed by armor). It has {health} health remaining."),
+    ("defeated", "You defeat the {monster}! (+{xp} XP)"),
+    ("defeated_with_gold", "You defeat the {monster}! (+{gold} gold) (+{xp} XP)"),
+    ("level_up", "*** Level Up! You are now level {level}. Hardiness +2, Agility +1. Health restored to {health}. ***"),
+    ("struck", "The {monster} strikes back for {damage} damage. Your health: {health}/{max}."),
+    ("struck_down", "The {monster} strikes back for {damage} damage. {death}"),
+    ("poisoned", "You have been poisoned!"),
+    ("poison_tick", "The poison burns in your veins ({damage} damage). Your health: {health}/{max}."),
+    ("poison_fatal", "The poison overwhelms you. {death}"),
+    ("monster_regenerates", "The {monster}'s wounds knit together."),
+    ("turns_hostile", "The {monster} recognizes you and turns hostile!"),
+    ("ally_joins", "The {monster} joins the fight!"),
+    ("monster_flees", "The wounded {monster} flees {direction}!"),
+    ("nothing_to_flee", "You aren't in combat — there's nothing to flee from."),
+    ("cant_flee_direction", "You can't flee {direction}."),
+    ("nowhere_to_flee", "You try to flee but have nowhere to go!"),
+    ("fled", "You flee {direction}!"),
+    ("flee_failed", "You fail to flee!"),
+];
+
+/// The string table for engine messages. Starts out as the built-in English
+/// text; a locale file can override any subset of keys.
+#[derive(Debug, Clone)]
+pub struct Messages {
+    table: HashMap<String, String>,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            table: DEFAULT_MESSAGES.iter()
+                .map(|&(key, text)| (key.to_string(), text.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Messages {
+    /// Replace the text for `key`.
+    pub fn set(&mut self, key: impl Into<String>, text: impl Into<String>) {
+        self.table.insert(key.into(), text.into());
+    }
+
+    /// Override messages from a locale JSON file: a flat object of `key: text`.
+    /// Keys missing from the file keep their current text.
+    pub fn load_overrides(&mut self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        let overrides: HashMap<String, String> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        self.table.extend(overrides);
+        Ok(())
+    }
+
+    /// Look up `key` and substitute `{name}` placeholders from `args`.
+    /// Unknown keys render as the key itself so missing text is easy to spot.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self.table.get(key).cloned().unwrap_or_else(|| key.to_string());
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// What a line of engine output is, so front-ends can style it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineKind {
+    RoomTitle,
+    Description,
+    Exits,
+    Item,
+    Combat,
+    Error,
+    System,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+impl OutputLine {
+    pub fn new(kind: LineKind, text: impl Into<String>) -> Self {
+        Self { kind, text: text.into() }
+    }
+}
+
+/// Split `text` into lines, all tagged `kind`.
+pub fn tagged(text: &str, kind: LineKind) -> Vec<OutputLine> {
+    text.split('\n').map(|line| OutputLine::new(kind, line)).collect()
+}
+
+/// A system's answer to a command; see [`crate::System::on_command_reply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// Text tagged with the answering system's [`crate::System::output_kind`].
+    Text(String),
+    /// Why the command didn't work, tagged [`LineKind::Error`].
+    Error(String),
+    /// Lines tagged where they were made, e.g. a room from
+    /// [`crate::AdventureGame::look_lines`].
+    Lines(Vec<OutputLine>),
+}
+
+impl Reply {
+    /// The reply as tagged lines, with plain text tagged `kind`.
+    pub fn into_lines(self, kind: LineKind) -> Vec<OutputLine> {
+        match self {
+            Reply::Text(text) => tagged(&text, kind),
+            Reply::Error(text) => tagged(&text, LineKind::Error),
+            Reply::Lines(lines) => lines,
+        }
+    }
+
+    /// The reply as plain text, one line per output line.
+    pub fn into_text(self) -> String {
+        match self {
+            Reply::Text(text) | Reply::Error(text) => text,
+            Reply::Lines(lines) => lines.into_iter().map(|line| line.text).collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
+impl From<String> for Reply {
+    fn from(text: String) -> Self {
+        Reply::Text(text)
+    }
+}
+
+impl From<Result<String, String>> for Reply {
+    fn from(result: Result<String, String>) -> Self {
+        result.map_or_else(Reply::Error, Reply::Text)
+    }
+}
+
+impl From<Vec<OutputLine>> for Reply {
+    fn from(lines: Vec<OutputLine>) -> Self {
+        Reply::Lines(lines)
+    }
+}
+
+/// Everything one command produced, plus the game state a UI needs to react to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepResult {
+    pub lines: Vec<OutputLine>,
+    pub game_over: bool,
+    pub turn: i32,
+}
+
+impl StepResult {
+    /// The output as plain text lines, for front-ends that don't style by kind.
+    pub fn to_plain_lines(&self) -> Vec<String> {
+        self.lines.iter().map(|l| l.text.clone()).collect()
+    }
+
+    /// Whether any line was tagged as `kind`.
+    pub fn has_kind(&self, kind: LineKind) -> bool {
+        self.lines.iter().any(|l| l.kind == kind)
+    }
+}
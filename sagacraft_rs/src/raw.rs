@@ -0,0 +1,506 @@
+//! On-disk shape of an adventure file. [`RawAdventure::parse`] reads the
+//! JSON into a [`Value`] first, normalizes the keys of the adventure and of
+//! each room, item and monster, and, for a lenient load, drops the entries
+//! that don't deserialize. Only then is the result turned into a
+//! [`RawAdventure`] and converted into the runtime types in
+//! [`crate::game_state`].
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::game_state::{Barrier, DialogueLine, Item, ItemType, Monster, MonsterStatus, Recipe, Room, TimeOfDay};
+use crate::effects::Effect;
+use crate::settings::GameSettings;
+
+/// Keys the editors and the catalog write that the engine has no use for.
+/// They are dropped without a warning.
+const ADVENTURE_META_KEYS: &[&str] = &["id", "description", "version", "author", "recommended_level", "tags"];
+const ROOM_EDITOR_KEYS: &[&str] = &[
+    "items", "light_level", "ambient_sound", "environmental_effects",
+];
+
+/// File extension for hand-authored adventures that may use comments and
+/// trailing commas; see [`strip_json5`].
+pub const JSON5_EXTENSION: &str = "json5";
+
+fn default_title() -> String { "Untitled Adventure".to_string() }
+fn default_one() -> i32 { 1 }
+fn default_six() -> i32 { 6 }
+fn default_ten() -> i32 { 10 }
+fn default_hundred() -> i32 { 100 }
+fn default_true() -> bool { true }
+
+#[derive(Debug, Deserialize)]
+pub struct RawAdventure {
+    #[serde(default = "default_title")]
+    pub title: String,
+    #[serde(default)]
+    pub intro: String,
+    #[serde(default = "default_one")]
+    pub start_room: i32,
+    #[serde(default)]
+    pub rooms: Vec<RawRoom>,
+    #[serde(default)]
+    pub items: Vec<RawItem>,
+    #[serde(default)]
+    pub monsters: Vec<RawMonster>,
+    #[serde(default)]
+    pub quests: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub settings: Option<GameSettings>,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    #[serde(default)]
+    pub recipes: Vec<Recipe>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+impl RawAdventure {
+    /// Parse adventure JSON, tolerating differently cased or spaced keys
+    /// (`"Is Dark"` reads as `is_dark`). Returns a warning for every key that
+    /// was ignored because the loader doesn't know it.
+    pub fn parse(json: &str) -> Result<(Self, Vec<String>), serde_json::Error> {
+        Self::parse_with(json, false).map(|(raw, warnings, _)| (raw, warnings))
+    }
+
+    /// Like [`RawAdventure::parse`], but a room, item or monster that doesn't
+    /// deserialize is left out and reported instead of failing the whole file.
+    pub fn parse_lenient(json: &str) -> Result<(Self, Vec<String>, Vec<SkippedEntity>), serde_json::Error> {
+        Self::parse_with(json, true)
+    }
+
+    fn parse_with(json: &str, lenient: bool) -> Result<(Self, Vec<String>, Vec<SkippedEntity>), serde_json::Error> {
+        let mut value: Value = serde_json::from_str(json)?;
+        normalize_keys(&mut value);
+        for section in ["rooms", "items", "monsters"] {
+            if let Some(entries) = value.get_mut(section).and_then(Value::as_array_mut) {
+                entries.iter_mut().for_each(normalize_keys);
+            }
+        }
+        let mut skipped = Vec::new();
+        if lenient {
+            skipped.extend(retain_valid::<RawRoom>(&mut value, "rooms", "room"));
+            skipped.extend(retain_valid::<RawItem>(&mut value, "items", "item"));
+            skipped.extend(retain_valid::<RawMonster>(&mut value, "monsters", "monster"));
+        }
+        let raw: RawAdventure = serde_json::from_value(value)?;
+
+        let mut warnings = Vec::new();
+        let mut report = |what: String, unknown: &BTreeMap<String, Value>, expected: &[&str]| {
+            let keys: Vec<&str> = unknown.keys().map(String::as_str).filter(|k| !expected.contains(k)).collect();
+            if !keys.is_empty() {
+                warnings.push(format!("{}: ignored unknown keys: {}", what, keys.join(", ")));
+            }
+        };
+        report("adventure".to_string(), &raw.unknown, ADVENTURE_META_KEYS);
+        for room in &raw.rooms {
+            report(format!("room {}", room.id), &room.unknown, ROOM_EDITOR_KEYS);
+        }
+        for item in &raw.items {
+            report(format!("item {}", item.id), &item.unknown, &[]);
+        }
+        for monster in &raw.monsters {
+            report(format!("monster {}", monster.id), &monster.unknown, &[]);
+        }
+        Ok((raw, warnings, skipped))
+    }
+}
+
+/// An entry a lenient load left out because it didn't deserialize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntity {
+    /// `"room"`, `"item"` or `"monster"`.
+    pub kind: &'static str,
+    /// Position of the entry in its section of the file.
+    pub index: usize,
+    /// The entry's id, if it has a readable one.
+    pub id: Option<i32>,
+    pub error: String,
+}
+
+impl fmt::Display for SkippedEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.id {
+            Some(id) => write!(f, "{} {} (entry {}): {}", self.kind, id, self.index + 1, self.error),
+            None => write!(f, "{} entry {}: {}", self.kind, self.index + 1, self.error),
+        }
+    }
+}
+
+/// Drop the entries of the `section` array in `adventure` that don't
+/// deserialize as `T`, returning what was dropped. Editors with their own
+/// adventure model use this to open files the engine would load leniently.
+pub fn retain_valid<T: DeserializeOwned>(adventure: &mut Value, section: &str, kind: &'static str) -> Vec<SkippedEntity> {
+    let Some(entries) = adventure.get_mut(section).and_then(Value::as_array_mut) else {
+        return Vec::new();
+    };
+    let mut skipped = Vec::new();
+    let mut index = 0;
+    entries.retain(|entry| {
+        let parsed = serde_json::from_value::<T>(entry.clone());
+        if let Err(err) = &parsed {
+            skipped.push(SkippedEntity {
+                kind,
+                index,
+                id: entry.get("id").and_then(Value::as_i64).and_then(|id| i32::try_from(id).ok()),
+                error: err.to_string(),
+            });
+        }
+        index += 1;
+        parsed.is_ok()
+    });
+    skipped
+}
+
+/// Trim and lowercase the keys of a JSON object, turning inner spaces and
+/// hyphens into underscores. Nested values are left alone.
+fn normalize_keys(value: &mut Value) {
+    if let Value::Object(map) = value {
+        *map = std::mem::take(map)
+            .into_iter()
+            .map(|(key, v)| (key.trim().to_lowercase().replace([' ', '-'], "_"), v))
+            .collect();
+    }
+}
+
+/// Rewrite the JSON5 conveniences authors reach for most, `//` and `/* */`
+/// comments and trailing commas, into strict JSON. Other JSON5 syntax
+/// (unquoted keys, single quotes, ...) is passed through and will fail to parse.
+pub fn strip_json5(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    // Byte offset in `out` of a comma that is trailing if the next token closes
+    let mut pending_comma: Option<usize> = None;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                out.push(' ');
+                continue;
+            }
+            '}' | ']' => {
+                if let Some(at) = pending_comma {
+                    out.replace_range(at..at + 1, " ");
+                }
+                out.push(c);
+            }
+            ',' => {
+                pending_comma = Some(out.len());
+                out.push(c);
+                continue;
+            }
+            c if c.is_whitespace() => {
+                out.push(c);
+                continue;
+            }
+            _ => out.push(c),
+        }
+        pending_comma = None;
+    }
+    out
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawRoom {
+    #[serde(default)]
+    pub id: i32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub exits: BTreeMap<String, i32>,
+    #[serde(default, alias = "dark")]
+    pub is_dark: bool,
+    #[serde(default, alias = "checkpoint")]
+    pub is_checkpoint: bool,
+    #[serde(default)]
+    pub auto_save_on_enter: bool,
+    #[serde(default)]
+    pub hidden_exits: BTreeMap<String, i32>,
+    #[serde(default)]
+    pub is_outdoors: bool,
+    #[serde(default)]
+    pub exit_times: BTreeMap<String, Vec<TimeOfDay>>,
+    #[serde(default)]
+    pub is_safe_zone: bool,
+    #[serde(default)]
+    pub barriers: BTreeMap<String, Barrier>,
+    #[serde(default, alias = "trap")]
+    pub has_trap: bool,
+    #[serde(default)]
+    pub trap_damage: i32,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawItem {
+    #[serde(default)]
+    pub id: i32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Kept as a string so unknown types fall back to `Normal` instead of failing the load.
+    #[serde(default, rename = "type", alias = "item_type")]
+    pub item_type: Option<String>,
+    #[serde(default = "default_one")]
+    pub weight: i32,
+    #[serde(default)]
+    pub value: i32,
+    #[serde(default)]
+    pub is_weapon: bool,
+    #[serde(default)]
+    pub weapon_type: i32,
+    #[serde(default = "default_one")]
+    pub weapon_dice: i32,
+    #[serde(default = "default_six")]
+    pub weapon_sides: i32,
+    #[serde(default)]
+    pub is_armor: bool,
+    #[serde(default)]
+    pub armor_value: i32,
+    #[serde(default = "default_true", alias = "takeable")]
+    pub is_takeable: bool,
+    #[serde(default, alias = "wearable")]
+    pub is_wearable: bool,
+    #[serde(default)]
+    pub location: i32,
+    #[serde(default)]
+    pub min_hardiness: Option<i32>,
+    #[serde(default)]
+    pub min_level: Option<i32>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub durability: Option<i32>,
+    #[serde(default, alias = "light_source")]
+    pub is_light_source: bool,
+    #[serde(default)]
+    pub disarms_traps: bool,
+    #[serde(default)]
+    pub contents: Vec<i32>,
+    #[serde(default, alias = "open")]
+    pub is_open: bool,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawMonster {
+    #[serde(default)]
+    pub id: i32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_one", alias = "room")]
+    pub room_id: i32,
+    #[serde(default = "default_ten")]
+    pub hardiness: i32,
+    #[serde(default = "default_ten")]
+    pub agility: i32,
+    #[serde(default)]
+    pub friendliness: Option<String>,
+    #[serde(default = "default_hundred")]
+    pub courage: i32,
+    #[serde(default)]
+    pub weapon_id: Option<i32>,
+    #[serde(default)]
+    pub armor_worn: i32,
+    #[serde(default)]
+    pub gold: i32,
+    #[serde(default)]
+    pub abilities: Vec<String>,
+    #[serde(default)]
+    pub available_times: Vec<TimeOfDay>,
+    #[serde(default)]
+    pub faction: Option<String>,
+    #[serde(default)]
+    pub barks: Vec<String>,
+    #[serde(default)]
+    pub dialogue: Vec<DialogueLine>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+impl From<RawRoom> for Room {
+    fn from(raw: RawRoom) -> Self {
+        Room {
+            id: raw.id,
+            name: raw.name,
+            description: raw.description,
+            exits: raw.exits,
+            is_dark: raw.is_dark,
+            is_checkpoint: raw.is_checkpoint,
+            auto_save_on_enter: raw.auto_save_on_enter,
+            hidden_exits: raw.hidden_exits,
+            searched: false,
+            is_outdoors: raw.is_outdoors,
+            exit_times: raw.exit_times,
+            is_safe_zone: raw.is_safe_zone,
+            barriers: raw.barriers,
+            has_trap: raw.has_trap,
+            trap_damage: raw.trap_damage,
+            trap_sprung: false,
+        }
+    }
+}
+
+impl From<RawItem> for Item {
+    fn from(raw: RawItem) -> Self {
+        Item {
+            id: raw.id,
+            name: raw.name,
+            description: raw.description,
+            item_type: match raw.item_type.as_deref() {
+                Some("weapon") => ItemType::Weapon,
+                Some("armor") => ItemType::Armor,
+                Some("treasure") => ItemType::Treasure,
+                Some("readable") => ItemType::Readable,
+                Some("edible") => ItemType::Edible,
+                Some("drinkable") => ItemType::Drinkable,
+                Some("container") => ItemType::Container,
+                _ => ItemType::Normal,
+            },
+            weight: raw.weight,
+            value: raw.value,
+            is_weapon: raw.is_weapon,
+            weapon_type: raw.weapon_type,
+            weapon_dice: raw.weapon_dice,
+            weapon_sides: raw.weapon_sides,
+            is_armor: raw.is_armor,
+            armor_value: raw.armor_value,
+            is_takeable: raw.is_takeable,
+            is_wearable: raw.is_wearable,
+            location: raw.location,
+            min_hardiness: raw.min_hardiness,
+            min_level: raw.min_level,
+            hidden: raw.hidden,
+            durability: raw.durability,
+            max_durability: raw.durability,
+            is_light_source: raw.is_light_source,
+            disarms_traps: raw.disarms_traps,
+            contents: raw.contents,
+            is_open: raw.is_open,
+        }
+    }
+}
+
+impl From<RawMonster> for Monster {
+    fn from(raw: RawMonster) -> Self {
+        let friendliness = match raw.friendliness.as_deref() {
+            Some("friendly") => MonsterStatus::Friendly,
+            Some("hostile") => MonsterStatus::Hostile,
+            _ => MonsterStatus::Neutral,
+        };
+        let mut monster = Monster::new(
+            raw.id,
+            raw.name,
+            raw.description,
+            raw.room_id,
+            raw.hardiness,
+            raw.agility,
+            friendliness,
+            raw.courage,
+        );
+        monster.weapon_id = raw.weapon_id;
+        monster.armor_worn = raw.armor_worn;
+        monster.gold = raw.gold;
+        monster.abilities = raw.abilities;
+        monster.available_times = raw.available_times;
+        monster.faction = raw.faction;
+        monster.barks = raw.barks;
+        monster.dialogue = raw.dialogue;
+        monster
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_key_spellings_load_and_unknown_keys_warn() {
+        let (raw, warnings) = RawAdventure::parse(r#"{
+            "rooms": [{"id": 1, "name": "Cellar", "dark": true, " Is Checkpoint ": true, "colour": "grey"}],
+            "items": [{"id": 1, "name": "Lamp", "item_type": "treasure"}],
+            "monsters": [{"id": 1, "name": "Rat", "room": 1}]
+        }"#).unwrap();
+
+        assert!(raw.rooms[0].is_dark);
+        assert!(raw.rooms[0].is_checkpoint);
+        assert_eq!(raw.items[0].item_type.as_deref(), Some("treasure"));
+        assert_eq!(raw.monsters[0].room_id, 1);
+        assert_eq!(warnings, vec!["room 1: ignored unknown keys: colour".to_string()]);
+    }
+
+    #[test]
+    fn lenient_load_skips_a_broken_room_and_keeps_the_rest() {
+        let json = r#"{
+            "rooms": [
+                {"id": 1, "name": "Hall", "exits": {"north": 2}},
+                {"id": 2, "name": "Attic", "exits": {"south": "one"}},
+                {"id": 3, "name": "Cellar"}
+            ],
+            "items": [{"id": 1, "name": "Lamp", "location": 3}]
+        }"#;
+        let mut game = crate::game_state::AdventureGame::new(String::new());
+        assert!(game.load_adventure_str(json).is_err());
+
+        game.load_adventure_str_lenient(json).unwrap();
+        let mut rooms: Vec<i32> = game.rooms.keys().copied().collect();
+        rooms.sort();
+        assert_eq!(rooms, [1, 3]);
+        assert!(game.items.contains_key(&1));
+        assert_eq!(game.skipped_entities.len(), 1);
+        let skipped = &game.skipped_entities[0];
+        assert_eq!((skipped.kind, skipped.index, skipped.id), ("room", 1, Some(2)));
+        assert!(skipped.to_string().starts_with("room 2 (entry 2): invalid type"), "{}", skipped);
+    }
+
+    #[test]
+    fn json5_adventure_with_comments_and_trailing_commas_loads() {
+        let path = std::env::temp_dir().join(format!("sagacraft_json5_{}.json5", std::process::id()));
+        std::fs::write(&path, r#"{
+            // The only room, for now
+            "title": "Annotated",
+            "rooms": [
+                {"id": 1, "name": "Cell /* not a comment */", "exits": {},}, /* trailing comma */
+            ],
+        }"#).unwrap();
+
+        let mut game = crate::game_state::AdventureGame::new(path.to_string_lossy().into_owned());
+        game.load_adventure().unwrap();
+        assert_eq!(game.adventure_title, "Annotated");
+        assert_eq!(game.rooms[&1].name, "Cell /* not a comment */");
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,160 @@
+//! Recorded play sessions that can be re-run to reproduce a bug.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::adventure::AdventureError;
+use crate::engine::Engine;
+use crate::game_state::AdventureGame;
+use crate::save::SaveState;
+
+/// One command and the output it produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayTurn {
+    pub input: String,
+    pub output: Vec<String>,
+}
+
+/// Everything needed to re-run a session: the adventure, the RNG seed, each
+/// input with its output, and the state the session ended in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub adventure: String,
+    pub seed: u64,
+    pub turns: Vec<ReplayTurn>,
+    pub final_state: Option<SaveState>,
+}
+
+/// Why a replay did not reproduce its recording.
+#[derive(Debug)]
+pub enum ReplayError {
+    Load(String),
+    /// Turn `turn` (1-based) produced different output than was recorded.
+    Diverged { turn: usize, input: String, expected: Vec<String>, actual: Vec<String> },
+    /// Every turn matched but the game ended in a different state.
+    FinalStateMismatch,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Load(err) => write!(f, "could not load adventure: {err}"),
+            ReplayError::Diverged { turn, input, expected, actual } => write!(
+                f,
+                "turn {turn} ('{input}') diverged\n--- recorded\n{}\n--- replayed\n{}",
+                expected.join("\n"),
+                actual.join("\n")
+            ),
+            ReplayError::FinalStateMismatch => write!(f, "all turns matched but the final state differs"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AdventureError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), AdventureError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load the adventure with all built-in systems, feed it the recorded
+    /// inputs with the recorded seed, and check that every turn's output and
+    /// the final state match. Returns the number of turns replayed.
+    pub fn run(&self) -> Result<usize, ReplayError> {
+        let mut engine = Engine::load(&self.adventure).map_err(|e| ReplayError::Load(e.to_string()))?;
+        engine.game.seed_rng(self.seed);
+        // Keep checkpoint saves from overwriting the player's real autosave.
+        engine.game.autosave_path = std::env::temp_dir().join("sagacraft_replay.autosave.json");
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let actual = engine.send(&turn.input);
+            if actual != turn.output {
+                return Err(ReplayError::Diverged {
+                    turn: index + 1,
+                    input: turn.input.clone(),
+                    expected: turn.output.clone(),
+                    actual,
+                });
+            }
+        }
+        match &self.final_state {
+            Some(expected) if comparable(expected) != comparable(&SaveState::capture(&engine.game)) => {
+                Err(ReplayError::FinalStateMismatch)
+            }
+            _ => Ok(self.turns.len()),
+        }
+    }
+}
+
+/// `state` without the systems' own data, which can hold wall-clock times
+/// (when a quest was accepted, ...) that never match between runs.
+fn comparable(state: &SaveState) -> SaveState {
+    SaveState { systems: Default::default(), ..state.clone() }
+}
+
+impl AdventureGame {
+    /// Reseed the RNG with `seed` and start recording every command into a [`Replay`].
+    pub fn start_recording(&mut self, seed: u64) {
+        self.seed_rng(seed);
+        self.recording = Some(Replay {
+            adventure: self.adventure_file.clone(),
+            seed,
+            turns: Vec::new(),
+            final_state: None,
+        });
+    }
+
+    /// The replay recorded so far, stamped with the current state; recording
+    /// carries on.
+    pub fn recording_so_far(&self) -> Option<Replay> {
+        let mut replay = self.recording.clone()?;
+        replay.final_state = Some(SaveState::capture(self));
+        Some(replay)
+    }
+
+    /// Stop recording and return the replay, stamped with the current state.
+    pub fn finish_recording(&mut self) -> Option<Replay> {
+        let replay = self.recording_so_far();
+        self.recording = None;
+        replay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_session_replays_to_the_same_state() {
+        let path = std::env::temp_dir().join(format!("sagacraft_replay_{}.json", std::process::id()));
+        fs::write(&path, r#"{
+            "rooms": [
+                {"id": 1, "name": "Gate", "exits": {"north": 2}},
+                {"id": 2, "name": "Den", "exits": {"south": 1}}
+            ],
+            "monsters": [{"id": 1, "name": "Wolf", "room_id": 2, "hardiness": 30, "friendliness": "hostile"}]
+        }"#).unwrap();
+
+        let mut engine = Engine::load(path.to_str().unwrap()).unwrap();
+        engine.game.autosave_path = std::env::temp_dir().join("sagacraft_replay_test.autosave.json");
+        engine.game.start_recording(7);
+        for input in ["north", "attack wolf", "attack wolf", "flee", "look"] {
+            engine.send(input);
+        }
+        let replay = engine.game.finish_recording().unwrap();
+        assert_eq!(replay.turns.len(), 5);
+        assert_eq!(replay.run().unwrap(), 5);
+
+        let mut tampered = replay.clone();
+        tampered.turns[1].output = vec!["You miss.".to_string()];
+        assert!(matches!(tampered.run(), Err(ReplayError::Diverged { turn: 2, .. })));
+        let _ = fs::remove_file(&path);
+    }
+}
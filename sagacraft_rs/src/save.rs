@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::adventure::AdventureError;
+use crate::event_log::EventLog;
+use crate::game_state::{AdventureGame, MonsterStatus, Player};
+
+/// Mutable runtime state of a play session. The adventure definition itself
+/// (rooms, item and monster templates) is never written to a save.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveState {
+    pub adventure_title: String,
+    pub player: Player,
+    pub turn_count: i32,
+    pub game_over: bool,
+    pub respawn_room: i32,
+    /// item id -> location
+    pub item_locations: BTreeMap<i32, i32>,
+    /// Items that have been used up, combined or given away.
+    #[serde(default)]
+    pub removed_items: BTreeSet<i32>,
+    /// monster id -> runtime state
+    pub monsters: BTreeMap<i32, MonsterState>,
+    /// Rooms the player has searched.
+    #[serde(default)]
+    pub searched_rooms: BTreeSet<i32>,
+    /// Items that have not been found by searching yet.
+    #[serde(default)]
+    pub hidden_items: BTreeSet<i32>,
+    /// (room id, direction) of every barrier the player has cleared.
+    #[serde(default)]
+    pub cleared_barriers: BTreeSet<(i32, String)>,
+    #[serde(default)]
+    pub flags: BTreeSet<String>,
+    /// Indices of effects that have already fired.
+    #[serde(default)]
+    pub fired_effects: BTreeSet<usize>,
+    /// item id -> durability left, for items that wear out.
+    #[serde(default)]
+    pub item_durability: BTreeMap<i32, i32>,
+    /// container id -> ids of the items inside.
+    #[serde(default)]
+    pub container_contents: BTreeMap<i32, Vec<i32>>,
+    /// Containers the player has left open.
+    #[serde(default)]
+    pub open_containers: BTreeSet<i32>,
+    /// Rooms whose trap has gone off or been disarmed.
+    #[serde(default)]
+    pub sprung_traps: BTreeSet<i32>,
+    /// Recent events, when `gameplay.save_event_log` is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<EventLog>,
+    /// Systems' own state (quest progress, ...), keyed by system name.
+    #[serde(default)]
+    pub systems: BTreeMap<String, serde_json::Value>,
+}
+
+/// What a load menu shows about one save: "slot1 — Quiet Village, turn 42".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveMeta {
+    /// The file name without `.json`.
+    pub name: String,
+    pub path: PathBuf,
+    pub modified_at: SystemTime,
+    pub player_name: String,
+    /// Name of the room the player was in.
+    pub location: String,
+    pub turn_count: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonsterState {
+    pub room_id: i32,
+    pub current_health: i32,
+    pub is_dead: bool,
+    /// Missing from older saves, which keep the adventure's attitude.
+    #[serde(default)]
+    pub friendliness: Option<MonsterStatus>,
+}
+
+impl SaveState {
+    /// Capture the runtime state of `game`.
+    pub fn capture(game: &AdventureGame) -> Self {
+        Self {
+            adventure_title: game.adventure_title.clone(),
+            player: game.player.clone(),
+            turn_count: game.turn_count,
+            game_over: game.game_over,
+            respawn_room: game.respawn_room,
+            item_locations: game.items.iter().map(|(&id, item)| (id, item.location)).collect(),
+            removed_items: game.removed_items.keys().copied().collect(),
+            monsters: game.monsters.iter()
+                .map(|(&id, m)| (id, MonsterState {
+                    room_id: m.room_id,
+                    current_health: m.current_health,
+                    is_dead: m.is_dead,
+                    friendliness: Some(m.friendliness.clone()),
+                }))
+                .collect(),
+            searched_rooms: game.rooms.values().filter(|r| r.searched).map(|r| r.id).collect(),
+            hidden_items: game.items.values().filter(|i| i.hidden).map(|i| i.id).collect(),
+            cleared_barriers: game.rooms.values()
+                .flat_map(|r| r.barriers.iter()
+                    .filter(|(_, barrier)| barrier.cleared)
+                    .map(|(direction, _)| (r.id, direction.clone())))
+                .collect(),
+            flags: game.flags.clone(),
+            fired_effects: game.fired_effects.clone(),
+            item_durability: game.items.iter()
+                .filter_map(|(&id, item)| item.durability.map(|d| (id, d)))
+                .collect(),
+            container_contents: game.items.iter()
+                .filter(|(_, item)| !item.contents.is_empty())
+                .map(|(&id, item)| (id, item.contents.clone()))
+                .collect(),
+            open_containers: game.items.values().filter(|i| i.is_open).map(|i| i.id).collect(),
+            sprung_traps: game.rooms.values().filter(|r| r.trap_sprung).map(|r| r.id).collect(),
+            event_log: game.settings.gameplay.save_event_log.then(|| game.event_log.clone()),
+            systems: game.system_save_data(),
+        }
+    }
+
+    /// Write the save to `path`. The JSON goes to a temporary file next to
+    /// it first and is then renamed into place, so a crash mid-write leaves
+    /// the old save intact.
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let temp = with_suffix(path, ".tmp");
+        fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        if let Err(err) = fs::rename(&temp, path) {
+            let _ = fs::remove_file(&temp);
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Put this runtime state back onto `game`, which must already have
+    /// loaded the adventure the save was made from. Ids the adventure no
+    /// longer has are skipped.
+    pub fn apply(self, game: &mut AdventureGame) -> Result<(), Box<dyn std::error::Error>> {
+        if self.adventure_title != game.adventure_title {
+            return Err(Box::new(AdventureError::Validation(format!(
+                "save is for '{}', not '{}'",
+                self.adventure_title, game.adventure_title
+            ))));
+        }
+        game.restore_system_data(&self.systems)?;
+        game.player = self.player;
+        game.turn_count = self.turn_count;
+        game.game_over = self.game_over;
+        game.respawn_room = self.respawn_room;
+        // Items gone now but not in the save come back (undo); items the
+        // save says are gone leave the world.
+        let returning: Vec<i32> = game.removed_items.keys()
+            .filter(|id| !self.removed_items.contains(id))
+            .copied()
+            .collect();
+        for id in returning {
+            if let Some(item) = game.removed_items.remove(&id) {
+                game.items.insert(id, item);
+            }
+        }
+        for &id in &self.removed_items {
+            game.discard_item(id);
+        }
+        for (id, item) in game.items.iter_mut() {
+            if let Some(&location) = self.item_locations.get(id) {
+                item.location = location;
+            }
+            item.hidden = self.hidden_items.contains(id);
+            if let Some(&durability) = self.item_durability.get(id) {
+                item.durability = Some(durability);
+            }
+            item.contents = self.container_contents.get(id).cloned().unwrap_or_default();
+            item.is_open = self.open_containers.contains(id);
+        }
+        for (id, state) in &self.monsters {
+            if let Some(monster) = game.monsters.get_mut(id) {
+                monster.room_id = state.room_id;
+                monster.current_health = state.current_health;
+                monster.is_dead = state.is_dead;
+                if let Some(friendliness) = &state.friendliness {
+                    monster.friendliness = friendliness.clone();
+                }
+            }
+        }
+        for room in game.rooms.values_mut() {
+            room.searched = self.searched_rooms.contains(&room.id);
+            room.trap_sprung = self.sprung_traps.contains(&room.id);
+            if room.searched {
+                // Searching opened these up for good
+                let found = std::mem::take(&mut room.hidden_exits);
+                room.exits.extend(found);
+            }
+            for (direction, barrier) in room.barriers.iter_mut() {
+                barrier.cleared = self.cleared_barriers.contains(&(room.id, direction.clone()));
+            }
+        }
+        game.flags = self.flags;
+        game.fired_effects = self.fired_effects;
+        if let Some(log) = self.event_log {
+            game.event_log = log;
+        }
+        Ok(())
+    }
+}
+
+/// `path` with `suffix` added to the file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Where [`AdventureGame::save_state`] keeps the save it replaced:
+/// `<path>.bak`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Put the save that the last [`AdventureGame::save_state`] to `path`
+/// replaced back in place. The backup is checked to be a readable save
+/// first, and is kept.
+pub fn restore_backup(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    SaveState::read(&backup_path(path))?.write(path)
+}
+
+impl AdventureGame {
+    /// Write the current runtime state to `path` as JSON. A save already at
+    /// `path` is copied to [`backup_path`] first.
+    pub fn save_state(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let state = SaveState::capture(self);
+        if path.exists() {
+            fs::copy(path, backup_path(path))?;
+        }
+        state.write(path)
+    }
+
+    /// Restore runtime state written by [`AdventureGame::save_state`] onto
+    /// this game, which must already have its adventure loaded.
+    pub fn load_state(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        SaveState::read(path)?.apply(self)
+    }
+
+    /// The saves in `dir` made from this adventure, newest first. Files that
+    /// aren't saves, or are saves of another adventure, are left out.
+    pub fn list_saves(&self, dir: &Path) -> Result<Vec<SaveMeta>, Box<dyn std::error::Error>> {
+        let mut saves = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Ok(state) = SaveState::read(&path) else { continue };
+            if state.adventure_title != self.adventure_title {
+                continue;
+            }
+            let room = state.player.current_room;
+            saves.push(SaveMeta {
+                name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+                modified_at: fs::metadata(&path)?.modified()?,
+                player_name: state.player.name,
+                location: self.rooms.get(&room).map_or_else(|| format!("room {}", room), |r| r.name.clone()),
+                turn_count: state.turn_count,
+                path,
+            });
+        }
+        saves.sort_by(|a, b| b.modified_at.cmp(&a.modified_at).then_with(|| a.name.cmp(&b.name)));
+        Ok(saves)
+    }
+
+    /// Write a checkpoint save to [`AdventureGame::autosave_path`], unless the
+    /// adventure has turned `gameplay.auto_save` off. Returns whether a save was written.
+    pub fn autosave(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.settings.gameplay.auto_save {
+            return Ok(false);
+        }
+        self.save_state(&self.autosave_path)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SaveState;
+    use crate::engine::Engine;
+    use crate::game_state::MonsterStatus;
+
+    const ADVENTURE: &str = r#"{
+        "title": "Two Rooms",
+        "rooms": [
+            {"id": 1, "name": "Porch", "exits": {"north": 2}},
+            {"id": 2, "name": "Parlour", "exits": {"south": 1}}
+        ],
+        "items": [
+            {"id": 1, "name": "Umbrella", "location": 2, "is_takeable": true},
+            {"id": 2, "name": "Apple", "type": "edible", "location": 1, "is_takeable": true, "value": 5},
+            {"id": 3, "name": "Flint", "location": 1, "is_takeable": true},
+            {"id": 4, "name": "Steel", "location": 1, "is_takeable": true},
+            {"id": 5, "name": "Tinderbox", "location": 99}
+        ],
+        "recipes": [{"inputs": [3, 4], "output": 5}],
+        "monsters": [{"id": 1, "name": "Butler", "room_id": 2, "friendliness": "neutral"}]
+    }"#;
+
+    fn fresh_game() -> Engine {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(ADVENTURE).unwrap();
+        engine
+    }
+
+    #[test]
+    fn saved_session_loads_into_a_fresh_game() {
+        let path = std::env::temp_dir().join(format!("sagacraft_save_{}.json", std::process::id()));
+        let mut engine = fresh_game();
+        engine.send("north");
+        engine.send("take umbrella");
+        engine.game.save_state(&path).unwrap();
+
+        let mut restored = fresh_game();
+        restored.game.load_state(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(restored.game.player.current_room, 2);
+        assert_eq!(restored.game.player.inventory, [1]);
+        assert_eq!(restored.game.items[&1].location, 0);
+        assert_eq!(restored.game.turn_count, engine.game.turn_count);
+    }
+
+    #[test]
+    fn used_up_items_and_changed_attitudes_survive_a_reload() {
+        let path = std::env::temp_dir().join(format!("sagacraft_consumed_{}.json", std::process::id()));
+        let mut engine = fresh_game();
+        engine.send("take apple");
+        engine.send("use apple");
+        engine.send("take flint");
+        engine.send("take steel");
+        engine.send("combine flint with steel");
+        assert_eq!(engine.game.player.inventory, [5]);
+        engine.game.monsters.get_mut(&1).unwrap().friendliness = MonsterStatus::Hostile;
+        engine.game.save_state(&path).unwrap();
+
+        let mut restored = fresh_game();
+        restored.game.load_state(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(super::backup_path(&path));
+        for id in [2, 3, 4] {
+            assert!(!restored.game.items.contains_key(&id), "item {id} came back");
+        }
+        assert_eq!(restored.game.player.inventory, [5]);
+        assert!(!restored.game.look().contains("Apple"));
+        assert_eq!(restored.game.monsters[&1].friendliness, MonsterStatus::Hostile);
+    }
+
+    #[test]
+    fn saving_again_keeps_the_previous_save_as_a_backup() {
+        let path = std::env::temp_dir().join(format!("sagacraft_backup_{}.json", std::process::id()));
+        let backup = super::backup_path(&path);
+        let mut engine = fresh_game();
+        engine.game.save_state(&path).unwrap();
+        assert!(!backup.exists());
+        engine.send("north");
+        engine.game.save_state(&path).unwrap();
+
+        let first = SaveState::read(&backup).unwrap();
+        let second = SaveState::read(&path).unwrap();
+        assert_eq!(first.player.current_room, 1);
+        assert_eq!(second.player.current_room, 2);
+
+        super::restore_backup(&path).unwrap();
+        let restored = SaveState::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&backup);
+        assert_eq!(restored, first);
+    }
+
+    #[test]
+    fn saves_are_listed_newest_first() {
+        let dir = std::env::temp_dir().join(format!("sagacraft_slots_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut engine = fresh_game();
+        engine.game.save_state(&dir.join("slot1.json")).unwrap();
+        engine.send("north");
+        engine.game.save_state(&dir.join("slot2.json")).unwrap();
+        std::fs::write(dir.join("notes.json"), "{}").unwrap();
+        let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options().write(true).open(dir.join("slot2.json")).unwrap()
+            .set_modified(an_hour_ago).unwrap();
+
+        let saves = engine.game.list_saves(&dir).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        let slots: Vec<(&str, &str, i32)> = saves.iter()
+            .map(|save| (save.name.as_str(), save.location.as_str(), save.turn_count))
+            .collect();
+        assert_eq!(slots, [("slot1", "Porch", 0), ("slot2", "Parlour", 1)]);
+        assert!(saves[0].modified_at > saves[1].modified_at);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::engine::Engine;
+use crate::game_state::AdventureGame;
+use crate::output::StepResult;
+
+pub type SessionId = u64;
+
+/// Independent play sessions shared across threads, e.g. behind a network
+/// front-end. Each session is a separate [`AdventureGame`] with its own RNG;
+/// commands for different sessions run concurrently.
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: Mutex<HashMap<SessionId, Arc<Mutex<AdventureGame>>>>,
+    next_id: AtomicU64,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `adventure_path` with all built-in systems into a new session.
+    /// Each session autosaves to its own `<adventure>.session<id>.autosave.json`
+    /// so sessions on the same adventure don't overwrite each other's checkpoints.
+    pub fn create_session(&self, adventure_path: &str) -> Result<SessionId, Box<dyn std::error::Error>> {
+        let mut engine = Engine::load(adventure_path)?;
+        let id = self.reserve_id();
+        engine.game.autosave_path = Path::new(adventure_path).with_extension(format!("session{}.autosave.json", id));
+        self.lock_sessions().insert(id, Arc::new(Mutex::new(engine.game)));
+        Ok(id)
+    }
+
+    /// Add an already set-up game as a new session. Its `autosave_path` is
+    /// left as the caller set it.
+    pub fn insert(&self, game: AdventureGame) -> SessionId {
+        let id = self.reserve_id();
+        self.lock_sessions().insert(id, Arc::new(Mutex::new(game)));
+        id
+    }
+
+    fn reserve_id(&self) -> SessionId {
+        self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Run one command in session `id`. Returns `None` if there is no such session.
+    pub fn step(&self, id: SessionId, input: &str) -> Option<StepResult> {
+        let game = self.lock_sessions().get(&id).cloned()?;
+        let mut game = game.lock().unwrap_or_else(PoisonError::into_inner);
+        Some(game.step(input))
+    }
+
+    /// Drop session `id`. Returns whether it existed.
+    pub fn end_session(&self, id: SessionId) -> bool {
+        self.lock_sessions().remove(&id).is_some()
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.lock_sessions().len()
+    }
+
+    fn lock_sessions(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Arc<Mutex<AdventureGame>>>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_on_the_same_adventure_are_independent() {
+        let path = std::env::temp_dir().join(format!("sagacraft_sessions_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{
+            "rooms": [
+                {"id": 1, "name": "Gate", "exits": {"north": 2}},
+                {"id": 2, "name": "Keep", "exits": {"south": 1}}
+            ]
+        }"#).unwrap();
+        let manager = SessionManager::new();
+        let first = manager.create_session(path.to_str().unwrap()).unwrap();
+        let second = manager.create_session(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| manager.step(first, "north"));
+        });
+        let first_look = manager.step(first, "look").unwrap().to_plain_lines().join("\n");
+        let second_look = manager.step(second, "look").unwrap().to_plain_lines().join("\n");
+        assert!(first_look.contains("Keep"));
+        assert!(second_look.contains("Gate"));
+
+        let autosave = |id| manager.lock_sessions()[&id].lock().unwrap().autosave_path.clone();
+        assert_ne!(autosave(first), autosave(second));
+        assert!(autosave(first).to_string_lossy().ends_with(&format!(".session{}.autosave.json", first)));
+
+        assert!(manager.end_session(first));
+        assert!(manager.step(first, "look").is_none());
+        assert_eq!(manager.session_count(), 1);
+    }
+}
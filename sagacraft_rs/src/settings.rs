@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Environment variables starting with this override the adventure's
+/// settings when it loads; see [`GameSettings::apply_env_overrides`].
+pub const SETTINGS_ENV_PREFIX: &str = "SAGACRAFT_";
+
+/// Per-adventure engine settings, read from the adventure JSON's `"settings"` object.
+/// Every field is optional; missing keys fall back to the defaults below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub inventory: InventorySettings,
+    pub gameplay: GameplaySettings,
+    pub clock: ClockSettings,
+    pub economy: EconomySettings,
+    pub score: ScoreSettings,
+    pub ui: UiSettings,
+    /// Locale file overriding engine messages, relative to the adventure file.
+    pub locale: Option<String>,
+    /// Systems switched on or off by name, e.g. `{"combat": false}`. Systems
+    /// not listed are on.
+    pub systems: BTreeMap<String, bool>,
+    /// Top-level spelling of `gameplay.turn_limit`; see [`GameSettings::apply_aliases`].
+    #[serde(skip_serializing)]
+    turn_limit: Option<i32>,
+    /// Top-level spelling of `clock.show_time`.
+    #[serde(skip_serializing)]
+    enable_time: Option<bool>,
+}
+
+impl GameSettings {
+    /// Move the top-level `turn_limit` and `enable_time` settings to
+    /// `gameplay.turn_limit` and `clock.show_time`. A turn limit already set
+    /// under `gameplay` wins.
+    pub fn apply_aliases(&mut self) {
+        if let Some(limit) = self.turn_limit.take() {
+            self.gameplay.turn_limit.get_or_insert(limit);
+        }
+        if let Some(enabled) = self.enable_time.take() {
+            self.clock.show_time = enabled;
+        }
+    }
+
+    /// The setting at dotted path `key` as JSON, or `None` if there's no
+    /// such setting. Unset optional settings are `Some(Value::Null)`.
+    pub fn get_dotted(&self, key: &str) -> Option<Value> {
+        let root = serde_json::to_value(self).ok()?;
+        root.pointer(&format!("/{}", key.replace('.', "/"))).cloned()
+    }
+
+    /// The setting at `key` as a bool, accepting `"true"`/`"false"` text,
+    /// or `default` if it's missing, unset or something else.
+    pub fn get_bool(&self, key: &str, default: bool) -> bool {
+        match self.get_dotted(key) {
+            Some(Value::Bool(value)) => value,
+            Some(Value::String(text)) => text.parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    /// The setting at `key` as an integer, accepting numeric text, or
+    /// `default` if it's missing, unset or something else.
+    pub fn get_i64(&self, key: &str, default: i64) -> i64 {
+        match self.get_dotted(key) {
+            Some(Value::Number(number)) => number.as_i64().unwrap_or(default),
+            Some(Value::String(text)) => text.trim().parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    /// The setting at `key` as text (numbers and bools are written out), or
+    /// `default` if it's missing, unset or a section.
+    pub fn get_string(&self, key: &str, default: &str) -> String {
+        match self.get_dotted(key) {
+            Some(Value::String(text)) => text,
+            Some(value @ (Value::Bool(_) | Value::Number(_))) => value.to_string(),
+            _ => default.to_string(),
+        }
+    }
+
+    /// Fill in settings the adventure left unset from `defaults`, shaped like
+    /// the `"settings"` object. Only optional settings that are `None` and
+    /// `systems` entries that aren't listed count as unset; nothing already
+    /// set is overwritten. Returns a message for each default that doesn't
+    /// fit its setting.
+    pub fn merge_defaults(&mut self, defaults: &Map<String, Value>) -> Vec<String> {
+        let mut leaves = Vec::new();
+        collect_leaves("", defaults, &mut leaves);
+        let mut problems = Vec::new();
+        for (key, value) in leaves {
+            if matches!(self.get_dotted(&key), None | Some(Value::Null))
+                && let Err(err) = self.set_dotted(&key, value)
+            {
+                problems.push(err);
+            }
+        }
+        problems
+    }
+
+    /// Set the setting at dotted path `key` (`"gameplay.turn_limit"`) to
+    /// `value`. Fails, leaving the settings alone, if there is no such
+    /// setting or `value` is the wrong type for it.
+    pub fn set_dotted(&mut self, key: &str, value: Value) -> Result<(), String> {
+        let mut root = serde_json::to_value(&*self).map_err(|err| err.to_string())?;
+        let mut node = &mut root;
+        for part in key.split('.') {
+            node = node.as_object_mut()
+                .ok_or_else(|| format!("'{}' is not a setting", key))?
+                .entry(part)
+                .or_insert(Value::Null);
+        }
+        *node = value.clone();
+        let updated: GameSettings = serde_json::from_value(root)
+            .map_err(|err| format!("bad value for '{}': {}", key, err))?;
+        // Keys serde doesn't know are dropped without complaint, so check the
+        // value actually landed.
+        let pointer = format!("/{}", key.replace('.', "/"));
+        let landed = serde_json::to_value(&updated).ok()
+            .is_some_and(|written| written.pointer(&pointer) == Some(&value));
+        if !landed {
+            return Err(format!("'{}' is not a setting", key));
+        }
+        *self = updated;
+        Ok(())
+    }
+
+    /// Override settings from environment variables named `prefix` followed
+    /// by the section and setting, e.g. `SAGACRAFT_GAMEPLAY_TURN_LIMIT=200`
+    /// sets `gameplay.turn_limit`. Values are read as JSON where they parse,
+    /// and as plain strings otherwise. Variables whose first word isn't a
+    /// settings section are ignored. Returns a message for each variable that
+    /// couldn't be applied.
+    pub fn apply_env_overrides(&mut self, prefix: &str) -> Vec<String> {
+        let sections = match serde_json::to_value(&*self) {
+            Ok(Value::Object(sections)) => sections,
+            _ => return Vec::new(),
+        };
+        let mut overrides: Vec<(String, String, String)> = std::env::vars()
+            .filter_map(|(name, value)| {
+                let rest = name.strip_prefix(prefix)?.to_lowercase();
+                let key = match rest.split_once('_') {
+                    Some((section, setting)) => format!("{}.{}", section, setting),
+                    None => rest,
+                };
+                let section = key.split('.').next().unwrap_or_default();
+                sections.contains_key(section).then_some((name, key, value))
+            })
+            .collect();
+        overrides.sort();
+        let mut problems = Vec::new();
+        for (name, key, raw) in overrides {
+            let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+            if let Err(err) = self.set_dotted(&key, value) {
+                problems.push(format!("{}: {}", name, err));
+            }
+        }
+        problems
+    }
+}
+
+/// Every non-object value under `object`, with its dotted path.
+fn collect_leaves(prefix: &str, object: &Map<String, Value>, leaves: &mut Vec<(String, Value)>) {
+    for (name, value) in object {
+        let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        match value {
+            Value::Object(inner) => collect_leaves(&key, inner, leaves),
+            _ => leaves.push((key, value.clone())),
+        }
+    }
+}
+
+/// How carrying capacity is limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryMode {
+    /// Total item weight may not exceed `hardiness * 10`.
+    #[default]
+    Weight,
+    /// At most `max_slots` distinct items; identical items stack in one slot.
+    Slots,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InventorySettings {
+    pub mode: InventoryMode,
+    pub max_slots: usize,
+}
+
+impl Default for InventorySettings {
+    fn default() -> Self {
+        Self {
+            mode: InventoryMode::Weight,
+            max_slots: 10,
+        }
+    }
+}
+
+/// What happens when the player's health reaches zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeathBehavior {
+    /// The game is over.
+    #[default]
+    End,
+    /// Return to the last checkpoint (or the start room) at half health.
+    Respawn,
+    /// Like `Respawn`, but the inventory is left behind in the room of death.
+    Drop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    pub death: DeathBehavior,
+    /// Whether checkpoint rooms, flagged quests and `save_interval` write autosaves.
+    pub auto_save: bool,
+    /// Autosave every N turns; `None` disables periodic saves.
+    pub save_interval: Option<i32>,
+    /// Refuse to load an adventure whose exits lead to missing rooms, instead
+    /// of removing those exits with a warning.
+    pub strict_load: bool,
+    /// Write the event log into saves alongside the game state.
+    pub save_event_log: bool,
+    /// Entries the event log keeps before dropping the oldest.
+    pub event_log_capacity: usize,
+    /// The game ends once `turn_count` passes this many turns.
+    pub turn_limit: Option<i32>,
+    /// Offer the `systems`, `enable` and `disable` commands.
+    pub debug_commands: bool,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            death: DeathBehavior::End,
+            auto_save: true,
+            save_interval: None,
+            strict_load: false,
+            save_event_log: false,
+            event_log_capacity: crate::event_log::EVENT_LOG_CAPACITY,
+            turn_limit: None,
+            debug_commands: false,
+        }
+    }
+}
+
+/// Day/night cycle timing. The clock advances with `turn_count`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSettings {
+    /// Turns in one full day.
+    pub day_length: i32,
+    /// Hour of day (0-23) the adventure starts at.
+    pub start_hour: i32,
+    /// Mention the time of day in room descriptions.
+    pub show_time: bool,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        Self {
+            day_length: 48,
+            start_hour: 8,
+            show_time: false,
+        }
+    }
+}
+
+/// What counts towards the player's score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreSettings {
+    /// Room treasures must be left in to score. With no trophy room,
+    /// treasures score while they are carried.
+    pub trophy_room: Option<i32>,
+    /// Points for each completed quest.
+    pub quest_points: i32,
+}
+
+impl Default for ScoreSettings {
+    fn default() -> Self {
+        Self {
+            trophy_room: None,
+            quest_points: 50,
+        }
+    }
+}
+
+/// Front-end presentation hints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Prompt template for the CLI player. `{room}`, `{health}`, `{gold}`
+    /// and `{turn}` are filled in each turn; `None` keeps the plain `"> "`.
+    pub prompt: Option<String>,
+}
+
+/// How gold interacts with the rest of the game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EconomySettings {
+    /// Weight of every 100 gold carried, counted against the weight limit.
+    /// 0 (the default) makes gold weightless.
+    pub gold_weight: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_overrides_settings_from_the_file() {
+        let mut settings: GameSettings = serde_json::from_str(
+            r#"{"gameplay": {"turn_limit": 100, "death": "respawn"}}"#
+        ).unwrap();
+        // A prefix of its own so other tests' loads don't pick these up
+        let prefix = "SAGACRAFT_SETTINGS_TEST_";
+        // SAFETY: no other test reads or writes variables with this prefix.
+        unsafe {
+            std::env::set_var("SAGACRAFT_SETTINGS_TEST_GAMEPLAY_TURN_LIMIT", "40");
+            std::env::set_var("SAGACRAFT_SETTINGS_TEST_UI_PROMPT", "{room}> ");
+            std::env::set_var("SAGACRAFT_SETTINGS_TEST_SYSTEMS_COMBAT", "false");
+            std::env::set_var("SAGACRAFT_SETTINGS_TEST_CLOCK_DAY_LENGTH", "long");
+            std::env::set_var("SAGACRAFT_SETTINGS_TEST_CLOCK_NO_SUCH", "1");
+        }
+
+        let problems = settings.apply_env_overrides(prefix);
+        assert_eq!(settings.gameplay.turn_limit, Some(40));
+        assert_eq!(settings.gameplay.death, DeathBehavior::Respawn);
+        assert_eq!(settings.ui.prompt.as_deref(), Some("{room}> "));
+        assert_eq!(settings.systems.get("combat"), Some(&false));
+        assert_eq!(settings.clock.day_length, 48);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("SAGACRAFT_SETTINGS_TEST_CLOCK_DAY_LENGTH: bad value for 'clock.day_length'"));
+        assert_eq!(problems[1], "SAGACRAFT_SETTINGS_TEST_CLOCK_NO_SUCH: 'clock.no_such' is not a setting");
+    }
+
+    #[test]
+    fn top_level_turn_limit_and_enable_time_are_aliases() {
+        let mut settings: GameSettings = serde_json::from_str(r#"{"turn_limit": 3, "enable_time": true}"#).unwrap();
+        settings.apply_aliases();
+        assert_eq!(settings.gameplay.turn_limit, Some(3));
+        assert!(settings.clock.show_time);
+        assert!(!serde_json::to_string(&settings).unwrap().contains("enable_time"));
+
+        let mut settings: GameSettings = serde_json::from_str(
+            r#"{"turn_limit": 3, "gameplay": {"turn_limit": 10}}"#
+        ).unwrap();
+        settings.apply_aliases();
+        assert_eq!(settings.gameplay.turn_limit, Some(10));
+    }
+
+    #[test]
+    fn typed_getters_read_the_default_settings() {
+        let settings = GameSettings::default();
+        assert!(settings.get_bool("gameplay.auto_save", false));
+        assert!(!settings.get_bool("gameplay.strict_load", true));
+        assert_eq!(settings.get_i64("clock.day_length", 0), 48);
+        assert_eq!(settings.get_i64("gameplay.turn_limit", 500), 500);
+        assert_eq!(settings.get_string("inventory.mode", ""), "weight");
+        assert_eq!(settings.get_string("score.quest_points", ""), "50");
+        assert_eq!(settings.get_string("clock", "none"), "none");
+        assert_eq!(settings.get_i64("inventory.mode", -1), -1);
+        assert!(settings.get_bool("no.such.setting", true));
+    }
+
+    #[test]
+    fn merged_defaults_only_fill_unset_settings() {
+        let mut settings: GameSettings = serde_json::from_str(
+            r#"{"gameplay": {"save_interval": 5}, "ui": {"prompt": "$ "}, "systems": {"combat": true}}"#
+        ).unwrap();
+        let defaults = serde_json::json!({
+            "gameplay": {"save_interval": 20, "turn_limit": 300, "death": "drop"},
+            "ui": {"prompt": "> "},
+            "systems": {"combat": false, "clock": false}
+        });
+
+        let problems = settings.merge_defaults(defaults.as_object().unwrap());
+        assert!(problems.is_empty(), "{problems:?}");
+        assert_eq!(settings.gameplay.save_interval, Some(5));
+        assert_eq!(settings.gameplay.turn_limit, Some(300));
+        assert_eq!(settings.gameplay.death, DeathBehavior::End);
+        assert_eq!(settings.ui.prompt.as_deref(), Some("$ "));
+        assert_eq!(settings.systems.get("combat"), Some(&true));
+        assert_eq!(settings.systems.get("clock"), Some(&false));
+    }
+}
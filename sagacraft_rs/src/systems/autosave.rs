@@ -0,0 +1,75 @@
+use crate::game_state::{AdventureGame, GameEvent};
+use crate::systems::System;
+
+/// Writes checkpoint saves when the player enters an `auto_save_on_enter`
+/// room and, if `gameplay.save_interval` is set, every N turns.
+#[derive(Debug, Default)]
+pub struct AutosaveSystem;
+
+impl AutosaveSystem {
+    fn save(game: &AdventureGame) -> Option<String> {
+        match game.autosave() {
+            Ok(true) => Some("[Checkpoint saved.]".to_string()),
+            Ok(false) => None,
+            Err(err) => {
+                game.logger.error(&format!("autosave to {} failed: {}", game.autosave_path.display(), err));
+                Some(format!("[Autosave failed: {}]", err))
+            }
+        }
+    }
+}
+
+impl System for AutosaveSystem {
+    fn name(&self) -> &'static str {
+        "autosave"
+    }
+
+    fn on_command(&mut self, _command: &str, _args: &[&str], _game: &mut AdventureGame) -> Option<String> {
+        None
+    }
+
+    fn on_turn(&mut self, game: &mut AdventureGame) -> Option<String> {
+        match game.settings.gameplay.save_interval {
+            Some(interval) if interval > 0 && game.turn_count % interval == 0 => Self::save(game),
+            _ => None,
+        }
+    }
+
+    fn on_events(&mut self, events: &[GameEvent], game: &mut AdventureGame) -> Option<String> {
+        let checkpoint = events.iter().any(|e| matches!(e,
+            GameEvent::RoomEntered { room_id } if game.rooms.get(room_id).is_some_and(|r| r.auto_save_on_enter)
+        ));
+        if checkpoint { Self::save(game) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::Room;
+    use crate::systems::BasicWorldSystem;
+
+    #[test]
+    fn entering_a_checkpoint_room_writes_a_save() {
+        let path = std::env::temp_dir().join(format!("sagacraft_autosave_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut game = AdventureGame::new(String::new());
+        let mut gate = Room::new(1, "Gate".to_string(), String::new());
+        gate.exits.insert("north".to_string(), 2);
+        let mut keep = Room::new(2, "Keep".to_string(), String::new());
+        keep.auto_save_on_enter = true;
+        game.rooms.insert(1, gate);
+        game.rooms.insert(2, keep);
+        game.player.current_room = 1;
+        game.autosave_path = path.clone();
+        game.add_system(Box::new(BasicWorldSystem));
+        game.add_system(Box::new(AutosaveSystem));
+
+        let out = game.process_command("north").join("\n");
+        assert!(out.contains("[Checkpoint saved.]"));
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["player"]["current_room"], 2);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,98 +1,390 @@
-use crate::game_state::{AdventureGame, MonsterStatus};
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::directions::canonical_reverse;
+use crate::game_state::AdventureGame;
+use crate::output::Reply;
 use crate::systems::System;
 
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("look", &["l"], ArgShape::OptionalTarget("at|in <thing>"), "Look around, at something, or into a container"),
+    CommandSpec::new("go", &["move"], ArgShape::Direction, "Move in a direction"),
+    CommandSpec::new("north", &["n"], ArgShape::None, "Go north"),
+    CommandSpec::new("south", &["s"], ArgShape::None, "Go south"),
+    CommandSpec::new("east", &["e"], ArgShape::None, "Go east"),
+    CommandSpec::new("west", &["w"], ArgShape::None, "Go west"),
+    CommandSpec::new("northeast", &["ne"], ArgShape::None, "Go northeast"),
+    CommandSpec::new("northwest", &["nw"], ArgShape::None, "Go northwest"),
+    CommandSpec::new("southeast", &["se"], ArgShape::None, "Go southeast"),
+    CommandSpec::new("southwest", &["sw"], ArgShape::None, "Go southwest"),
+    CommandSpec::new("up", &["u"], ArgShape::None, "Go up"),
+    CommandSpec::new("down", &["d"], ArgShape::None, "Go down"),
+    CommandSpec::new("back", &["retreat"], ArgShape::None, "Go back the way you came"),
+    CommandSpec::new("search", &[], ArgShape::OptionalTarget("container"), "Search the room, or an open container, for hidden things"),
+    CommandSpec::new("disarm", &[], ArgShape::Direction, "Disarm a trap in the room that way"),
+    CommandSpec::new("help", &["?"], ArgShape::None, "Show this help"),
+    CommandSpec::new("systems", &[], ArgShape::None, "Debug: list game systems and whether they're on").debug_only(),
+    CommandSpec::new("enable", &[], ArgShape::Target("system"), "Debug: switch a game system on").debug_only(),
+    CommandSpec::new("disable", &[], ArgShape::Target("system"), "Debug: switch a game system off").debug_only(),
+];
+
 #[derive(Debug, Default)]
 pub struct BasicWorldSystem;
 
 impl BasicWorldSystem {
-    /// Expand single-letter direction abbreviations to full words so exit
-    /// keys in the adventure JSON ("north", "south" …) are matched reliably.
-    fn expand_direction(dir: &str) -> &str {
+    /// Expand direction abbreviations to full words so exit keys in the
+    /// adventure JSON ("north", "southwest" …) are matched reliably.
+    pub(crate) fn expand_direction(dir: &str) -> &str {
         match dir {
             "n" => "north",
             "s" => "south",
             "e" => "east",
             "w" => "west",
+            "ne" => "northeast",
+            "nw" => "northwest",
+            "se" => "southeast",
+            "sw" => "southwest",
             "u" => "up",
             "d" => "down",
             other => other,
         }
     }
+
 }
 
 impl System for BasicWorldSystem {
+    fn name(&self) -> &'static str {
+        "world"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
     fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
-        match command {
-            "help" | "?" => {
-                Some(Self::help_text())
+        self.on_command_reply(command, args, game).map(Reply::into_text)
+    }
+
+    fn on_command_reply(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<Reply> {
+        match command::resolve(COMMANDS, command)? {
+            "help" => {
+                Some(command::help_text(&game.grammar()).into())
             }
-            "look" | "l" => {
-                Some(game.look())
+            "look" => match args {
+                ["at", thing @ ..] if !thing.is_empty() => {
+                    Some(game.examine(&thing.join(" ")).unwrap_or_else(|| game.messages("nothing_special", &[])).into())
+                }
+                ["in" | "into", container @ ..] if !container.is_empty() => {
+                    Some(game.look_in(&container.join(" ")).into())
+                }
+                _ => Some(game.look_lines().into()),
+            },
+            "systems" | "enable" | "disable" if !game.settings.gameplay.debug_commands => None,
+            "systems" => {
+                let lines: Vec<String> = game.system_states().iter()
+                    .map(|(name, on)| format!("  {}: {}", name, if *on { "on" } else { "off" }))
+                    .collect();
+                Some(format!("Systems:\n{}", lines.join("\n")).into())
+            }
+            "enable" | "disable" => {
+                let enabled = command == "enable";
+                let Some(name) = args.first() else {
+                    return Some(Reply::Error(format!("{} which system?", if enabled { "Enable" } else { "Disable" })));
+                };
+                if !enabled && name.eq_ignore_ascii_case(self.name()) {
+                    return Some(Reply::Error("The world system can't be disabled.".to_string()));
+                }
+                let result = game.set_system_enabled(name, enabled).map(|mut changed| {
+                    let name = changed.pop().unwrap_or_default();
+                    let state = if enabled { "on" } else { "off" };
+                    if changed.is_empty() {
+                        format!("System '{}' is now {}.", name, state)
+                    } else {
+                        format!("System '{}' is now {}, along with {}.", name, state, changed.join(", "))
+                    }
+                });
+                Some(result.into())
             }
-            "go" | "move" => {
+            "go" => {
                 if let Some(dir) = args.first() {
                     let full = Self::expand_direction(dir);
-                    match game.move_player(full) {
-                        Some(desc) => Some(desc),
-                        None => Some(format!("You can't go {}.", full)),
+                    match game.move_player_lines(full) {
+                        Some(lines) => Some(lines.into()),
+                        None => Some(Reply::Error(game.barred_message(full)
+                            .unwrap_or_else(|| game.messages("cant_go_direction", &[("direction", full)])))),
                     }
                 } else {
-                    Some("Go where?".to_string())
+                    Some(Reply::Error(game.messages("go_where", &[])))
                 }
             }
-            dir if ["north", "south", "east", "west", "up", "down", "n", "s", "e", "w", "u", "d"].contains(&dir) => {
-                let full = Self::expand_direction(dir);
-                match game.move_player(full) {
-                    Some(desc) => Some(desc),
-                    None => Some("You can't go that way.".to_string()),
+            dir @ ("north" | "south" | "east" | "west" | "northeast" | "northwest" | "southeast" | "southwest"
+                | "up" | "down") => {
+                match game.move_player_lines(dir) {
+                    Some(lines) => Some(lines.into()),
+                    None => Some(Reply::Error(game.barred_message(dir).unwrap_or_else(|| game.messages("cant_go", &[])))),
                 }
             }
-            "say" | "shout" | "yell" => {
-                let text = args.join(" ");
-                if text.is_empty() {
-                    Some("Say what?".to_string())
+            "back" => {
+                let reverse = game.player.last_exit_used.as_deref().and_then(canonical_reverse);
+                Some(match reverse.and_then(|dir| game.move_player_lines(dir)) {
+                    Some(lines) => lines.into(),
+                    None => Reply::Error(game.messages("cant_retrace", &[])),
+                })
+            }
+            "search" => {
+                let (items, exits) = if args.is_empty() {
+                    game.search_room()
                 } else {
-                    // Collect all non-hostile NPCs in the room
-                    let monsters = game.get_monsters_in_room(game.player.current_room);
-                    let npc_names: Vec<String> = monsters.iter()
-                        .filter(|m| m.friendliness != MonsterStatus::Hostile)
-                        .map(|m| m.name.clone())
-                        .collect();
-                    let mut response = format!("You say: \"{}\"", text);
-                    if !npc_names.is_empty() {
-                        for npc in &npc_names {
-                            response.push_str(&format!("\n{} turns to face you.", npc));
-                        }
+                    match game.search_container(&args.join(" ")) {
+                        Ok(items) => (items, Vec::new()),
+                        Err(err) => return Some(Reply::Error(err)),
                     }
-                    Some(response)
+                };
+                let mut lines: Vec<String> = items.iter()
+                    .map(|item| game.messages("search_found_item", &[("item", item)]))
+                    .chain(exits.iter().map(|dir| game.messages("search_found_exit", &[("direction", dir)])))
+                    .collect();
+                if lines.is_empty() {
+                    lines.push(game.messages("search_nothing", &[]));
                 }
+                Some(lines.join("\n").into())
             }
+            "disarm" => match args.first() {
+                Some(dir) => Some(game.disarm_trap(Self::expand_direction(&dir.to_lowercase())).into()),
+                None => Some(Reply::Error(game.messages("disarm_where", &[]))),
+            },
             _ => None,
         }
     }
 }
 
-impl BasicWorldSystem {
-    fn help_text() -> String {
-        [
-            "Commands:",
-            "  look / l                    Look around",
-            "  inventory / i / inv         Show inventory",
-            "  n/s/e/w/u/d                 Move in a direction",
-            "  take <item>                 Pick up an item",
-            "  drop <item>                 Drop an item",
-            "  equip/wield/wear <item>     Equip a weapon or armor",
-            "  unequip/remove <slot>       Unequip weapon or armor",
-            "  use <item>                  Use/consume an item",
-            "  examine / x <item>          Examine an item",
-            "  attack / fight <monster>    Attack a monster",
-            "  flee / run                  Attempt to flee combat",
-            "  say / shout / yell <text>   Speak",
-            "  status / stats              Show player status & XP",
-            "  quests / journal            Show quest journal",
-            "  accept <quest_id>           Accept a quest",
-            "  complete <quest_id>         Complete a quest",
-            "  help / ?                    Show this help",
-        ].join("\n")
-    }
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Item, ItemType, Room};
+
+    #[test]
+    fn disabled_system_no_longer_handles_its_commands() {
+        let mut game = crate::Engine::new("").game;
+        game.rooms.insert(1, Room::new(1, "Yard".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.settings.gameplay.debug_commands = true;
+
+        assert_eq!(game.process_command("attack"), ["There's nothing hostile here."]);
+        assert_eq!(game.process_command("disable Combat"), ["System 'combat' is now off."]);
+        assert_eq!(game.process_command("attack"), ["Unknown command: attack"]);
+        assert!(!game.grammar().iter().any(|spec| spec.verb == "attack"));
+        assert!(game.process_command("systems").contains(&"  combat: off".to_string()));
+
+        assert_eq!(game.process_command("disable world"), ["The world system can't be disabled."]);
+        game.process_command("enable combat");
+        assert_eq!(game.process_command("attack"), ["There's nothing hostile here."]);
+    }
+
+    #[test]
+    fn debug_commands_are_off_unless_the_setting_allows_them() {
+        let mut game = crate::Engine::new("").game;
+        game.rooms.insert(1, Room::new(1, "Yard".to_string(), String::new()));
+        game.player.current_room = 1;
+
+        assert_eq!(game.process_command("disable combat"), ["Unknown command: disable combat"]);
+        assert!(game.system_enabled("combat"));
+        assert!(!game.process_command("help").join("\n").contains("disable"));
+
+        game.settings.gameplay.debug_commands = true;
+        assert!(game.process_command("help").join("\n").contains("disable <system>"));
+    }
+
+    #[test]
+    fn diagonal_and_vertical_shortcuts_move_the_player() {
+        for (short, full) in [("ne", "northeast"), ("nw", "northwest"), ("se", "southeast"), ("sw", "southwest"),
+                              ("u", "up"), ("d", "down")] {
+            assert_eq!(BasicWorldSystem::expand_direction(short), full);
+
+            let mut game = AdventureGame::new(String::new());
+            let mut hub = Room::new(1, "Hub".to_string(), String::new());
+            hub.exits.insert(full.to_string(), 2);
+            game.rooms.insert(1, hub);
+            game.rooms.insert(2, Room::new(2, "Spoke".to_string(), String::new()));
+            game.player.current_room = 1;
+            game.add_system(Box::new(BasicWorldSystem));
+
+            game.process_command(short);
+            assert_eq!(game.player.current_room, 2, "'{}' should go {}", short, full);
+            game.player.current_room = 1;
+            game.process_command(&format!("go {}", short));
+            assert_eq!(game.player.current_room, 2, "'go {}' should go {}", short, full);
+        }
+    }
+
+    #[test]
+    fn searching_reveals_hidden_items_and_exits() {
+        let mut game = AdventureGame::new(String::new());
+        let mut study = Room::new(1, "Study".to_string(), String::new());
+        study.hidden_exits.insert("down".to_string(), 2);
+        game.rooms.insert(1, study);
+        game.rooms.insert(2, Room::new(2, "Cellar".to_string(), String::new()));
+        let mut key = Item::new(1, "Brass key".to_string(), String::new(), ItemType::Normal, 1, 5);
+        key.location = 1;
+        key.hidden = true;
+        game.items.insert(1, key);
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        assert!(!game.look().contains("Brass key"));
+        assert_eq!(game.process_command("search"), ["You find: Brass key!", "You discover a hidden way down!"]);
+        assert!(game.look().contains("Brass key"));
+        assert!(game.rooms[&1].searched);
+        assert_eq!(game.process_command("search"), ["You search carefully but find nothing new."]);
+
+        game.process_command("down");
+        assert_eq!(game.player.current_room, 2);
+    }
+
+    #[test]
+    fn searching_a_container_reveals_what_is_hidden_inside() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Study".to_string(), String::new()));
+        let mut desk = Item::new(1, "Desk".to_string(), String::new(), ItemType::Container, 50, 0);
+        desk.location = 1;
+        desk.contents = vec![2];
+        game.items.insert(1, desk);
+        let mut letter = Item::new(2, "Letter".to_string(), String::new(), ItemType::Normal, 1, 0);
+        letter.location = 0;
+        letter.hidden = true;
+        game.items.insert(2, letter);
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+        game.add_system(Box::new(crate::systems::InventorySystem));
+
+        assert_eq!(game.process_command("search desk"), ["The Desk is closed."]);
+        game.process_command("open desk");
+        assert_eq!(game.process_command("look in desk"), ["The Desk is empty."]);
+        assert_eq!(game.process_command("search desk"), ["You find: Letter!"]);
+        assert_eq!(game.process_command("look in desk"), ["The Desk holds: Letter."]);
+        assert_eq!(game.process_command("search rug"), ["There's no 'rug' here that holds anything."]);
+    }
+
+    fn dark_cave(lantern_carried: bool) -> AdventureGame {
+        let mut game = AdventureGame::new(String::new());
+        let mut cave = Room::new(1, "Cave".to_string(), String::new());
+        cave.is_dark = true;
+        cave.exits.insert("out".to_string(), 2);
+        game.rooms.insert(1, cave);
+        game.rooms.insert(2, Room::new(2, "Hillside".to_string(), String::new()));
+        let mut bone = Item::new(1, "Bone".to_string(), String::new(), ItemType::Normal, 1, 0);
+        bone.location = 1;
+        game.items.insert(1, bone);
+        let mut lantern = Item::new(2, "Lantern".to_string(), String::new(), ItemType::Normal, 2, 10);
+        lantern.is_light_source = true;
+        if lantern_carried {
+            game.player.inventory.push(2);
+        } else {
+            lantern.location = 2;
+        }
+        game.items.insert(2, lantern);
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+        game
+    }
+
+    #[test]
+    fn dark_rooms_hide_everything_without_a_light() {
+        let game = dark_cave(false);
+        assert_eq!(game.look(), "It is pitch black. You can't see anything.");
+        assert!(game.visible_exits().is_empty());
+
+        let game = dark_cave(true);
+        assert!(game.look().contains("Bone"));
+        assert_eq!(game.visible_exits(), ["out"]);
+    }
+
+    #[test]
+    fn moving_in_the_dark_can_get_nowhere() {
+        let mut game = dark_cave(false);
+        game.seed_rng(3);
+        let mut stumbles = 0;
+        while game.player.current_room == 1 {
+            if game.process_command("go out") == ["You stumble about in the dark and get nowhere."] {
+                stumbles += 1;
+            }
+        }
+        assert!(stumbles > 0);
+        assert_eq!(game.turn_count, stumbles + 1);
+
+        let mut game = dark_cave(true);
+        game.process_command("go out");
+        assert_eq!(game.player.current_room, 2);
+    }
+
+    #[test]
+    fn back_retraces_the_last_move() {
+        let mut game = AdventureGame::new(String::new());
+        let mut gate = Room::new(1, "Gate".to_string(), String::new());
+        gate.exits.insert("north".to_string(), 2);
+        let mut yard = Room::new(2, "Yard".to_string(), String::new());
+        yard.exits.insert("south".to_string(), 1);
+        game.rooms.insert(1, gate);
+        game.rooms.insert(2, yard);
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+
+        assert_eq!(game.process_command("back"), ["You can't retrace your steps."]);
+        game.process_command("n");
+        assert_eq!(game.player.current_room, 2);
+        game.process_command("back");
+        assert_eq!(game.player.current_room, 1);
+        assert_eq!(game.player.last_exit_used.as_deref(), Some("south"));
+
+        game.process_command("go North");
+        assert_eq!(game.player.last_exit_used.as_deref(), Some("north"));
+        game.process_command("back");
+        assert_eq!(game.player.current_room, 1);
+    }
+
+    fn trapped_hall(tool_location: i32) -> AdventureGame {
+        let mut game = crate::Engine::new("").game;
+        game.load_adventure_str(&format!(r#"{{
+            "rooms": [
+                {{"id": 1, "name": "Antechamber", "exits": {{"north": 2}}}},
+                {{"id": 2, "name": "Tomb", "exits": {{"south": 1}}, "has_trap": true, "trap_damage": 4}}
+            ],
+            "items": [{{"id": 1, "name": "Probe", "disarms_traps": true, "location": {}}}]
+        }}"#, tool_location)).unwrap();
+        game.player.current_room = 1;
+        game
+    }
+
+    #[test]
+    fn trap_goes_off_only_on_the_first_entry() {
+        let mut game = trapped_hall(99);
+        let output = game.process_command("north");
+        assert_eq!(output.last().unwrap(), "A trap goes off! You take 4 damage. (8/12 health)");
+        assert_eq!(game.player.current_health, 8);
+
+        game.process_command("south");
+        let output = game.process_command("north");
+        assert!(!output.iter().any(|line| line.contains("trap")));
+        assert_eq!(game.player.current_health, 8);
+    }
+
+    #[test]
+    fn trap_tool_disarms_the_trap_ahead() {
+        let mut game = trapped_hall(0);
+        game.player.inventory.push(1);
+        assert_eq!(game.process_command("disarm n"), ["You spot a trap and disarm it with the Probe."]);
+        assert_eq!(game.process_command("disarm north"), ["You find no trap north."]);
+        game.process_command("north");
+        assert_eq!(game.player.current_health, 12);
+    }
+
+    #[test]
+    fn overriding_cant_go_changes_movement_failure_text() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Cell".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+        assert_eq!(game.process_command("north"), ["You can't go that way."]);
+
+        game.message_table.set("cant_go", "Der Weg ist versperrt.");
+        assert_eq!(game.process_command("north"), ["Der Weg ist versperrt."]);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::game_state::{AdventureGame, TimeOfDay};
+use crate::systems::System;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("time", &[], ArgShape::None, "Show the time of day"),
+];
+
+/// The day/night cycle: answers `time` and announces dusk and dawn.
+#[derive(Debug, Default)]
+pub struct ClockSystem {
+    last_seen: Option<TimeOfDay>,
+}
+
+impl System for ClockSystem {
+    fn name(&self) -> &'static str {
+        "clock"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
+    fn on_command(&mut self, command: &str, _args: &[&str], game: &mut AdventureGame) -> Option<String> {
+        match command::resolve(COMMANDS, command)? {
+            "time" => {
+                let period = match game.time_of_day() {
+                    TimeOfDay::Day => "day",
+                    TimeOfDay::Night => "night",
+                };
+                Some(game.messages("clock_time", &[("hour", &format!("{:02}", game.hour())), ("period", period)]))
+            }
+            _ => None,
+        }
+    }
+
+    fn on_turn(&mut self, game: &mut AdventureGame) -> Option<String> {
+        let now = game.time_of_day();
+        let previous = self.last_seen.replace(now);
+        match (previous, now) {
+            (Some(TimeOfDay::Day), TimeOfDay::Night) => Some(game.messages("night_falls", &[])),
+            (Some(TimeOfDay::Night), TimeOfDay::Day) => Some(game.messages("sun_rises", &[])),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::Room;
+    use crate::systems::BasicWorldSystem;
+
+    #[test]
+    fn night_only_exit_opens_after_dark() {
+        let mut game = AdventureGame::new(String::new());
+        let mut square = Room::new(1, "Town Square".to_string(), String::new());
+        square.exits.insert("north".to_string(), 2);
+        square.exit_times.insert("north".to_string(), vec![TimeOfDay::Night]);
+        game.rooms.insert(1, square);
+        game.rooms.insert(2, Room::new(2, "Night Market".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(BasicWorldSystem));
+        game.add_system(Box::new(ClockSystem::default()));
+
+        assert_eq!(game.process_command("time"), ["It is 08:00 (day)."]);
+        assert!(!game.look().contains("north"));
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 1);
+
+        // Default day is 48 turns long, so 22:00 is 28 turns after the 08:00 start.
+        game.turn_count = 28;
+        assert_eq!(game.time_of_day(), TimeOfDay::Night);
+        game.process_command("north");
+        assert_eq!(game.player.current_room, 2);
+    }
+
+    fn corridor(settings: &str) -> AdventureGame {
+        let mut game = crate::engine::Engine::new("").game;
+        game.load_adventure_str(&format!(r#"{{
+            "settings": {},
+            "rooms": [
+                {{"id": 1, "name": "West End", "exits": {{"east": 2}}}},
+                {{"id": 2, "name": "East End", "exits": {{"west": 1}}}}
+            ]
+        }}"#, settings)).unwrap();
+        game
+    }
+
+    #[test]
+    fn turn_limit_ends_the_game_on_the_move_past_it() {
+        let mut game = corridor(r#"{"gameplay": {"turn_limit": 3}}"#);
+        for direction in ["east", "west", "east"] {
+            game.process_command(direction);
+        }
+        assert!(!game.game_over);
+        let output = game.process_command("west");
+        assert!(game.game_over);
+        assert_eq!(output.last().unwrap(), "You have run out of time. The game is over.");
+    }
+
+    #[test]
+    fn look_mentions_the_time_only_when_enabled() {
+        assert!(!corridor("{}").look().contains("It is morning."));
+        let mut game = corridor(r#"{"clock": {"show_time": true}}"#);
+        assert!(game.look().contains("It is morning."));
+        game.turn_count = 12;
+        assert!(game.look().contains("It is afternoon."));
+    }
+}
@@ -1,36 +1,189 @@
 use rand::Rng;
-use crate::game_state::{name_matches, AdventureGame, GameEvent, MonsterStatus};
-use crate::systems::System;
+use std::cmp::Reverse;
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::event_log::LoggedEvent;
+use crate::game_state::{name_matches, AdventureGame, GameEvent, MonsterStatus, HOSTILE_STANDING};
+use crate::output::{tagged, LineKind, Reply};
+use crate::systems::{BasicWorldSystem, System};
 
 #[derive(Debug, Default)]
 pub struct CombatSystem;
 
+/// Someone taking a turn in a combat round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Combatant {
+    Player,
+    Monster(i32),
+}
+
 /// XP needed to level up: level * 100 (level 1→2 needs 100 XP, level 2→3 needs 200, etc.)
-const XP_PER_LEVEL: i32 = 100;
+pub(crate) const XP_PER_LEVEL: i32 = 100;
+
+/// Turns a "poison" monster's hit keeps the player poisoned, and damage per turn.
+const POISON_TURNS: i32 = 3;
+const POISON_DAMAGE: i32 = 1;
+
+/// Standing lost with a faction for killing one of its members.
+const KILL_STANDING_PENALTY: i32 = 10;
+
+/// A monster with the "flee" ability runs once its health drops below this percentage.
+const FLEE_HEALTH_PERCENT: i32 = 25;
+
+/// Weapon ability a new character starts with; each [`ABILITY_PER_BONUS`]
+/// points above it add 1 damage to blows with that weapon type.
+const BASE_WEAPON_ABILITY: i32 = 5;
+const ABILITY_PER_BONUS: i32 = 5;
+/// Practice stops raising weapon ability here (+4 damage).
+const MAX_WEAPON_ABILITY: i32 = 25;
+
+/// Each point of armor expertise makes worn armor absorb 10% more.
+const MAX_ARMOR_EXPERTISE: i32 = 20;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("attack", &["fight", "kill"], ArgShape::OptionalTarget("monster"), "Attack a monster"),
+    CommandSpec::new("flee", &["run", "escape"], ArgShape::OptionalDirection, "Attempt to flee combat"),
+    CommandSpec::new("status", &["stats"], ArgShape::None, "Show player status & XP"),
+    CommandSpec::new("reputation", &["standing"], ArgShape::None, "Show standing with each faction"),
+];
 
 impl System for CombatSystem {
+    fn name(&self) -> &'static str {
+        "combat"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
     fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
-        match command {
-            "attack" | "fight" | "kill" => {
+        self.on_command_reply(command, args, game).map(Reply::into_text)
+    }
+
+    fn on_command_reply(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<Reply> {
+        match command::resolve(COMMANDS, command)? {
+            "attack" => {
                 if let Some(target) = args.first() {
-                    self.attack_monster(game, target)
+                    Some(self.attack_monster(game, target))
                 } else {
-                    Some("Attack what?".to_string())
+                    Some(self.attack_default_target(game))
                 }
             }
-            "status" | "stats" | "score" => {
-                Some(self.show_status(game))
+            "status" => {
+                Some(game.character_sheet().join("\n").into())
             }
-            "flee" | "run" | "escape" => {
-                Some(self.flee(game))
+            "flee" => {
+                Some(self.flee(game, args.first().copied()))
+            }
+            "reputation" => {
+                if game.player.reputation.is_empty() {
+                    Some("You have no standing with any faction yet.".to_string().into())
+                } else {
+                    let lines: Vec<String> = game.player.reputation.iter()
+                        .map(|(faction, standing)| format!("  {}: {:+}", faction, standing))
+                        .collect();
+                    Some(format!("Reputation:\n{}", lines.join("\n")).into())
+                }
             }
             _ => None,
         }
     }
+
+    fn output_kind(&self) -> LineKind {
+        LineKind::Combat
+    }
+
+    fn on_turn(&mut self, game: &mut AdventureGame) -> Option<String> {
+        let mut lines: Vec<String> = Vec::new();
+
+        // Poison ticks down on the player
+        if let Some(turns) = game.player.status_effects.get_mut("poison") {
+            *turns -= 1;
+            if *turns <= 0 {
+                game.player.status_effects.remove("poison");
+            }
+            if !game.game_over {
+                game.player.current_health -= POISON_DAMAGE;
+                if game.player.current_health <= 0 {
+                    let death = game.handle_player_death();
+                    lines.push(game.messages("poison_fatal", &[("death", &death)]));
+                } else {
+                    lines.push(game.messages("poison_tick", &[
+                        ("damage", &POISON_DAMAGE.to_string()),
+                        ("health", &game.player.current_health.to_string()),
+                        ("max", &game.player.hardiness.to_string()),
+                    ]));
+                }
+            }
+        }
+
+        // Regenerating monsters heal a point per turn
+        let current_room = game.player.current_room;
+        let mut healed_here = Vec::new();
+        for monster in game.monsters.values_mut() {
+            if !monster.is_dead
+                && monster.has_ability("regenerate")
+                && monster.current_health < monster.hardiness
+            {
+                monster.current_health += 1;
+                if monster.room_id == current_room {
+                    healed_here.push(monster.name.clone());
+                }
+            }
+        }
+        lines.extend(healed_here.iter().map(|name| game.messages("monster_regenerates", &[("monster", name)])));
+
+        if lines.is_empty() { None } else { Some(lines.join("\n")) }
+    }
+
+    fn on_events(&mut self, events: &[GameEvent], game: &mut AdventureGame) -> Option<String> {
+        // Members of factions the player has badly offended turn hostile on
+        // sight, then everyone present gets a chance to bark
+        let mut lines = Vec::new();
+        for event in events {
+            let GameEvent::RoomEntered { room_id } = event else { continue };
+            let mut enemies: Vec<i32> = game.get_monsters_in_room(*room_id)
+                .iter()
+                .filter(|m| m.friendliness != MonsterStatus::Hostile
+                    && m.faction.as_deref().is_some_and(|f| game.player.standing(f) <= HOSTILE_STANDING))
+                .map(|m| m.id)
+                .collect();
+            enemies.sort();
+            for id in enemies {
+                if let Some(monster) = game.monsters.get_mut(&id) {
+                    monster.friendliness = MonsterStatus::Hostile;
+                    let name = monster.name.clone();
+                    lines.push(game.messages("turns_hostile", &[("monster", &name)]));
+                }
+            }
+            let mut present: Vec<i32> = game.get_monsters_in_room(*room_id).iter().map(|m| m.id).collect();
+            present.sort();
+            lines.extend(present.into_iter().filter_map(|id| game.bark(id)));
+        }
+        if lines.is_empty() { None } else { Some(lines.join("\n")) }
+    }
 }
 
 impl CombatSystem {
-    fn attack_monster(&self, game: &mut AdventureGame, target_name: &str) -> Option<String> {
+    /// `attack` with no target: fight the only hostile in the room, or ask
+    /// which one when there are several.
+    fn attack_default_target(&self, game: &mut AdventureGame) -> Reply {
+        let mut hostiles: Vec<(String, i32)> = game.get_monsters_in_room(game.player.current_room)
+            .iter()
+            .filter(|m| m.friendliness == MonsterStatus::Hostile)
+            .map(|m| (m.name.clone(), m.id))
+            .collect();
+        hostiles.sort();
+        match hostiles.as_slice() {
+            [] => Reply::Error(game.messages("nothing_hostile", &[])),
+            &[(_, only)] => Reply::Text(self.attack(game, only)),
+            several => {
+                let names: Vec<&str> = several.iter().map(|(name, _)| name.as_str()).collect();
+                Reply::Text(game.messages("attack_which", &[("monsters", &names.join(", "))]))
+            }
+        }
+    }
+
+    fn attack_monster(&self, game: &mut AdventureGame, target_name: &str) -> Reply {
         // Collect matching monster id first to avoid borrow conflicts
         let monster_id = game
             .get_monsters_in_room(game.player.current_room)
@@ -39,88 +192,147 @@ impl CombatSystem {
             .map(|m| m.id);
 
         let Some(monster_id) = monster_id else {
-            return Some(format!("There's no {} here to attack.", target_name));
+            return Reply::Error(game.messages("no_one_to_attack", &[("name", target_name)]));
         };
-
         // Don't allow attacking non-hostile NPCs
         if let Some(m) = game.monsters.get(&monster_id)
             && m.friendliness != MonsterStatus::Hostile
         {
-            return Some(format!(
-                "You can't bring yourself to attack the friendly {}.",
-                m.name
-            ));
+            return Reply::Error(game.messages("wont_attack_friendly", &[("monster", &m.name)]));
+        }
+        Reply::Text(self.attack(game, monster_id))
+    }
+
+    /// One round of combat against `monster_id`, a hostile in the player's room.
+    fn attack(&self, game: &mut AdventureGame, monster_id: i32) -> String {
+        // An unhurt monster is only now being drawn into the fight
+        let mut lines = Vec::new();
+        if game.monsters.get(&monster_id).is_some_and(|m| m.current_health >= m.hardiness)
+            && let Some(bark) = game.bark(monster_id)
+        {
+            lines.push(bark);
+        }
+        Self::rally_faction(game, monster_id, &mut lines);
+
+        // One full round: everyone acts in initiative order until the player
+        // dies or is carried off by a respawn
+        let room_id = game.player.current_room;
+        for combatant in Self::initiative(game, room_id) {
+            if game.game_over || game.player.current_room != room_id {
+                break;
+            }
+            match combatant {
+                Combatant::Player => lines.push(Self::player_strike(game, monster_id)),
+                Combatant::Monster(id) => {
+                    // Monsters killed or driven off earlier in the round don't act
+                    if !game.monsters.get(&id).is_some_and(|m| !m.is_dead && m.room_id == room_id) {
+                        continue;
+                    }
+                    // Badly wounded monsters with the "flee" ability run instead of striking
+                    match Self::monster_flee(game, id) {
+                        Some(flight) => lines.push(flight),
+                        None => lines.push(self.monster_counter_attack(game, id)),
+                    }
+                }
+            }
+        }
+        game.turn_count += 1;
+
+        lines.join("\n")
+    }
+
+    /// Everyone fighting in `room_id`, in the order they act in a round: the
+    /// player and each living hostile monster there, highest agility first.
+    /// The player wins ties; tied monsters go in id order.
+    pub(crate) fn initiative(game: &AdventureGame, room_id: i32) -> Vec<Combatant> {
+        let mut order: Vec<(Reverse<i32>, bool, i32, Combatant)> = game.get_monsters_in_room(room_id)
+            .iter()
+            .filter(|m| m.friendliness == MonsterStatus::Hostile)
+            .map(|m| (Reverse(m.agility), true, m.id, Combatant::Monster(m.id)))
+            .collect();
+        order.push((Reverse(game.player.agility), false, 0, Combatant::Player));
+        order.sort_by_key(|&(agility, is_monster, id, _)| (agility, is_monster, id));
+        order.into_iter().map(|(.., combatant)| combatant).collect()
+    }
+
+    /// The player's blow against `monster_id`, including the rewards if it dies.
+    fn player_strike(game: &mut AdventureGame, monster_id: i32) -> String {
+        let Some(monster) = game.monsters.get(&monster_id) else {
+            return String::new();
+        };
+        if monster.is_dead || monster.room_id != game.player.current_room {
+            return game.messages("out_of_reach", &[("monster", &monster.name)]);
         }
 
         // Determine player damage using equipped weapon, or unarmed fallback
         let player_damage = if let Some(weapon_id) = game.player.equipped_weapon {
             if let Some(weapon) = game.items.get(&weapon_id) {
-                weapon.get_damage()
+                let weapon_type = weapon.weapon_type;
+                let damage = weapon.roll_damage(&mut game.rng) + Self::weapon_bonus(game, weapon_type);
+                Self::practice_weapon(game, weapon_type);
+                damage
             } else {
-                rand::thread_rng().gen_range(1..=4)
+                game.rng.gen_range(1..=4)
             }
         } else {
             let best = game.player.weapon_ability.values().copied().max().unwrap_or(4);
-            rand::thread_rng().gen_range(1..=best.max(4))
+            game.rng.gen_range(1..=best.max(4))
         };
 
-        let mut output = String::new();
+        // Every blow that lands wears the weapon down
+        let weapon_broke = game.player.equipped_weapon.and_then(|id| game.wear_equipment(id));
 
         // Apply player's attack to monster; monster armor reduces damage
-        if let Some(monster) = game.monsters.get_mut(&monster_id) {
-            let armor_reduction = monster.armor_worn;
-            let net_damage = (player_damage - armor_reduction).max(1);
-            monster.current_health -= net_damage;
-
-            if armor_reduction > 0 {
-                output.push_str(&format!(
-                    "You attack the {} for {} damage ({} absorbed by armor).",
-                    monster.name, net_damage, armor_reduction
-                ));
-            } else {
-                output.push_str(&format!(
-                    "You attack the {} for {} damage.",
-                    monster.name, net_damage
-                ));
+        let monster = game.monsters.get_mut(&monster_id).expect("checked above");
+        let armor_reduction = monster.armor_worn;
+        let net_damage = (player_damage - armor_reduction).max(1);
+        monster.current_health -= net_damage;
+        game.event_log.push(game.turn_count, LoggedEvent::Attacked { monster_id, damage: net_damage });
+
+        let (name, health) = (monster.name.clone(), monster.current_health.to_string());
+        let mut msg = if monster.current_health <= 0 {
+            monster.is_dead = true;
+            let room_id = monster.room_id;
+            let gold = monster.gold;
+            let xp_gained = monster.hardiness * 5;
+            if let Some(faction) = monster.faction.clone() {
+                game.player.adjust_standing(&faction, -KILL_STANDING_PENALTY);
             }
+            game.log_event(LoggedEvent::Killed { monster_id });
+            if gold > 0 {
+                game.log_event(LoggedEvent::Looted { monster_id, gold });
+            }
+            game.player.gold += gold;
+            game.player.experience_points += xp_gained;
 
-            if monster.current_health <= 0 {
-                monster.is_dead = true;
-                let name = monster.name.clone();
-                let room_id = monster.room_id;
-                let gold = monster.gold;
-                let xp_gained = monster.hardiness * 5;
-                game.player.gold += gold;
-                game.player.experience_points += xp_gained;
-                game.turn_count += 1;
-
-                let mut msg = format!("You defeat the {}!", name);
-                if gold > 0 {
-                    msg.push_str(&format!(" (+{} gold)", gold));
-                }
-                msg.push_str(&format!(" (+{} XP)", xp_gained));
-                // Check for level-up
-                let level_up_msg = Self::check_level_up(game);
-                if let Some(lu) = level_up_msg {
-                    msg.push('\n');
-                    msg.push_str(&lu);
-                }
-                game.events.push(GameEvent::MonsterKilled { monster_name: name, room_id });
-                return Some(msg);
+            let xp = xp_gained.to_string();
+            let mut msg = if gold > 0 {
+                game.messages("defeated_with_gold", &[("monster", &name), ("gold", &gold.to_string()), ("xp", &xp)])
             } else {
-                output.push_str(&format!(" It has {} health remaining.", monster.current_health));
+                game.messages("defeated", &[("monster", &name), ("xp", &xp)])
+            };
+            // Check for level-up
+            if let Some(lu) = Self::check_level_up(game) {
+                msg.push('\n');
+                msg.push_str(&lu);
             }
+            game.events.push(GameEvent::MonsterKilled { monster_name: name, monster_id, room_id });
+            msg
+        } else if armor_reduction > 0 {
+            game.messages("hit_through_armor", &[
+                ("monster", &name),
+                ("damage", &net_damage.to_string()),
+                ("absorbed", &armor_reduction.to_string()),
+                ("health", &health),
+            ])
         } else {
-            return Some(format!("There's no {} here to attack.", target_name));
+            game.messages("hit", &[("monster", &name), ("damage", &net_damage.to_string()), ("health", &health)])
+        };
+        if let Some(broke) = weapon_broke {
+            msg.push('\n');
+            msg.push_str(&broke);
         }
-
-        // Monster counter-attack (if still alive)
-        let counter = self.monster_counter_attack(game, monster_id);
-        output.push('\n');
-        output.push_str(&counter);
-        game.turn_count += 1;
-
-        Some(output)
+        msg
     }
 
     fn monster_counter_attack(&self, game: &mut AdventureGame, monster_id: i32) -> String {
@@ -129,89 +341,203 @@ impl CombatSystem {
             let dmg = if let Some(weapon_id) = m.weapon_id {
                 // Use the weapon's damage if the item exists, otherwise fall back
                 if let Some(weapon) = game.items.get(&weapon_id) {
-                    weapon.get_damage()
+                    weapon.roll_damage(&mut game.rng)
                 } else {
                     let max_dmg = (m.agility / 3 + 1).max(2);
-                    rand::thread_rng().gen_range(1..=max_dmg)
+                    game.rng.gen_range(1..=max_dmg)
                 }
             } else {
                 let max_dmg = (m.agility / 3 + 1).max(2);
-                rand::thread_rng().gen_range(1..=max_dmg)
+                game.rng.gen_range(1..=max_dmg)
             };
             (dmg, m.name.clone())
         } else {
             return String::new();
         };
 
-        // Reduce by player armor
-        let armor_reduction = if let Some(armor_id) = game.player.equipped_armor {
-            game.items.get(&armor_id).map_or(0, |a| a.armor_value)
-        } else {
-            0
-        };
-        let net_damage = (monster_dmg - armor_reduction).max(1);
+        let net_damage = (monster_dmg - Self::armor_reduction(game)).max(1);
+        if game.player.equipped_armor.is_some() {
+            game.player.armor_expertise = (game.player.armor_expertise + 1).min(MAX_ARMOR_EXPERTISE);
+        }
 
         game.player.current_health -= net_damage;
         let current_hp = game.player.current_health;
 
+        let damage = net_damage.to_string();
         if current_hp <= 0 {
-            game.game_over = true;
-            format!(
-                "The {} strikes back for {} damage. You have been slain!",
-                monster_name, net_damage
-            )
+            let death = game.handle_player_death();
+            game.messages("struck_down", &[("monster", &monster_name), ("damage", &damage), ("death", &death)])
         } else {
-            format!(
-                "The {} strikes back for {} damage. Your health: {}/{}.",
-                monster_name, net_damage, current_hp, game.player.hardiness
-            )
+            let mut msg = game.messages("struck", &[
+                ("monster", &monster_name),
+                ("damage", &damage),
+                ("health", &current_hp.to_string()),
+                ("max", &game.player.hardiness.to_string()),
+            ]);
+            if game.monsters.get(&monster_id).is_some_and(|m| m.has_ability("poison")) {
+                game.player.status_effects.insert("poison".to_string(), POISON_TURNS);
+                msg.push(' ');
+                msg.push_str(&game.messages("poisoned", &[]));
+            }
+            if let Some(broke) = game.player.equipped_armor.and_then(|id| game.wear_equipment(id)) {
+                msg.push(' ');
+                msg.push_str(&broke);
+            }
+            msg
         }
     }
 
-    fn flee(&self, game: &mut AdventureGame) -> String {
-        let has_hostiles = game
-            .get_monsters_in_room(game.player.current_room)
-            .into_iter()
-            .any(|m| m.friendliness == MonsterStatus::Hostile);
+    /// Extra damage from practice with `weapon_type`.
+    fn weapon_bonus(game: &AdventureGame, weapon_type: i32) -> i32 {
+        let ability = game.player.weapon_ability.get(&weapon_type).copied().unwrap_or(BASE_WEAPON_ABILITY);
+        (ability - BASE_WEAPON_ABILITY).max(0) / ABILITY_PER_BONUS
+    }
 
-        if !has_hostiles {
-            return "You aren't in combat — there's nothing to flee from.".to_string();
+    /// A blow with `weapon_type` raises its ability by one, up to the cap.
+    fn practice_weapon(game: &mut AdventureGame, weapon_type: i32) {
+        let ability = game.player.weapon_ability.entry(weapon_type).or_insert(BASE_WEAPON_ABILITY);
+        if *ability < MAX_WEAPON_ABILITY {
+            *ability += 1;
         }
+    }
 
-        // Flee success chance based on player agility (10% – 90%)
-        let flee_chance = (game.player.agility as f32 / 20.0).clamp(0.10, 0.90);
-        if rand::random::<f32>() < flee_chance {
-            // Choose the first available exit
-            let exit = game.get_current_room()
-                .and_then(|r| r.exits.iter().next().map(|(dir, &dest)| (dir.clone(), dest)));
-            if let Some((dir, dest_id)) = exit
-                && game.rooms.contains_key(&dest_id)
+    /// Damage the player's worn armor absorbs: its armor value, plus 10% per
+    /// point of armor expertise.
+    pub(crate) fn armor_reduction(game: &AdventureGame) -> i32 {
+        let armor = game.player.equipped_armor
+            .and_then(|id| game.items.get(&id))
+            .map_or(0, |a| a.armor_value);
+        armor * (10 + game.player.armor_expertise) / 10
+    }
+
+    /// Other living monsters in the room that share the attacked monster's
+    /// faction come to its aid: any that weren't hostile turn hostile.
+    fn rally_faction(game: &mut AdventureGame, monster_id: i32, lines: &mut Vec<String>) {
+        let Some(faction) = game.monsters.get(&monster_id).and_then(|m| m.faction.clone()) else {
+            return;
+        };
+        let mut allies: Vec<i32> = game.get_monsters_in_room(game.player.current_room)
+            .iter()
+            .filter(|m| m.id != monster_id && m.faction.as_ref() == Some(&faction))
+            .map(|m| m.id)
+            .collect();
+        allies.sort();
+        for id in &allies {
+            if let Some(ally) = game.monsters.get_mut(id)
+                && ally.friendliness != MonsterStatus::Hostile
             {
-                game.player.current_room = dest_id;
-                game.turn_count += 1;
-                game.events.push(GameEvent::RoomEntered { room_id: dest_id });
-                return format!("You flee {}!\n{}", dir, game.look());
+                ally.friendliness = MonsterStatus::Hostile;
+                let name = ally.name.clone();
+                lines.push(game.messages("ally_joins", &[("monster", &name)]));
+            }
+        }
+    }
+
+    /// Move a badly wounded "flee" monster through a random exit of its room.
+    fn monster_flee(game: &mut AdventureGame, monster_id: i32) -> Option<String> {
+        let monster = game.monsters.get(&monster_id)?;
+        if monster.is_dead
+            || !monster.has_ability("flee")
+            || monster.current_health * 100 >= monster.hardiness * FLEE_HEALTH_PERCENT
+        {
+            return None;
+        }
+        let exits: Vec<(String, i32)> = game.rooms.get(&monster.room_id)?
+            .exits.iter()
+            .filter(|(_, dest)| game.rooms.contains_key(dest))
+            .map(|(dir, &dest)| (dir.clone(), dest))
+            .collect();
+        if exits.is_empty() {
+            return None;
+        }
+        let (dir, dest) = exits[game.rng.gen_range(0..exits.len())].clone();
+        let monster = game.monsters.get_mut(&monster_id)?;
+        monster.room_id = dest;
+        let name = monster.name.clone();
+        Some(game.messages("monster_flees", &[("monster", &name), ("direction", &dir)]))
+    }
+
+    /// Try to escape through `direction` (or a random exit) while hostiles are present.
+    /// Success chance is based on the player's agility against the quickest hostile.
+    fn flee(&self, game: &mut AdventureGame, direction: Option<&str>) -> Reply {
+        let hostile_agility = game
+            .get_monsters_in_room(game.player.current_room)
+            .into_iter()
+            .filter(|m| m.friendliness == MonsterStatus::Hostile)
+            .map(|m| m.agility)
+            .max();
+
+        let Some(hostile_agility) = hostile_agility else {
+            // Nothing to flee from: a direction is just an ordinary move.
+            return match direction {
+                Some(dir) => {
+                    let full = BasicWorldSystem::expand_direction(dir);
+                    match game.move_player_lines(full) {
+                        Some(lines) => Reply::Lines(lines),
+                        None => Reply::Error(game.messages("cant_go_direction", &[("direction", full)])),
+                    }
+                }
+                None => Reply::Error(game.messages("nothing_to_flee", &[])),
+            };
+        };
+
+        // Pick the escape route before rolling so a bad direction costs nothing
+        let exit = match direction {
+            Some(dir) => {
+                let full = BasicWorldSystem::expand_direction(dir);
+                match game.get_current_room().and_then(|r| r.get_exit(full)) {
+                    Some(dest) => Some((full.to_string(), dest)),
+                    None => return Reply::Error(game.messages("cant_flee_direction", &[("direction", full)])),
+                }
             }
-            "You try to flee but have nowhere to go!".to_string()
+            None => {
+                let mut exits: Vec<(String, i32)> = game.get_current_room()
+                    .map(|r| r.exits.iter().map(|(d, &dest)| (d.clone(), dest)).collect())
+                    .unwrap_or_default();
+                exits.sort();
+                if exits.is_empty() {
+                    None
+                } else {
+                    Some(exits.swap_remove(game.rng.gen_range(0..exits.len())))
+                }
+            }
+        };
+        let Some((dir, dest_id)) = exit.filter(|(_, dest)| game.rooms.contains_key(dest)) else {
+            return Reply::Text(game.messages("nowhere_to_flee", &[]));
+        };
+
+        // 50% at equal agility, ±5% per point of difference, clamped to 10% – 90%
+        let flee_chance = (0.5 + (game.player.agility - hostile_agility) as f64 * 0.05).clamp(0.10, 0.90);
+        if game.rng.gen_bool(flee_chance) {
+            game.turn_count += 1;
+            game.enter_room(dest_id);
+            game.player.last_exit_used = Some(dir.clone());
+            let mut lines = tagged(&game.messages("fled", &[("direction", &dir)]), LineKind::Combat);
+            lines.extend(game.look_lines());
+            if let Some(trap) = game.spring_trap() {
+                lines.extend(tagged(&trap, LineKind::System));
+            }
+            Reply::Lines(lines)
         } else {
-            // Failed flee: first hostile monster gets a free attack
+            // Failed flee: the quickest hostile monster gets a free attack
             let monster_id = game
                 .get_monsters_in_room(game.player.current_room)
                 .into_iter()
-                .find(|m| m.friendliness == MonsterStatus::Hostile)
+                .filter(|m| m.friendliness == MonsterStatus::Hostile)
+                .max_by_key(|m| (m.agility, -m.id))
                 .map(|m| m.id);
+            game.turn_count += 1;
+            let mut text = game.messages("flee_failed", &[]);
             if let Some(mid) = monster_id {
-                let counter = self.monster_counter_attack(game, mid);
-                game.turn_count += 1;
-                format!("You fail to flee!\n{}", counter)
-            } else {
-                "You fail to flee!".to_string()
+                text.push('\n');
+                text.push_str(&self.monster_counter_attack(game, mid));
             }
+            Reply::Text(text)
         }
     }
 
     /// Check whether the player should level up and apply it.
-    fn check_level_up(game: &mut AdventureGame) -> Option<String> {
+    pub(crate) fn check_level_up(game: &mut AdventureGame) -> Option<String> {
         let threshold = game.player.level * XP_PER_LEVEL;
         if game.player.experience_points >= threshold {
             game.player.level += 1;
@@ -220,39 +546,314 @@ impl CombatSystem {
             game.player.agility += 1;
             // Restore health to new max
             game.player.current_health = game.player.hardiness;
-            Some(format!(
-                "*** Level Up! You are now level {}. Hardiness +2, Agility +1. Health restored to {}. ***",
-                game.player.level, game.player.hardiness
-            ))
+            Some(game.messages("level_up", &[
+                ("level", &game.player.level.to_string()),
+                ("health", &game.player.hardiness.to_string()),
+            ]))
         } else {
             None
         }
     }
+}
 
-    fn show_status(&self, game: &AdventureGame) -> String {
-        let weapon_name = game.player.equipped_weapon
-            .and_then(|id| game.items.get(&id))
-            .map(|w| w.name.as_str())
-            .unwrap_or("none");
-        let armor_name = game.player.equipped_armor
-            .and_then(|id| game.items.get(&id))
-            .map(|a| a.name.as_str())
-            .unwrap_or("none");
-        let (carry_cur, carry_max) = game.carry_weight();
-        let next_level_xp = game.player.level * 100;
-        format!(
-            "Player: {}\nHealth: {}/{}\nLevel: {}  XP: {}/{}\nGold: {}\nWeapon: {}\nArmor: {}\nCarrying: {}/{} weight\nLocation: Room {}",
-            game.player.name,
-            game.player.current_health,
-            game.player.hardiness,
-            game.player.level,
-            game.player.experience_points,
-            next_level_xp,
-            game.player.gold,
-            weapon_name,
-            armor_name,
-            carry_cur, carry_max,
-            game.player.current_room,
-        )
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Item, ItemType, Monster, Room};
+
+    fn arena() -> AdventureGame {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Arena".to_string(), "Sand and blood.".to_string()));
+        game.player.current_room = 1;
+        game.player.hardiness = 100;
+        game.player.current_health = 100;
+        game.add_system(Box::new(CombatSystem));
+        game
+    }
+
+    #[test]
+    fn poison_monster_poisons_player_on_retaliation() {
+        let mut game = arena();
+        let mut spider = Monster::new(1, "Spider".to_string(), String::new(), 1, 100, 10, MonsterStatus::Hostile, 100);
+        spider.abilities = vec!["poison".to_string()];
+        game.monsters.insert(1, spider);
+
+        let out = game.process_command("attack spider").join("\n");
+        assert!(out.contains("You have been poisoned!"));
+        assert!(game.player.status_effects.contains_key("poison"));
+    }
+
+    #[test]
+    fn monsters_bark_when_the_player_walks_in() {
+        let mut game = arena();
+        game.rooms.get_mut(&1).unwrap().exits.insert("north".to_string(), 2);
+        game.rooms.insert(2, Room::new(2, "Den".to_string(), String::new()));
+        let mut goblin = Monster::new(1, "Goblin".to_string(), String::new(), 2, 10, 1, MonsterStatus::Neutral, 100);
+        goblin.barks = vec!["Who goes there?".to_string(), "Fresh meat!".to_string(), "Leave now!".to_string()];
+        game.monsters.insert(1, goblin);
+        game.monsters.insert(2, Monster::new(2, "Rat".to_string(), String::new(), 2, 10, 1, MonsterStatus::Neutral, 100));
+        game.add_system(Box::new(BasicWorldSystem));
+        game.seed_rng(7);
+
+        let out = game.process_command("north").join("\n");
+        assert!(out.contains("The Goblin calls out: \"Who goes there?\""), "{}", out);
+        assert!(!out.contains("The Rat calls out"));
+    }
+
+    #[test]
+    fn worn_out_weapon_breaks_and_is_unequipped() {
+        let mut game = arena();
+        game.add_system(Box::new(crate::systems::InventorySystem));
+        game.monsters.insert(1, Monster::new(1, "Troll".to_string(), String::new(), 1, 1000, 1, MonsterStatus::Hostile, 100));
+        let mut sword = Item::new(1, "Sword".to_string(), String::new(), ItemType::Weapon, 3, 10);
+        sword.is_weapon = true;
+        sword.durability = Some(2);
+        sword.max_durability = Some(2);
+        game.items.insert(1, sword);
+        game.player.inventory.push(1);
+        game.process_command("wield sword");
+
+        assert!(!game.process_command("attack troll").join("\n").contains("breaks"));
+        assert!(game.process_command("attack troll").join("\n").contains("Your Sword breaks!"));
+        assert_eq!(game.player.equipped_weapon, None);
+        assert!(game.player.inventory.contains(&1));
+        assert_eq!(game.process_command("wield sword"), ["The Sword is broken."]);
+
+        game.player.gold = 10;
+        game.rooms.get_mut(&1).unwrap().is_safe_zone = true;
+        assert_eq!(game.process_command("repair sword"), ["You pay 4 gold and the Sword is as good as new."]);
+        assert_eq!(game.items[&1].durability, Some(2));
+    }
+
+    #[test]
+    fn attack_without_target_picks_the_only_hostile() {
+        let mut game = arena();
+        game.monsters.insert(1, Monster::new(1, "Goblin".to_string(), String::new(), 1, 50, 1, MonsterStatus::Hostile, 100));
+        game.monsters.insert(2, Monster::new(2, "Merchant".to_string(), String::new(), 1, 10, 1, MonsterStatus::Friendly, 100));
+
+        let out = game.process_command("attack").join("\n");
+        assert!(out.starts_with("You attack the Goblin"));
+        assert!(game.monsters[&1].current_health < 50);
+    }
+
+    #[test]
+    fn attack_without_target_fights_the_hostile_not_a_friend_of_the_same_name() {
+        let mut game = arena();
+        game.monsters.insert(1, Monster::new(1, "Goblin".to_string(), String::new(), 1, 50, 1, MonsterStatus::Friendly, 100));
+        game.monsters.insert(2, Monster::new(2, "Goblin".to_string(), String::new(), 1, 50, 1, MonsterStatus::Hostile, 100));
+
+        assert!(game.process_command("attack").join("\n").starts_with("You attack the Goblin"));
+        assert_eq!(game.monsters[&1].current_health, 50);
+        assert!(game.monsters[&2].current_health < 50);
+    }
+
+    #[test]
+    fn attack_without_target_asks_when_several_hostiles() {
+        let mut game = arena();
+        assert_eq!(game.process_command("attack"), ["There's nothing hostile here."]);
+
+        game.monsters.insert(1, Monster::new(1, "Goblin".to_string(), String::new(), 1, 50, 1, MonsterStatus::Hostile, 100));
+        game.monsters.insert(2, Monster::new(2, "Bandit".to_string(), String::new(), 1, 50, 1, MonsterStatus::Hostile, 100));
+        assert_eq!(game.process_command("attack"), ["Attack which one: Bandit, Goblin?"]);
+        assert_eq!(game.monsters[&1].current_health, 50);
+    }
+
+    #[test]
+    fn attacking_one_goblin_turns_its_faction_hostile() {
+        let mut game = arena();
+        let mut goblin = |id, name: &str, status, faction: &str| {
+            let mut m = Monster::new(id, name.to_string(), String::new(), 1, 50, 5, status, 100);
+            m.faction = Some(faction.to_string());
+            game.monsters.insert(id, m);
+        };
+        goblin(1, "Goblin Scout", MonsterStatus::Hostile, "goblins");
+        goblin(2, "Goblin Cook", MonsterStatus::Neutral, "goblins");
+        goblin(3, "Hermit", MonsterStatus::Neutral, "hermits");
+
+        let out = game.process_command("attack scout").join("\n");
+        assert!(out.contains("The Goblin Cook joins the fight!"));
+        assert!(out.contains("The Goblin Cook strikes back"));
+        assert_eq!(game.monsters[&2].friendliness, MonsterStatus::Hostile);
+        assert_eq!(game.monsters[&3].friendliness, MonsterStatus::Neutral);
+    }
+
+    #[test]
+    fn wounds_persist_between_attacks() {
+        let mut game = arena();
+        let mut club = Item::new(5, "Club".to_string(), String::new(), ItemType::Weapon, 3, 1);
+        club.is_weapon = true;
+        club.weapon_dice = 4;
+        club.weapon_sides = 1;
+        game.items.insert(5, club);
+        game.player.inventory.push(5);
+        game.player.equipped_weapon = Some(5);
+        game.monsters.insert(1, Monster::new(1, "Troll".to_string(), String::new(), 1, 12, 1, MonsterStatus::Hostile, 100));
+
+        let first = game.process_command("attack troll").join("\n");
+        assert!(first.contains("It has 8 health remaining."));
+        let second = game.process_command("attack troll").join("\n");
+        assert!(second.contains("It has 4 health remaining."));
+        assert_eq!(game.monsters[&1].current_health, 4);
+
+        let third = game.process_command("attack troll").join("\n");
+        assert!(third.contains("You defeat the Troll!"));
+        assert!(game.monsters[&1].is_dead);
+    }
+
+    #[test]
+    fn wielded_sword_deals_its_dice_damage() {
+        let mut game = arena();
+        game.add_system(Box::new(crate::systems::InventorySystem));
+        let mut sword = Item::new(5, "Sword".to_string(), String::new(), ItemType::Weapon, 3, 10);
+        sword.is_weapon = true;
+        sword.weapon_type = 5;
+        sword.weapon_dice = 7;
+        sword.weapon_sides = 1;
+        game.items.insert(5, sword);
+        game.player.inventory.push(5);
+        game.monsters.insert(1, Monster::new(1, "Troll".to_string(), String::new(), 1, 100, 1, MonsterStatus::Hostile, 100));
+
+        assert_eq!(game.process_command("wield sword"), ["You wield the Sword."]);
+        assert_eq!(game.player.equipped_weapon, Some(5));
+        let out = game.process_command("attack troll").join("\n");
+        assert!(out.contains("You attack the Troll for 7 damage."), "{out}");
+        assert_eq!(game.player.weapon_ability[&5], 6);
+    }
+
+    fn armored_duel(armor_expertise: i32) -> AdventureGame {
+        let mut game = arena();
+        let mut mail = Item::new(5, "Mail".to_string(), String::new(), ItemType::Armor, 5, 1);
+        mail.is_armor = true;
+        mail.armor_value = 2;
+        let mut mace = Item::new(6, "Mace".to_string(), String::new(), ItemType::Weapon, 3, 1);
+        mace.is_weapon = true;
+        mace.weapon_dice = 8;
+        mace.weapon_sides = 1;
+        game.items.extend([(5, mail), (6, mace)]);
+        game.player.inventory.push(5);
+        game.player.equipped_armor = Some(5);
+        game.player.armor_expertise = armor_expertise;
+        let mut brute = Monster::new(1, "Brute".to_string(), String::new(), 1, 100, 1, MonsterStatus::Hostile, 100);
+        brute.weapon_id = Some(6);
+        game.monsters.insert(1, brute);
+        game
+    }
+
+    #[test]
+    fn armor_expertise_reduces_damage_taken() {
+        let mut novice = armored_duel(0);
+        novice.process_command("attack brute");
+        assert_eq!(novice.player.current_health, 94);
+        assert_eq!(novice.player.armor_expertise, 1);
+
+        let mut veteran = armored_duel(10);
+        veteran.process_command("attack brute");
+        assert_eq!(veteran.player.current_health, 96);
+    }
+
+    #[test]
+    fn practising_a_weapon_type_raises_its_ability() {
+        let mut game = arena();
+        let mut club = Item::new(5, "Club".to_string(), String::new(), ItemType::Weapon, 3, 1);
+        club.is_weapon = true;
+        club.weapon_type = 3;
+        club.weapon_dice = 1;
+        club.weapon_sides = 1;
+        game.items.insert(5, club);
+        game.player.inventory.push(5);
+        game.player.equipped_weapon = Some(5);
+        game.monsters.insert(1, Monster::new(1, "Troll".to_string(), String::new(), 1, 100, 1, MonsterStatus::Hostile, 100));
+
+        for _ in 0..5 {
+            let out = game.process_command("attack troll").join("\n");
+            assert!(out.contains("You attack the Troll for 1 damage."), "{out}");
+        }
+        assert_eq!(game.player.weapon_ability[&3], 10);
+        assert_eq!(game.player.weapon_ability[&1], 5);
+        let out = game.process_command("attack troll").join("\n");
+        assert!(out.contains("You attack the Troll for 2 damage."), "{out}");
+    }
+
+    #[test]
+    fn round_resolves_in_agility_order() {
+        let mut game = arena();
+        game.player.agility = 12;
+        game.monsters.insert(1, Monster::new(1, "Slug".to_string(), String::new(), 1, 50, 2, MonsterStatus::Hostile, 100));
+        game.monsters.insert(2, Monster::new(2, "Wolf".to_string(), String::new(), 1, 50, 20, MonsterStatus::Hostile, 100));
+        game.monsters.insert(3, Monster::new(3, "Jackal".to_string(), String::new(), 1, 50, 20, MonsterStatus::Hostile, 100));
+        game.seed_rng(3);
+
+        assert_eq!(CombatSystem::initiative(&game, 1), [
+            Combatant::Monster(2),
+            Combatant::Monster(3),
+            Combatant::Player,
+            Combatant::Monster(1),
+        ]);
+
+        let out = game.process_command("attack slug").join("\n");
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{out}");
+        assert!(lines[0].starts_with("The Wolf"), "{out}");
+        assert!(lines[1].starts_with("The Jackal"), "{out}");
+        assert!(lines[2].starts_with("You attack the Slug"), "{out}");
+        assert!(lines[3].starts_with("The Slug"), "{out}");
+        assert_eq!(game.turn_count, 1);
+    }
+
+    fn flee_setup(player_agility: i32, monster_agility: i32) -> AdventureGame {
+        let mut game = arena();
+        let mut hall = Room::new(2, "Hall".to_string(), "A quiet hall.".to_string());
+        hall.exits.insert("south".to_string(), 1);
+        game.rooms.insert(2, hall);
+        game.rooms.get_mut(&1).unwrap().exits.insert("north".to_string(), 2);
+        game.player.agility = player_agility;
+        game.monsters.insert(1, Monster::new(1, "Ogre".to_string(), String::new(), 1, 50, monster_agility, MonsterStatus::Hostile, 100));
+        game.seed_rng(42);
+        game
+    }
+
+    #[test]
+    fn quick_player_flees_through_chosen_exit() {
+        let mut game = flee_setup(30, 1);
+        let out = game.process_command("flee north").join("\n");
+        assert!(out.starts_with("You flee north!"));
+        assert_eq!(game.player.current_room, 2);
+    }
+
+    #[test]
+    fn slow_player_fails_to_flee_and_is_struck() {
+        let mut game = flee_setup(1, 30);
+        let out = game.process_command("flee north").join("\n");
+        assert!(out.starts_with("You fail to flee!"));
+        assert!(out.contains("The Ogre strikes back"));
+        assert_eq!(game.player.current_room, 1);
+        assert!(game.player.current_health < 100);
+    }
+
+    #[test]
+    fn flee_and_attack_text_comes_from_the_message_table() {
+        let mut game = flee_setup(1, 30);
+        game.message_table.set("flee_failed", "No escape!");
+        game.message_table.set("cant_go_direction", "Nothing lies {direction}.");
+        assert!(game.process_command("flee north")[0].starts_with("No escape!"));
+
+        game.monsters.clear();
+        assert_eq!(game.process_command("flee west"), ["Nothing lies west."]);
+    }
+
+    #[test]
+    fn character_sheet_lists_equipped_weapon() {
+        let mut game = arena();
+        let mut sword = Item::new(5, "Longsword".to_string(), String::new(), ItemType::Weapon, 3, 10);
+        sword.is_weapon = true;
+        sword.weapon_dice = 2;
+        game.items.insert(5, sword);
+        game.player.inventory.push(5);
+        game.add_system(Box::new(crate::systems::InventorySystem));
+
+        game.process_command("wield longsword");
+        let sheet = game.character_sheet();
+        assert!(sheet.iter().any(|line| line.contains("Weapon:") && line.contains("Longsword (2d6)")));
+        assert!(sheet.iter().any(|line| line.ends_with("Arena")));
     }
 }
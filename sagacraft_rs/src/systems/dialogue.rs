@@ -0,0 +1,169 @@
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::game_state::{name_matches, AdventureGame, GameEvent, MonsterStatus, GREETING_TOPIC};
+use crate::systems::System;
+
+/// Lets the player talk to the monsters in the room. Anyone who isn't
+/// hostile answers from its `dialogue` lines, and other systems hear about
+/// it through [`GameEvent::TalkedTo`] (quest givers offering work, talk
+/// objectives).
+#[derive(Debug, Default)]
+pub struct DialogueSystem;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("talk", &["speak"], ArgShape::Target("monster"), "Talk to someone"),
+    CommandSpec::new("say", &["shout", "yell"], ArgShape::Text, "Speak, or say something to someone"),
+];
+
+impl DialogueSystem {
+    /// Find the monster called `target` and have it answer: with its line on
+    /// the first topic `said` mentions, or its greeting when `said` is `None`.
+    fn talk(game: &mut AdventureGame, target: &str, said: Option<&str>) -> String {
+        let mut present: Vec<_> = game.get_monsters_in_room(game.player.current_room)
+            .into_iter()
+            .filter(|m| name_matches(&m.name, target))
+            .collect();
+        present.sort_by_key(|m| m.id);
+        let Some(monster) = present.first() else {
+            return game.messages("no_one_to_talk_to", &[("name", target)]);
+        };
+        let (monster_id, monster_name) = (monster.id, monster.name.clone());
+        if monster.friendliness == MonsterStatus::Hostile {
+            return match monster.line_on("threat") {
+                Some(line) => game.messages("talk_threat", &[("monster", &monster_name), ("line", line)]),
+                None => game.messages("talk_refused", &[("monster", &monster_name)]),
+            };
+        }
+        let line = match said {
+            None => monster.line_on(GREETING_TOPIC),
+            Some(said) => {
+                let words: Vec<String> = said.split_whitespace()
+                    .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+                    .collect();
+                monster.dialogue.iter()
+                    .find(|line| line.topic != GREETING_TOPIC && words.contains(&line.topic.to_lowercase()))
+                    .map(|line| line.text.as_str())
+            }
+        };
+        let reply = match (line, said) {
+            (Some(line), _) => game.messages("talk_line", &[("monster", &monster_name), ("line", line)]),
+            (None, None) => game.messages("talk_greeting", &[("monster", &monster_name)]),
+            (None, Some(_)) => game.messages("talk_no_answer", &[("monster", &monster_name)]),
+        };
+        game.events.push(GameEvent::TalkedTo { monster_id, monster_name });
+        reply
+    }
+
+    /// `say <text>` to the room, or `say <text> to <monster>`. Only a "to"
+    /// followed by the name of someone here addresses them, so "say I want
+    /// to leave" is said to the room.
+    fn say(game: &mut AdventureGame, args: &[&str]) -> String {
+        if args.is_empty() {
+            return game.messages("say_what", &[]);
+        }
+        let present = game.get_monsters_in_room(game.player.current_room);
+        let addressed = (1..args.len().saturating_sub(1)).rev()
+            .filter(|&at| args[at].eq_ignore_ascii_case("to"))
+            .map(|at| (args[..at].join(" "), args[at + 1..].join(" ")))
+            .find(|(_, target)| present.iter().any(|m| name_matches(&m.name, target)));
+        let (text, target) = match addressed {
+            Some((text, target)) => (text, Some(target)),
+            None => (args.join(" "), None),
+        };
+        let mut lines = vec![game.messages("you_say", &[("text", &text)])];
+        match target {
+            Some(target) => lines.push(Self::talk(game, &target, Some(&text))),
+            None => {
+                let mut listeners: Vec<_> = game.get_monsters_in_room(game.player.current_room);
+                listeners.retain(|m| m.friendliness != MonsterStatus::Hostile);
+                listeners.sort_by_key(|m| m.id);
+                let listeners: Vec<String> = listeners.iter().map(|m| m.name.clone()).collect();
+                lines.extend(listeners.iter().map(|name| game.messages("turns_to_face", &[("monster", name)])));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl System for DialogueSystem {
+    fn name(&self) -> &'static str {
+        "dialogue"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
+    fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
+        match command::resolve(COMMANDS, command)? {
+            "talk" => {
+                let target = args.strip_prefix(&["to"]).unwrap_or(args).join(" ");
+                if target.is_empty() {
+                    Some(game.messages("talk_to_whom", &[]))
+                } else {
+                    Some(Self::talk(game, &target, None))
+                }
+            }
+            "say" => Some(Self::say(game, args)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Monster, Room};
+
+    fn tavern() -> AdventureGame {
+        let mut game = crate::engine::Engine::new("").game;
+        game.load_adventure_str(r#"{
+            "rooms": [{"id": 1, "name": "Tavern"}],
+            "monsters": [
+                {"id": 1, "name": "Innkeeper", "room_id": 1, "friendliness": "friendly",
+                 "dialogue": [
+                     {"text": "Welcome, traveller!"},
+                     {"topic": "rooms", "text": "Five gold a night."}
+                 ]},
+                {"id": 2, "name": "Drunk", "room_id": 1, "friendliness": "neutral"},
+                {"id": 3, "name": "Thug", "room_id": 1, "friendliness": "hostile",
+                 "dialogue": [{"topic": "threat", "text": "Mind your own business."}]}
+            ]
+        }"#).unwrap();
+        game.player.current_room = 1;
+        game
+    }
+
+    #[test]
+    fn monsters_answer_from_their_dialogue() {
+        let mut game = tavern();
+        assert_eq!(game.process_command("talk to innkeeper"), ["The Innkeeper says: \"Welcome, traveller!\""]);
+        assert_eq!(game.process_command("talk drunk"), ["The Drunk greets you."]);
+        assert_eq!(game.process_command("talk thug"), ["The Thug snarls: \"Mind your own business.\""]);
+
+        assert_eq!(game.process_command("say any rooms free? to innkeeper"),
+            ["You say: \"any rooms free?\"", "The Innkeeper says: \"Five gold a night.\""]);
+        assert_eq!(game.process_command("say nice weather to drunk"),
+            ["You say: \"nice weather\"", "The Drunk has nothing to say about that."]);
+        assert_eq!(game.process_command("say hello"),
+            ["You say: \"hello\"", "Innkeeper turns to face you.", "Drunk turns to face you."]);
+        assert_eq!(game.process_command("say I want to leave"),
+            ["You say: \"I want to leave\"", "Innkeeper turns to face you.", "Drunk turns to face you."]);
+        assert_eq!(game.process_command("say time to go to innkeeper"),
+            ["You say: \"time to go\"", "The Innkeeper has nothing to say about that."]);
+    }
+
+    #[test]
+    fn only_peaceful_monsters_answer() {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Camp".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.monsters.insert(1, Monster::new(1, "Scout".to_string(), String::new(), 1, 10, 5, MonsterStatus::Friendly, 100));
+        game.monsters.insert(2, Monster::new(2, "Bandit".to_string(), String::new(), 1, 10, 5, MonsterStatus::Hostile, 0));
+        game.add_system(Box::new(DialogueSystem));
+
+        assert_eq!(game.process_command("talk to scout"), ["The Scout greets you."]);
+        assert_eq!(game.process_command("talk bandit"), ["The Bandit glares at you and says nothing."]);
+        assert_eq!(game.process_command("talk ghost"), ["There's no 'ghost' here to talk to."]);
+        assert_eq!(game.process_command("talk"), ["Talk to whom?"]);
+    }
+}
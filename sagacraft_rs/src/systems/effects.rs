@@ -0,0 +1,119 @@
+use crate::effects::{Action, Trigger};
+use crate::game_state::{AdventureGame, GameEvent};
+use crate::systems::System;
+
+/// Runs the adventure's scripted effects when their trigger happens.
+#[derive(Debug, Default)]
+pub struct EffectSystem;
+
+impl EffectSystem {
+    /// Run every effect whose trigger is `trigger` and whose condition holds.
+    fn fire(trigger: &Trigger, game: &mut AdventureGame, out: &mut Vec<String>) {
+        for index in 0..game.effects.len() {
+            let effect = game.effects[index].clone();
+            if effect.trigger != *trigger
+                || (effect.once && game.fired_effects.contains(&index))
+                || !effect.condition.holds(game)
+            {
+                continue;
+            }
+            game.fired_effects.insert(index);
+            for action in effect.actions {
+                Self::apply(action, game, out);
+            }
+        }
+    }
+
+    fn apply(action: Action, game: &mut AdventureGame, out: &mut Vec<String>) {
+        match action {
+            Action::Print(text) => out.push(text),
+            Action::SetFlag(flag) => {
+                game.flags.insert(flag);
+            }
+            Action::ClearFlag(flag) => {
+                game.flags.remove(&flag);
+            }
+            Action::Spawn { monster, room } => {
+                let room = room.unwrap_or(game.player.current_room);
+                if let Some(m) = game.monsters.get_mut(&monster) {
+                    m.room_id = room;
+                    m.is_dead = false;
+                    m.current_health = m.hardiness;
+                }
+            }
+            Action::Teleport(room) => {
+                if game.rooms.contains_key(&room) {
+                    game.enter_room(room);
+                    out.push(game.look());
+                }
+            }
+            Action::Damage(amount) => {
+                game.player.current_health -= amount;
+                if game.player.current_health <= 0 {
+                    out.push(game.handle_player_death());
+                }
+            }
+        }
+    }
+}
+
+impl System for EffectSystem {
+    fn name(&self) -> &'static str {
+        "effects"
+    }
+
+    fn on_command(&mut self, _command: &str, _args: &[&str], _game: &mut AdventureGame) -> Option<String> {
+        None
+    }
+
+    fn on_events(&mut self, events: &[GameEvent], game: &mut AdventureGame) -> Option<String> {
+        let mut out = Vec::new();
+        for event in events {
+            let trigger = match event {
+                GameEvent::RoomEntered { room_id } => Trigger::OnEnterRoom(*room_id),
+                GameEvent::ItemCollected { item_id, .. } => Trigger::OnTakeItem(*item_id),
+                _ => continue,
+            };
+            Self::fire(&trigger, game, &mut out);
+        }
+        if out.is_empty() { None } else { Some(out.join("\n")) }
+    }
+
+    fn on_turn(&mut self, game: &mut AdventureGame) -> Option<String> {
+        let mut out = Vec::new();
+        Self::fire(&Trigger::OnTurn, game, &mut out);
+        if out.is_empty() { None } else { Some(out.join("\n")) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::BasicWorldSystem;
+
+    #[test]
+    fn entering_a_room_fires_its_effect_once() {
+        let mut game = AdventureGame::new(String::new());
+        game.load_adventure_str(r#"{
+            "rooms": [
+                {"id": 1, "name": "Hall", "exits": {"north": 2}},
+                {"id": 2, "name": "Belfry", "exits": {"south": 1}}
+            ],
+            "effects": [{
+                "trigger": {"on_enter_room": 2},
+                "actions": [{"print": "A bell tolls overhead."}, {"set_flag": "heard_bell"}],
+                "once": true
+            }]
+        }"#).unwrap();
+        game.add_system(Box::new(BasicWorldSystem));
+        game.add_system(Box::new(EffectSystem));
+
+        let out = game.process_command("north");
+        assert_eq!(out.last().map(String::as_str), Some("A bell tolls overhead."));
+        assert!(game.flags.contains("heard_bell"));
+
+        game.process_command("south");
+        let out = game.process_command("north");
+        assert!(!out.iter().any(|line| line.contains("bell")));
+    }
+}
@@ -1,84 +1,530 @@
-use crate::game_state::AdventureGame;
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::game_state::{item_list, AdventureGame, InventorySort};
+use crate::output::Reply;
+use crate::settings::InventoryMode;
 use crate::systems::System;
 
 #[derive(Debug, Default)]
 pub struct InventorySystem;
 
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("inventory", &["i", "inv"], ArgShape::OptionalTarget("sort <key>|group"), "Show inventory, or change how it is listed"),
+    CommandSpec::new("slots", &[], ArgShape::None, "Show free inventory slots"),
+    CommandSpec::new("take", &["get"], ArgShape::Target("items|all"), "Pick up items, or take one from a container"),
+    CommandSpec::new("drop", &[], ArgShape::Target("items|all"), "Drop items"),
+    CommandSpec::new("open", &[], ArgShape::Target("container"), "Open a container"),
+    CommandSpec::new("close", &["shut"], ArgShape::Target("container"), "Close a container"),
+    CommandSpec::new("put", &["place"], ArgShape::Pair("item", "in"), "Put an item in a container"),
+    CommandSpec::new("equip", &["wield"], ArgShape::Target("item"), "Equip a weapon or armor"),
+    CommandSpec::new("wear", &[], ArgShape::Target("armor"), "Put on a piece of armor"),
+    CommandSpec::new("remove", &[], ArgShape::Target("armor"), "Take off worn armor"),
+    CommandSpec::new("unequip", &[], ArgShape::Target("slot"), "Unequip weapon or armor"),
+    CommandSpec::new("use", &[], ArgShape::Target("item"), "Use/consume an item, or use it on something"),
+    CommandSpec::new("examine", &["x", "inspect"], ArgShape::Target("thing"), "Examine an item or creature"),
+    CommandSpec::new("combine", &["craft"], ArgShape::Pair("item", "with"), "Combine two items into one"),
+    CommandSpec::new("deposit", &["bank"], ArgShape::OptionalTarget("amount"), "Bank gold in a safe zone"),
+    CommandSpec::new("withdraw", &[], ArgShape::OptionalTarget("amount"), "Take gold out of the bank"),
+    CommandSpec::new("repair", &["mend"], ArgShape::Target("item"), "Repair worn equipment for gold"),
+];
+
+/// Parse an optional gold amount; `None` means "all of it".
+fn gold_amount(args: &[&str]) -> Result<Option<i32>, String> {
+    match args.first().copied() {
+        None | Some("all") => Ok(None),
+        Some(n) => n.parse().map(Some).map_err(|_| format!("'{}' is not an amount of gold.", n)),
+    }
+}
+
+impl InventorySystem {
+    /// The `inventory` listing, in the player's chosen order.
+    fn listing(game: &AdventureGame) -> String {
+        let mut result = match game.settings.inventory.mode {
+            InventoryMode::Weight => {
+                let (cur, max) = game.carry_weight();
+                format!("Inventory ({}/{} weight):\n", cur, max)
+            }
+            InventoryMode::Slots => {
+                let (used, max) = game.slot_usage();
+                format!("Inventory ({}/{} slots):\n", used, max)
+            }
+        };
+        let grouped = game.player.inventory_grouped;
+        let mut heading = None;
+        for item in game.sorted_inventory() {
+            if grouped && heading != Some(&item.item_type) {
+                heading = Some(&item.item_type);
+                result.push_str(&format!("  {}:\n", item.item_type.heading()));
+            }
+            let equipped = if game.player.equipped_weapon == Some(item.id) {
+                " [wielded]"
+            } else if game.player.equipped_armor == Some(item.id) {
+                " [worn]"
+            } else {
+                ""
+            };
+            let indent = if grouped { "    " } else { "  " };
+            result.push_str(&format!("{}- {}{}\n", indent, item.name, equipped));
+        }
+        result.trim_end().to_string()
+    }
+}
+
 impl System for InventorySystem {
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
     fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
-        match command {
-            "inventory" | "inv" | "i" => {
-                if game.player.inventory.is_empty() {
-                    Some("Your inventory is empty.".to_string())
-                } else {
-                    let (cur, max) = game.carry_weight();
-                    let mut result = format!("Inventory ({}/{} weight):\n", cur, max);
-                    for &item_id in &game.player.inventory {
-                        if let Some(item) = game.items.get(&item_id) {
-                            let equipped = if game.player.equipped_weapon == Some(item_id) {
-                                " [wielded]"
-                            } else if game.player.equipped_armor == Some(item_id) {
-                                " [worn]"
-                            } else {
-                                ""
-                            };
-                            result.push_str(&format!("  - {}{}\n", item.name, equipped));
-                        }
+        self.on_command_reply(command, args, game).map(Reply::into_text)
+    }
+
+    fn on_command_reply(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<Reply> {
+        match command::resolve(COMMANDS, command)? {
+            "inventory" => match args {
+                ["sort", key] => match key.parse::<InventorySort>() {
+                    Ok(sort) => {
+                        game.player.inventory_sort = sort;
+                        Some(match sort {
+                            InventorySort::None => game.messages("inventory_unsorted", &[]),
+                            _ => game.messages("inventory_sorted", &[("key", key)]),
+                        }.into())
+                    }
+                    Err(()) => Some(Reply::Error(game.messages("inventory_sort_keys", &[]))),
+                },
+                ["sort"] => Some(game.messages("inventory_sort_keys", &[]).into()),
+                ["group"] | ["ungroup"] => {
+                    game.player.inventory_grouped = args[0] == "group";
+                    let key = if game.player.inventory_grouped { "inventory_grouped" } else { "inventory_ungrouped" };
+                    Some(game.messages(key, &[]).into())
+                }
+                _ if game.player.inventory.is_empty() => Some(game.messages("inventory_empty", &[]).into()),
+                _ => Some(Self::listing(game).into()),
+            },
+            "slots" => {
+                match game.settings.inventory.mode {
+                    InventoryMode::Slots => {
+                        let (used, max) = game.slot_usage();
+                        Some(format!(
+                            "Inventory slots: {} used, {} free ({} total).",
+                            used, max.saturating_sub(used), max
+                        ).into())
+                    }
+                    InventoryMode::Weight => {
+                        let (cur, max) = game.carry_weight();
+                        Some(format!(
+                            "This adventure limits inventory by weight, not slots ({}/{} weight used).",
+                            cur, max
+                        ).into())
                     }
-                    Some(result.trim_end().to_string())
                 }
             }
-            "take" | "get" => {
-                let item_name = args.join(" ");
-                if item_name.is_empty() {
-                    Some("Take what?".to_string())
+            "take" => {
+                // "take <item> from <container>"
+                if let Some(at) = args.iter().rposition(|w| w.eq_ignore_ascii_case("from"))
+                    && at > 0
+                    && at + 1 < args.len()
+                {
+                    let (item_name, container) = (args[..at].join(" "), args[at + 1..].join(" "));
+                    return Some(game.take_from_container(&item_name, &container).into());
+                }
+                let in_room = game.get_items_in_room(game.player.current_room);
+                let names = item_list(&args.join(" "), in_room.iter().map(|i| i.name.as_str()));
+                if names.is_empty() {
+                    Some(Reply::Error(game.messages("take_what", &[])))
                 } else {
-                    Some(game.take_item(&item_name).unwrap_or_else(|e| e))
+                    Some(game.take_items(&names).join("\n").into())
                 }
             }
+            "open" | "close" => {
+                let name = args.join(" ");
+                Some(match (command == "open", name.is_empty()) {
+                    (true, true) => Reply::Error(game.messages("open_what", &[])),
+                    (false, true) => Reply::Error(game.messages("close_what", &[])),
+                    (true, false) => game.open_container(&name).into(),
+                    (false, false) => game.close_container(&name).into(),
+                })
+            }
+            "put" => {
+                let text = args.join(" ");
+                let Some((item_name, container)) = text.split_once(" in ").or_else(|| text.split_once(" into ")) else {
+                    return Some(Reply::Error(game.messages("put_what", &[])));
+                };
+                Some(game.put_in_container(item_name.trim(), container.trim()).into())
+            }
             "drop" => {
+                let carried = game.player.inventory.iter().filter_map(|id| game.items.get(id));
+                let names = item_list(&args.join(" "), carried.map(|i| i.name.as_str()));
+                if names.is_empty() {
+                    Some(Reply::Error(game.messages("drop_what", &[])))
+                } else {
+                    Some(game.drop_items(&names).join("\n").into())
+                }
+            }
+            "equip" => {
                 let item_name = args.join(" ");
                 if item_name.is_empty() {
-                    Some("Drop what?".to_string())
+                    Some(Reply::Error(game.messages("equip_what", &[])))
                 } else {
-                    match game.drop_item(&item_name) {
-                        Some(name) => Some(format!("Dropped: {}.", name)),
-                        None => Some("You don't have that.".to_string()),
-                    }
+                    Some(game.equip_item(&item_name).into())
                 }
             }
-            "equip" | "wield" | "wear" => {
+            "wear" => {
                 let item_name = args.join(" ");
                 if item_name.is_empty() {
-                    Some("Equip what?".to_string())
+                    Some(Reply::Error(game.messages("wear_what", &[])))
                 } else {
-                    Some(game.equip_item(&item_name).unwrap_or_else(|e| e))
+                    Some(game.wear_item(&item_name).into())
                 }
             }
-            "unequip" | "remove" => {
+            "unequip" => {
                 match args.first().copied() {
-                    None => Some("Unequip what? Specify 'weapon' or 'armor'.".to_string()),
-                    Some(slot) => Some(game.unequip_slot(slot).unwrap_or_else(|e| e)),
+                    None => Some(Reply::Error(game.messages("unequip_what", &[]))),
+                    Some(slot) => Some(game.unequip_slot(slot).into()),
+                }
+            }
+            "remove" => {
+                let item_name = args.join(" ");
+                match item_name.as_str() {
+                    "" => Some(Reply::Error(game.messages("remove_what", &[]))),
+                    "weapon" | "armor" => Some(game.unequip_slot(&item_name).into()),
+                    _ => Some(game.remove_item(&item_name).into()),
                 }
             }
             "use" => {
+                // "use <item> on <target>" / "use <item> with <target>"
+                if let Some(at) = args.iter().position(|w| matches!(w.to_lowercase().as_str(), "on" | "with")) {
+                    let (item_name, target) = (args[..at].join(" "), args[at + 1..].join(" "));
+                    return Some(match (item_name.is_empty(), target.is_empty()) {
+                        (true, _) => Reply::Error(game.messages("use_what", &[])),
+                        (false, true) => Reply::Error(game.messages("use_on_what", &[])),
+                        (false, false) => game.use_item_on(&item_name, &target).into(),
+                    });
+                }
                 let item_name = args.join(" ");
                 if item_name.is_empty() {
-                    Some("Use what?".to_string())
+                    Some(Reply::Error(game.messages("use_what", &[])))
                 } else {
-                    Some(game.use_item(&item_name).unwrap_or_else(|e| e))
+                    Some(game.use_item(&item_name).into())
                 }
             }
-            "examine" | "inspect" | "x" => {
+            "examine" => {
                 let item_name = args.join(" ");
                 if item_name.is_empty() {
-                    Some("Examine what?".to_string())
+                    Some(Reply::Error(game.messages("examine_what", &[])))
                 } else {
-                    Some(game.examine_item(&item_name)
-                        .unwrap_or_else(|| format!("You don't see any '{}' here.", item_name)))
+                    Some(game.examine(&item_name).unwrap_or_else(|| game.messages("nothing_special", &[])).into())
+                }
+            }
+            "combine" => {
+                let text = args.join(" ");
+                let Some((first, second)) = text.split_once(" with ").or_else(|| text.split_once(" and ")) else {
+                    return Some(Reply::Error(game.messages("combine_what", &[])));
+                };
+                Some(match game.combine_items(first.trim(), second.trim()) {
+                    Ok(name) => Reply::Text(game.messages("combined", &[("item", &name)])),
+                    Err(err) => Reply::Error(err),
+                })
+            }
+            "deposit" => {
+                let result = gold_amount(args).and_then(|amount| game.deposit_gold(amount));
+                Some(match result {
+                    Ok(gold) => Reply::Text(game.messages("deposited", &[
+                        ("gold", &gold.to_string()),
+                        ("banked", &game.player.banked_gold.to_string()),
+                    ])),
+                    Err(err) => Reply::Error(err),
+                })
+            }
+            "withdraw" => {
+                let result = gold_amount(args).and_then(|amount| game.withdraw_gold(amount));
+                Some(match result {
+                    Ok(gold) => Reply::Text(game.messages("withdrew", &[
+                        ("gold", &gold.to_string()),
+                        ("banked", &game.player.banked_gold.to_string()),
+                    ])),
+                    Err(err) => Reply::Error(err),
+                })
+            }
+            "repair" => {
+                let item_name = args.join(" ");
+                if item_name.is_empty() {
+                    return Some(Reply::Error(game.messages("repair_what", &[])));
                 }
+                Some(match game.repair_item(&item_name) {
+                    Ok((name, cost)) => Reply::Text(game.messages("repaired", &[("cost", &cost.to_string()), ("item", &name)])),
+                    Err(err) => Reply::Error(err),
+                })
             }
             _ => None,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Item, ItemType, Monster, MonsterStatus, Room};
+    use crate::systems::CombatSystem;
+
+    fn storeroom() -> AdventureGame {
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Storeroom".to_string(), "Shelves everywhere.".to_string()));
+        game.player.current_room = 1;
+        game.add_system(Box::new(InventorySystem));
+        for (id, name, weight) in [(1, "Anvil", 40), (2, "Feather", 1), (3, "Coin", 1), (4, "Coin", 1)] {
+            let mut item = Item::new(id, name.to_string(), String::new(), ItemType::Normal, weight, 1);
+            item.location = 1;
+            game.items.insert(id, item);
+        }
+        game
+    }
+
+    #[test]
+    fn take_all_and_drop_all_move_everything_in_one_turn() {
+        let mut game = storeroom();
+        game.items.retain(|&id, _| id <= 2);
+
+        assert_eq!(game.process_command("take all"), ["Taken: Anvil.", "Taken: Feather."]);
+        assert_eq!(game.player.inventory, [1, 2]);
+        assert_eq!(game.turn_count, 1);
+        assert_eq!(game.process_command("take all"), ["There's nothing here to take."]);
+
+        assert_eq!(game.process_command("drop all"), ["Dropped: Anvil.", "Dropped: Feather."]);
+        assert!(game.player.inventory.is_empty());
+        assert_eq!(game.items[&2].location, 1);
+        assert_eq!(game.turn_count, 2);
+    }
+
+    #[test]
+    fn listed_items_are_taken_one_line_each() {
+        let mut game = storeroom();
+        assert_eq!(game.process_command("take feather, anvil and lute"),
+            ["Taken: Feather.", "Taken: Anvil.", "You can't take that."]);
+        assert_eq!(game.process_command("drop anvil and feather"), ["Dropped: Anvil.", "Dropped: Feather."]);
+    }
+
+    #[test]
+    fn an_item_named_with_and_is_not_split() {
+        let mut game = storeroom();
+        let mut snack = Item::new(5, "Bread and Butter".to_string(), String::new(), ItemType::Normal, 1, 1);
+        snack.location = 1;
+        game.items.insert(5, snack);
+
+        assert_eq!(game.process_command("take bread and butter"), ["Taken: Bread and Butter."]);
+        assert_eq!(game.process_command("drop Bread and Butter"), ["Dropped: Bread and Butter."]);
+        assert_eq!(game.process_command("take bread and feather"), ["Taken: Bread and Butter.", "Taken: Feather."]);
+    }
+
+    #[test]
+    fn weight_mode_rejects_items_over_carry_limit() {
+        let mut game = storeroom();
+        game.player.hardiness = 4;
+
+        assert_eq!(game.process_command("take feather"), ["Taken: Feather."]);
+        assert_eq!(game.current_carry_weight(), 1);
+        assert_eq!(game.process_command("take anvil"), ["It's too heavy; you're carrying too much."]);
+        assert_eq!(game.player.inventory, [2]);
+    }
+
+    #[test]
+    fn slot_mode_rejects_new_items_when_full_but_stacks_duplicates() {
+        let mut game = storeroom();
+        game.settings.inventory.mode = InventoryMode::Slots;
+        game.settings.inventory.max_slots = 2;
+
+        game.process_command("take anvil");
+        game.process_command("take coin");
+        let out = game.process_command("take feather").join("\n");
+        assert!(out.contains("no free inventory slots (2/2 used)"));
+
+        let out = game.process_command("take coin").join("\n");
+        assert!(out.contains("Taken: Coin."));
+        assert_eq!(game.player.inventory.len(), 3);
+        assert_eq!(game.slot_usage(), (2, 2));
+    }
+
+    #[test]
+    fn sorting_by_name_lists_items_alphabetically() {
+        let mut game = storeroom();
+        game.player.hardiness = 10;
+        for item in ["feather", "anvil", "coin"] {
+            game.process_command(&format!("take {}", item));
+        }
+        game.player.equipped_weapon = Some(1);
+
+        assert_eq!(game.process_command("inventory sort name"), ["Your inventory is now sorted by name."]);
+        let out = game.process_command("inventory").join("\n");
+        assert_eq!(out, "Inventory (42/100 weight):\n  - Anvil [wielded]\n  - Coin\n  - Feather");
+        assert_eq!(game.player.inventory, [2, 1, 3]);
+    }
+
+    #[test]
+    fn examine_finds_room_items_then_carried_ones_then_monsters() {
+        let mut game = storeroom();
+        game.add_system(Box::new(crate::systems::BasicWorldSystem));
+        game.items.get_mut(&2).unwrap().description = "Light as air.".to_string();
+        let mut lamp = Item::new(5, "Lamp".to_string(), "Dented brass.".to_string(), ItemType::Normal, 1, 1);
+        lamp.location = 0;
+        game.items.insert(5, lamp);
+        game.player.inventory.push(5);
+        game.monsters.insert(1, Monster::new(1, "Cat".to_string(), "It ignores you.".to_string(), 1, 3, 5, MonsterStatus::Neutral, 50));
+
+        assert!(game.process_command("examine FEATHER").join("\n").starts_with("Feather\nLight as air."));
+        assert!(game.process_command("x lamp").join("\n").starts_with("Lamp\nDented brass."));
+        assert_eq!(game.process_command("look at cat"), ["Cat", "It ignores you."]);
+        assert_eq!(game.process_command("examine dragon"), ["You see nothing special about that."]);
+    }
+
+    #[test]
+    fn closed_chest_hides_what_was_put_inside() {
+        let mut game = crate::engine::Engine::new("").game;
+        game.load_adventure_str(r#"{
+            "rooms": [{"id": 1, "name": "Attic"}],
+            "items": [
+                {"id": 1, "name": "Chest", "type": "container", "location": 1, "is_takeable": false, "contents": [2]},
+                {"id": 2, "name": "Doll", "location": 1},
+                {"id": 3, "name": "Key", "location": 0}
+            ]
+        }"#).unwrap();
+        game.player.inventory = vec![3];
+        assert!(!game.look().contains("Doll"));
+
+        assert_eq!(game.process_command("put key in chest"), ["The Chest is closed."]);
+        assert_eq!(game.process_command("open chest"), ["You open the Chest.", "The Chest holds: Doll."]);
+        assert_eq!(game.process_command("put key in chest"), ["You put the Key in the Chest."]);
+        assert!(game.player.inventory.is_empty());
+        assert!(!game.look().contains("Key"));
+        assert_eq!(game.process_command("look in chest"), ["The Chest holds: Doll, Key."]);
+
+        assert_eq!(game.process_command("close chest"), ["You close the Chest."]);
+        assert_eq!(game.process_command("look in chest"), ["The Chest is closed."]);
+        assert_eq!(game.process_command("take key from chest"), ["The Chest is closed."]);
+        assert_eq!(game.process_command("take key"), ["You can't take that."]);
+
+        game.process_command("open chest");
+        assert_eq!(game.process_command("take key from chest"), ["Taken: Key."]);
+        assert_eq!(game.player.inventory, [3]);
+        assert_eq!(game.items[&1].contents, [2]);
+    }
+
+    #[test]
+    fn combining_ingredients_crafts_the_recipe_output() {
+        let mut game = storeroom();
+        for (id, name) in [(5, "Rope"), (6, "Hook"), (7, "Grappling hook")] {
+            game.items.insert(id, Item::new(id, name.to_string(), String::new(), ItemType::Normal, 1, 1));
+        }
+        game.player.inventory.extend([2, 5, 6]);
+        game.recipes.push(crate::game_state::Recipe { inputs: [5, 6], output: 7 });
+
+        assert_eq!(game.process_command("combine rope with feather"), ["Nothing happens."]);
+        assert_eq!(game.process_command("combine hook with rope"), ["You combine them into: Grappling hook."]);
+        assert_eq!(game.player.inventory, [2, 7]);
+        assert!(!game.items.contains_key(&5) && !game.items.contains_key(&6));
+        assert_eq!(game.process_command("combine rope"), ["Combine what with what?"]);
+    }
+
+    #[test]
+    fn heavy_purse_reduces_carry_capacity_until_banked() {
+        let mut game = storeroom();
+        game.player.hardiness = 5; // carries 50
+        game.player.gold = 1500;
+
+        assert!(game.process_command("take anvil").join("\n").contains("Taken: Anvil."));
+        game.process_command("drop anvil");
+
+        game.settings.economy.gold_weight = 1; // 15 weight of gold
+        assert_eq!(game.carry_weight(), (15, 50));
+        assert!(game.process_command("take anvil").join("\n").contains("It's too heavy; you're carrying too much."));
+        assert!(game.process_command("deposit").join("\n").contains("nowhere safe"));
+
+        game.rooms.get_mut(&1).unwrap().is_safe_zone = true;
+        assert!(game.process_command("bank 1000").join("\n").contains("You deposit 1000 gold. (1000 banked)"));
+        assert_eq!(game.carry_weight(), (5, 50));
+        assert!(game.process_command("take anvil").join("\n").contains("Taken: Anvil."));
+    }
+
+    fn carry_sword(game: &mut AdventureGame, id: i32, name: &str) {
+        let mut sword = Item::new(id, name.to_string(), String::new(), ItemType::Weapon, 3, 10);
+        sword.is_weapon = true;
+        game.items.insert(id, sword);
+        game.player.inventory.push(id);
+    }
+
+    #[test]
+    fn equip_is_blocked_until_player_levels_up() {
+        let mut game = storeroom();
+        carry_sword(&mut game, 10, "Dagger");
+        carry_sword(&mut game, 11, "Greatsword");
+        game.items.get_mut(&11).unwrap().min_level = Some(2);
+        game.process_command("wield dagger");
+
+        let out = game.process_command("wield greatsword").join("\n");
+        assert!(out.contains("You aren't strong enough to wield that."));
+        assert_eq!(game.player.equipped_weapon, Some(10));
+
+        game.player.experience_points = 100;
+        assert!(CombatSystem::check_level_up(&mut game).is_some());
+        let out = game.process_command("wield greatsword").join("\n");
+        assert!(out.contains("You put away the Dagger and wield the Greatsword."));
+        assert_eq!(game.player.equipped_weapon, Some(11));
+    }
+
+    fn armored_duel() -> AdventureGame {
+        let mut game = storeroom();
+        game.add_system(Box::new(CombatSystem));
+        game.player.hardiness = 100;
+        game.player.current_health = 100;
+
+        let mut club = Item::new(20, "Club".to_string(), String::new(), ItemType::Weapon, 3, 1);
+        club.is_weapon = true;
+        club.weapon_dice = 5;
+        club.weapon_sides = 1;
+        game.items.insert(20, club);
+        let mut brute = Monster::new(1, "Brute".to_string(), String::new(), 1, 1000, 5, MonsterStatus::Hostile, 0);
+        brute.weapon_id = Some(20);
+        game.monsters.insert(1, brute);
+
+        let mut mail = Item::new(21, "Chain Mail".to_string(), String::new(), ItemType::Armor, 5, 50);
+        mail.is_armor = true;
+        mail.armor_value = 3;
+        game.items.insert(21, mail);
+        game.player.inventory.push(21);
+        game
+    }
+
+    fn damage_taken(game: &mut AdventureGame) -> i32 {
+        let before = game.player.current_health;
+        game.process_command("attack brute");
+        before - game.player.current_health
+    }
+
+    #[test]
+    fn worn_armor_reduces_damage_until_removed() {
+        let mut game = armored_duel();
+        assert_eq!(damage_taken(&mut game), 5);
+
+        let out = game.process_command("wear chain mail").join("\n");
+        assert!(out.contains("You wear the Chain Mail."));
+        assert_eq!(game.items[&21].location, -1);
+        assert_eq!(damage_taken(&mut game), 2);
+
+        let out = game.process_command("remove chain mail").join("\n");
+        assert!(out.contains("You take off the Chain Mail."));
+        assert_eq!(game.items[&21].location, 0);
+        assert!(game.player.inventory.contains(&21));
+        assert_eq!(damage_taken(&mut game), 5);
+    }
+
+    #[test]
+    fn wearing_a_non_armor_item_is_refused() {
+        let mut game = storeroom();
+        game.process_command("take feather");
+        let out = game.process_command("wear feather").join("\n");
+        assert!(out.contains("You can't wear the Feather."));
+        assert_eq!(game.player.equipped_armor, None);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::game_state::AdventureGame;
+use crate::systems::System;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("undo", &[], ArgShape::None, "Take back the last turn"),
+];
+
+/// Commands about the game session rather than the game world.
+#[derive(Debug, Default)]
+pub struct MetaSystem;
+
+impl System for MetaSystem {
+    fn name(&self) -> &'static str {
+        "meta"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
+    fn on_command(&mut self, command: &str, _args: &[&str], game: &mut AdventureGame) -> Option<String> {
+        match command::resolve(COMMANDS, command)? {
+            "undo" if game.request_undo() => Some(game.messages("undone", &[])),
+            "undo" => Some(game.messages("nothing_to_undo", &[])),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Engine;
+
+    #[test]
+    fn undo_walks_the_player_back() {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{"rooms": [
+            {"id": 1, "name": "Porch", "exits": {"north": 2}},
+            {"id": 2, "name": "Parlour", "exits": {"south": 1}}
+        ]}"#).unwrap();
+
+        assert_eq!(engine.send("undo"), ["There is nothing to undo."]);
+        engine.send("look");
+        engine.send("north");
+        assert_eq!(engine.game.player.current_room, 2);
+        assert_eq!(engine.send("undo"), ["Reverted."]);
+        assert_eq!(engine.game.player.current_room, 1);
+        assert_eq!(engine.game.turn_count, 0);
+        assert_eq!(engine.send("undo"), ["There is nothing to undo."]);
+    }
+
+    #[test]
+    fn undoing_a_meal_puts_the_food_back() {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{
+            "rooms": [{"id": 1, "name": "Kitchen"}],
+            "items": [{"id": 1, "name": "Apple", "type": "edible", "location": 1, "is_takeable": true, "value": 5}]
+        }"#).unwrap();
+        engine.send("take apple");
+        engine.send("use apple");
+        assert!(!engine.game.items.contains_key(&1));
+
+        assert_eq!(engine.send("undo"), ["Reverted."]);
+        assert_eq!(engine.game.player.inventory, [1]);
+        assert_eq!(engine.game.items[&1].location, 0);
+        assert!(engine.send("inventory").join("\n").contains("Apple"));
+        assert!(engine.send("use apple")[0].starts_with("You consume the Apple."));
+    }
+}
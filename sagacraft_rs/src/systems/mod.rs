@@ -2,19 +2,56 @@ pub mod basic_world;
 pub mod inventory;
 pub mod combat;
 pub mod quests;
+pub mod autosave;
+pub mod clock;
+pub mod effects;
+pub mod dialogue;
+pub mod score;
+pub mod meta;
 
 pub use basic_world::BasicWorldSystem;
 pub use inventory::InventorySystem;
 pub use combat::CombatSystem;
 pub use quests::QuestSystem;
+pub use autosave::AutosaveSystem;
+pub use clock::ClockSystem;
+pub use effects::EffectSystem;
+pub use dialogue::DialogueSystem;
+pub use score::ScoreSystem;
+pub use meta::MetaSystem;
 
+use crate::command::CommandSpec;
 use crate::game_state::{AdventureGame, GameEvent};
+use crate::output::{LineKind, Reply};
+
+/// A pluggable slice of game behaviour. Systems must be `Send` so a game can
+/// be handed between threads (see [`crate::SessionManager`]).
+pub trait System: Send {
+    /// Name the system is known by in `settings.systems` and the `enable` /
+    /// `disable` commands. Defaults to the type's name.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>().rsplit("::").next().unwrap_or("system")
+    }
 
-pub trait System {
     /// Handle a typed player command. Return `Some(output)` to claim the command;
     /// returning `None` passes the command on to the next system.
     fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String>;
 
+    /// What the game actually calls to handle a command. Override it to say
+    /// which output is an error, or to pass on lines already tagged, such as
+    /// a room from [`AdventureGame::look_lines`]; `on_command` can then
+    /// return this reply's text. The default wraps `on_command`'s output as
+    /// [`Reply::Text`].
+    fn on_command_reply(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<Reply> {
+        self.on_command(command, args, game).map(Reply::Text)
+    }
+
+    /// Called with each event given to [`AdventureGame::publish`] or pushed
+    /// during a command, before [`System::on_events`]. Changes made to the
+    /// event are seen by the systems after this one.
+    /// The default implementation leaves the event alone.
+    fn on_publish(&mut self, _event: &mut GameEvent, _game: &mut AdventureGame) {}
+
     /// Called after every command round when there are pending game events
     /// (monster kills, item pickups, room transitions, etc.).
     /// Return `Some(output)` to append an observer message (e.g. quest updates).
@@ -22,4 +59,42 @@ pub trait System {
     fn on_events(&mut self, _events: &[GameEvent], _game: &mut AdventureGame) -> Option<String> {
         None
     }
+
+    /// Called once after any command that advanced `turn_count`.
+    /// Return `Some(output)` to report per-turn effects (poison ticks, etc.).
+    /// The default implementation is a no-op.
+    fn on_turn(&mut self, _game: &mut AdventureGame) -> Option<String> {
+        None
+    }
+
+    /// Names of the systems this one needs to work. Switching it on with
+    /// [`AdventureGame::set_system_enabled`] switches these on first, and
+    /// switching one of them off switches this off too.
+    /// The default implementation needs none.
+    fn dependencies(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The commands this system handles, for `help` and tooling.
+    /// The default implementation declares none.
+    fn commands(&self) -> &'static [CommandSpec] {
+        &[]
+    }
+
+    /// How this system's output is tagged in a [`crate::StepResult`].
+    fn output_kind(&self) -> LineKind {
+        LineKind::System
+    }
+
+    /// State of the system's own to keep in save files, if any.
+    /// The default implementation keeps none.
+    fn save_data(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Restore state written by [`System::save_data`].
+    /// The default implementation ignores it.
+    fn load_data(&mut self, _data: &serde_json::Value) -> Result<(), String> {
+        Ok(())
+    }
 }
\ No newline at end of file
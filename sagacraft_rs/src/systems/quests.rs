@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::collections::HashSet;
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::event_log::LoggedEvent;
 use crate::systems::System;
-use crate::game_state::{AdventureGame, GameEvent};
+use crate::game_state::{split_target, AdventureGame, GameEvent};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QuestStatus {
@@ -10,6 +12,7 @@ pub enum QuestStatus {
     Active,
     Completed,
     Failed,
+    Abandoned,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -24,7 +27,7 @@ pub enum ObjectiveType {
     Puzzle,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum QuestDifficulty {
     Easy,
     Moderate,
@@ -136,6 +139,14 @@ pub struct Quest {
     pub acceptance_time: Option<String>,
     pub completion_time: Option<String>,
     pub current_stage_index: usize,
+    /// Quest ids that must be completed before this one can be accepted.
+    pub prerequisites: Vec<String>,
+    /// Turns allowed after acceptance before the quest fails.
+    pub time_limit: Option<i32>,
+    /// `turn_count` at the moment the quest was accepted.
+    pub accepted_turn: Option<i32>,
+    /// Once abandoned, the quest is never offered again.
+    pub one_time: bool,
 }
 
 impl Quest {
@@ -153,6 +164,21 @@ impl Quest {
             acceptance_time: None,
             completion_time: None,
             current_stage_index: 0,
+            prerequisites: Vec::new(),
+            time_limit: None,
+            accepted_turn: None,
+            one_time: false,
+        }
+    }
+
+    /// Put the quest back in its never-accepted state, with all progress cleared.
+    pub fn reset(&mut self) {
+        self.status = QuestStatus::Available;
+        self.acceptance_time = None;
+        self.accepted_turn = None;
+        self.current_stage_index = 0;
+        for objective in self.stages.iter_mut().flat_map(|s| s.objectives.iter_mut()) {
+            objective.current_count = 0;
         }
     }
 
@@ -166,6 +192,17 @@ impl Quest {
             && self.get_current_stage().is_some_and(|s: &QuestStage| s.is_complete())
     }
 
+    /// Overall completion across all stages: finished stages count fully,
+    /// the current stage contributes its own percentage.
+    pub fn get_progress_percentage(&self) -> i32 {
+        if self.stages.is_empty() {
+            return 0;
+        }
+        let done = self.current_stage_index.min(self.stages.len()) as i32 * 100;
+        let current = self.get_current_stage().map_or(0, |s| s.get_progress_percentage());
+        (done + current) / self.stages.len() as i32
+    }
+
     pub fn mark_complete(&mut self) {
         self.status = QuestStatus::Completed;
         self.completion_time = Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
@@ -177,6 +214,9 @@ pub struct QuestTracker {
     pub active_quests: HashMap<String, Quest>,
     pub completed_quests: HashSet<String>,
     pub failed_quests: HashSet<String>,
+    /// One-time quests the player gave up on; they are not offered again.
+    #[serde(default)]
+    pub abandoned_quests: HashSet<String>,
     pub quest_history: Vec<(String, QuestStatus, String)>,
 }
 
@@ -186,6 +226,7 @@ impl QuestTracker {
             active_quests: HashMap::new(),
             completed_quests: HashSet::new(),
             failed_quests: HashSet::new(),
+            abandoned_quests: HashSet::new(),
             quest_history: Vec::new(),
         }
     }
@@ -225,6 +266,39 @@ impl QuestTracker {
         }
     }
 
+    pub fn fail_quest(&mut self, quest_id: &str) -> Option<Quest> {
+        let mut quest = self.active_quests.remove(quest_id)?;
+        quest.status = QuestStatus::Failed;
+        self.failed_quests.insert(quest_id.to_string());
+        self.record_history(quest_id.to_string(), QuestStatus::Failed);
+        Some(quest)
+    }
+
+    /// Drop an active quest, resetting its progress. Returns the quest so it
+    /// can be offered again.
+    pub fn abandon_quest(&mut self, quest_id: &str) -> Option<Quest> {
+        let mut quest = self.active_quests.remove(quest_id)?;
+        quest.reset();
+        if quest.one_time {
+            self.abandoned_quests.insert(quest_id.to_string());
+        }
+        self.record_history(quest_id.to_string(), QuestStatus::Abandoned);
+        Some(quest)
+    }
+
+    /// Active quests in the order they were accepted, ties broken by id.
+    pub fn active_in_order(&self) -> Vec<&Quest> {
+        let mut quests: Vec<&Quest> = self.active_quests.values().collect();
+        quests.sort_by(|a, b| accepted_order(a, b));
+        quests
+    }
+
+    fn active_in_order_mut(&mut self) -> Vec<&mut Quest> {
+        let mut quests: Vec<&mut Quest> = self.active_quests.values_mut().collect();
+        quests.sort_by(|a, b| accepted_order(a, b));
+        quests
+    }
+
     pub fn get_active_count(&self) -> usize {
         self.active_quests.len()
     }
@@ -239,9 +313,25 @@ impl QuestTracker {
     }
 } // end impl QuestTracker (methods)
 
+/// Everything [`QuestSystem::export_state`] writes, in a stable order.
+#[derive(Debug, Serialize, Deserialize)]
+struct QuestState {
+    active: Vec<Quest>,
+    available: Vec<Quest>,
+    completed: Vec<String>,
+    failed: Vec<String>,
+    abandoned: Vec<String>,
+    history: Vec<(String, QuestStatus, String)>,
+    #[serde(default)]
+    offered: Vec<String>,
+}
+
 pub struct QuestSystem {
     pub tracker: QuestTracker,
     pub available_quests: HashMap<String, Quest>,
+    /// Quests whose `giver_npc` the player has talked to. Quests with a
+    /// giver can only be accepted once they are in here.
+    pub offered: HashSet<String>,
     loaded: bool,
 }
 
@@ -250,6 +340,7 @@ impl QuestSystem {
         Self {
             tracker: QuestTracker::new(),
             available_quests: HashMap::new(),
+            offered: HashSet::new(),
             loaded: false,
         }
     }
@@ -269,7 +360,17 @@ impl QuestSystem {
         self.loaded = true;
 
         for quest_data in &game.quests {
-            if let Ok(quest) = self.parse_quest_from_json(quest_data) {
+            if let Ok(mut quest) = self.parse_quest_from_json(quest_data) {
+                // A giver the player can never meet would lock the quest away
+                let giver_exists = game.monsters.values()
+                    .any(|m| target_matches(&quest.giver_npc, "monster", m.id, &m.name));
+                if !quest.giver_npc.is_empty() && !giver_exists {
+                    game.logger.info(&format!(
+                        "quest {}: giver '{}' is not in the adventure; it can be accepted without them",
+                        quest.quest_id, quest.giver_npc
+                    ));
+                    quest.giver_npc.clear();
+                }
                 self.available_quests.insert(quest.quest_id.clone(), quest);
             }
         }
@@ -280,18 +381,37 @@ impl QuestSystem {
         let title = data.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string();
         let description = data.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string();
         let giver_npc = data.get("giver_npc").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let difficulty = match data.get("difficulty").and_then(|v| v.as_str()) {
+            Some("easy") => QuestDifficulty::Easy,
+            Some("challenging") => QuestDifficulty::Challenging,
+            Some("hard") => QuestDifficulty::Hard,
+            _ => QuestDifficulty::Moderate,
+        };
+        let prerequisites = data.get("prerequisites").and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(json_id).collect())
+            .unwrap_or_default();
+        let time_limit = data.get("time_limit").and_then(|v| v.as_i64()).map(|v| v as i32);
+        let one_time = data.get("one_time").and_then(|v| v.as_bool()).unwrap_or(false);
 
         // Parse rewards: supports both {"rewards": {"gold": N, "xp": N}} and flat fields
-        let (reward_gold, reward_xp) = if let Some(rewards) = data.get("rewards") {
+        let (reward_gold, reward_xp, reward_items, reputation_changes) = if let Some(rewards) = data.get("rewards") {
             let gold = rewards.get("gold").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
             let xp = rewards.get("xp")
                 .or_else(|| rewards.get("experience_points"))
                 .and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            (gold, xp)
+            let items = rewards.get("items").and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(json_id).collect())
+                .unwrap_or_default();
+            let reputation = rewards.get("reputation").and_then(|v| v.as_object())
+                .map(|factions| factions.iter()
+                    .filter_map(|(faction, change)| Some((faction.clone(), change.as_i64()? as i32)))
+                    .collect())
+                .unwrap_or_default();
+            (gold, xp, items, reputation)
         } else {
             let gold = data.get("rewards_gold").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
             let xp = data.get("rewards_xp").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-            (gold, xp)
+            (gold, xp, Vec::new(), HashMap::new())
         };
 
         let mut stages = Vec::new();
@@ -310,13 +430,12 @@ impl QuestSystem {
                 };
 
                 // target_id can be a string name or an integer id
-                let target = obj.get("target_id")
-                    .and_then(|v| v.as_str().map(str::to_string)
-                        .or_else(|| v.as_i64().map(|n| n.to_string())))
-                    .unwrap_or_default();
+                let target = obj.get("target_id").and_then(json_id).unwrap_or_default();
 
                 let desc = obj.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                let required = obj.get("count").and_then(|v| v.as_i64()).unwrap_or(1) as i32;
+                let required = obj.get("required_count")
+                    .or_else(|| obj.get("count"))
+                    .and_then(|v| v.as_i64()).unwrap_or(1) as i32;
 
                 objectives.push(QuestObjective::new(
                     format!("obj_{}", objectives.len()),
@@ -344,17 +463,23 @@ impl QuestSystem {
             description,
             giver_npc,
             quest_giver_level: 1,
-            difficulty: QuestDifficulty::Moderate,
+            difficulty,
             stages,
             rewards: QuestReward {
                 experience_points: reward_xp,
                 gold: reward_gold,
+                items: reward_items,
+                reputation_changes,
                 ..QuestReward::default()
             },
             status: QuestStatus::Available,
             acceptance_time: None,
             completion_time: None,
             current_stage_index: 0,
+            prerequisites,
+            time_limit,
+            accepted_turn: None,
+            one_time,
         })
     }
 
@@ -362,11 +487,40 @@ impl QuestSystem {
         self.available_quests.insert(quest.quest_id.clone(), quest);
     }
 
+    /// Quests not yet accepted, easiest first, ties broken by id.
     pub fn get_available_quests(&self) -> Vec<&Quest> {
-        self.available_quests.values().collect()
+        let mut quests: Vec<&Quest> = self.available_quests.values().collect();
+        quests.sort_by_key(|q| (q.difficulty.clone(), id_key(&q.quest_id)));
+        quests
+    }
+
+    /// Whether `quest` can be accepted now: it has no giver, or the player
+    /// has talked to its giver.
+    pub fn is_offered(&self, quest: &Quest) -> bool {
+        quest.giver_npc.is_empty() || self.offered.contains(&quest.quest_id)
+    }
+
+    /// The player talked to a monster: every available quest it gives is
+    /// now offered. Returns the newly offered quests' ids and titles.
+    fn meet(&mut self, monster_id: i32, monster_name: &str) -> Vec<(String, String)> {
+        let newly: Vec<(String, String)> = self.get_available_quests().into_iter()
+            .filter(|q| !q.giver_npc.is_empty() && !self.offered.contains(&q.quest_id))
+            .filter(|q| target_matches(&q.giver_npc, "monster", monster_id, monster_name))
+            .map(|q| (q.quest_id.clone(), q.title.clone()))
+            .collect();
+        self.offered.extend(newly.iter().map(|(id, _)| id.clone()));
+        newly
     }
 
     pub fn accept_quest(&mut self, quest_id: &str) -> Result<String, String> {
+        if let Some(quest) = self.available_quests.get(quest_id).filter(|q| !self.is_offered(q)) {
+            return Err(format!("No one has offered you that quest yet; try talking to {}", quest.giver_npc));
+        }
+        if let Some(missing) = self.available_quests.get(quest_id)
+            .and_then(|q| q.prerequisites.iter().find(|p| !self.tracker.completed_quests.contains(*p)))
+        {
+            return Err(format!("You must complete quest {} first", missing));
+        }
         if let Some(quest) = self.available_quests.remove(quest_id) {
             let title = quest.title.clone();
             if self.tracker.accept_quest(quest) {
@@ -379,44 +533,193 @@ impl QuestSystem {
         }
     }
 
+    /// Give up an active quest. It goes back to the available list with no
+    /// progress, unless it is a one-time quest.
+    pub fn abandon_quest(&mut self, quest_id: &str) -> Result<String, String> {
+        let quest = self.tracker.abandon_quest(quest_id).ok_or("Quest not found or not active")?;
+        let msg = format!("Abandoned quest: {}", quest.title);
+        if quest.one_time {
+            Ok(format!("{}. It will not be offered again.", msg))
+        } else {
+            self.available_quests.insert(quest.quest_id.clone(), quest);
+            Ok(msg)
+        }
+    }
+
+    /// Quest progress as JSON, for save files and outside tools: active and
+    /// available quests with their objective counts and timestamps, the ids
+    /// of completed, failed and abandoned quests, and the quest history.
+    pub fn export_state(&self) -> serde_json::Value {
+        let sorted = |ids: &HashSet<String>| {
+            let mut ids: Vec<String> = ids.iter().cloned().collect();
+            ids.sort_by(|a, b| id_key(a).cmp(&id_key(b)));
+            ids
+        };
+        let state = QuestState {
+            active: self.tracker.active_in_order().into_iter().cloned().collect(),
+            available: self.get_available_quests().into_iter().cloned().collect(),
+            completed: sorted(&self.tracker.completed_quests),
+            failed: sorted(&self.tracker.failed_quests),
+            abandoned: sorted(&self.tracker.abandoned_quests),
+            history: self.tracker.quest_history.clone(),
+            offered: sorted(&self.offered),
+        };
+        serde_json::to_value(state).expect("quest state serializes to JSON")
+    }
+
+    /// Replace all quest progress with what [`QuestSystem::export_state`]
+    /// wrote. The adventure's quest list is not read again afterwards.
+    pub fn import_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        let state = QuestState::deserialize(state)?;
+        self.tracker = QuestTracker {
+            active_quests: state.active.into_iter().map(|q| (q.quest_id.clone(), q)).collect(),
+            completed_quests: state.completed.into_iter().collect(),
+            failed_quests: state.failed.into_iter().collect(),
+            abandoned_quests: state.abandoned.into_iter().collect(),
+            quest_history: state.history,
+        };
+        self.available_quests = state.available.into_iter().map(|q| (q.quest_id.clone(), q)).collect();
+        self.offered = state.offered.into_iter().collect();
+        self.loaded = true;
+        Ok(())
+    }
+
     pub fn show_quests(&self) -> String {
         let mut result = String::new();
         result.push_str("Active Quests:\n");
-        for quest in self.tracker.active_quests.values() {
+        for quest in self.tracker.active_in_order() {
             result.push_str(&format!("- {}: {}\n", quest.title, quest.description));
+            result.push_str(&format!("  Progress: {}\n", progress_bar(quest.get_progress_percentage())));
             if let Some(stage) = quest.get_current_stage() {
                 result.push_str(&format!("  Current Stage: {}\n", stage.title));
                 for obj in &stage.objectives {
-                    result.push_str(&format!("    - {} ({}/{})\n",
-                        obj.description, obj.current_count, obj.required_count));
+                    result.push_str(&format!("    - {} ({}/{}) {}%\n",
+                        obj.description, obj.current_count, obj.required_count,
+                        obj.get_progress_percentage()));
                 }
             }
         }
+        let (offered, unoffered): (Vec<&Quest>, Vec<&Quest>) = self.get_available_quests().into_iter()
+            .partition(|q| !q.giver_npc.is_empty() && self.is_offered(q));
+        if !offered.is_empty() {
+            result.push_str("\nOffered by NPCs You've Met:\n");
+            for quest in offered {
+                result.push_str(&format!("- {}: {} (from {})\n", quest.title, quest.description, quest.giver_npc));
+            }
+        }
         result.push_str("\nAvailable Quests:\n");
-        for quest in self.available_quests.values() {
-            result.push_str(&format!("- {}: {}\n", quest.title, quest.description));
+        for quest in unoffered {
+            if quest.giver_npc.is_empty() {
+                result.push_str(&format!("- {}: {}\n", quest.title, quest.description));
+            } else {
+                result.push_str(&format!("- {}: {} (ask {})\n", quest.title, quest.description, quest.giver_npc));
+            }
         }
         result
     }
 }
 
+/// Sort key for quest ids: numeric ids in numeric order, then any others by name.
+fn id_key(id: &str) -> (i64, &str) {
+    (id.parse().unwrap_or(i64::MAX), id)
+}
+
+/// Order quests by the turn they were accepted, then by id.
+fn accepted_order(a: &Quest, b: &Quest) -> std::cmp::Ordering {
+    (a.accepted_turn, id_key(&a.quest_id)).cmp(&(b.accepted_turn, id_key(&b.quest_id)))
+}
+
+/// Read an id that may be authored as either a JSON string or an integer.
+fn json_id(value: &serde_json::Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+        .or_else(|| value.as_i64().map(|n| n.to_string()))
+}
+
+/// Whether an objective target (a name fragment, a bare id, or a namespaced
+/// id like `monster:3`) refers to the given entity.
+fn target_matches(target: &str, kind: &str, id: i32, name: &str) -> bool {
+    if let Some((target_kind, target_id)) = split_target(target) {
+        return target_kind == kind && target_id == id;
+    }
+    match target.parse::<i32>() {
+        Ok(target_id) => target_id == id,
+        Err(_) => !name.is_empty() && name.to_lowercase().contains(&target.to_lowercase()),
+    }
+}
+
+/// Width (in characters) of the text progress bar shown in the quest log.
+const PROGRESS_BAR_WIDTH: i32 = 10;
+
+/// Render a percentage as a plain-text bar, e.g. `[#####-----] 50%`.
+fn progress_bar(percent: i32) -> String {
+    let percent = percent.clamp(0, 100);
+    let filled = (percent * PROGRESS_BAR_WIDTH / 100) as usize;
+    format!(
+        "[{}{}] {}%",
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH as usize - filled),
+        percent
+    )
+}
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("quests", &["journal"], ArgShape::None, "Show quest journal"),
+    CommandSpec::new("accept", &[], ArgShape::Target("quest_id"), "Accept a quest"),
+    CommandSpec::new("complete", &["finish"], ArgShape::Target("quest_id"), "Complete a quest"),
+    CommandSpec::new("abandon", &[], ArgShape::Target("quest_id"), "Give up an active quest"),
+];
+
 impl System for QuestSystem {
+    fn name(&self) -> &'static str {
+        "quests"
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        // Quests with a giver are accepted by talking to them first
+        &["dialogue"]
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
+    fn save_data(&self) -> Option<serde_json::Value> {
+        // Nothing to save until the adventure's quests have been read
+        self.loaded.then(|| self.export_state())
+    }
+
+    fn load_data(&mut self, data: &serde_json::Value) -> Result<(), String> {
+        self.import_state(data).map_err(|err| err.to_string())
+    }
+
     fn on_command(&mut self, command: &str, args: &[&str], game: &mut AdventureGame) -> Option<String> {
         self.load_quests_from_game(game);
 
-        match command {
-            "quests" | "journal" => Some(self.show_quests()),
+        match command::resolve(COMMANDS, command)? {
+            "quests" => Some(self.show_quests()),
             "accept" => {
                 if args.is_empty() {
                     Some("Usage: accept <quest_id>. Use 'quests' to see available quests.".to_string())
                 } else {
                     match self.accept_quest(args[0]) {
-                        Ok(msg) => Some(msg),
+                        Ok(msg) => {
+                            if let Some(quest) = self.tracker.active_quests.get_mut(args[0]) {
+                                quest.accepted_turn = Some(game.turn_count);
+                            }
+                            Some(msg)
+                        }
                         Err(err) => Some(format!("Error: {}", err)),
                     }
                 }
             }
-            "complete" | "finish" => {
+            "abandon" => {
+                if args.is_empty() {
+                    Some("Usage: abandon <quest_id>. Use 'quests' to see active quests.".to_string())
+                } else {
+                    Some(self.abandon_quest(args[0]).unwrap_or_else(|err| format!("Error: {}", err)))
+                }
+            }
+            "complete" => {
                 if args.is_empty() {
                     Some("Usage: complete <quest_id>. Use 'quests' to see active quests.".to_string())
                 } else {
@@ -424,6 +727,7 @@ impl System for QuestSystem {
                         Some(reward) => {
                             game.player.gold += reward.gold;
                             game.player.experience_points += reward.experience_points;
+                            game.player.quests_completed += 1;
                             let mut msg = format!("Completed quest: {}", args[0]);
                             if reward.gold > 0 {
                                 msg.push_str(&format!(" (+{} gold)", reward.gold));
@@ -431,6 +735,30 @@ impl System for QuestSystem {
                             if reward.experience_points > 0 {
                                 msg.push_str(&format!(" (+{} XP)", reward.experience_points));
                             }
+                            let mut standings: Vec<(&String, &i32)> = reward.reputation_changes.iter().collect();
+                            standings.sort();
+                            for (faction, change) in standings {
+                                game.player.adjust_standing(faction, *change);
+                                msg.push_str(&format!(" ({:+} standing with {})", change, faction));
+                            }
+                            for item_id in reward.items.iter().filter_map(|id| id.parse::<i32>().ok()) {
+                                if let Some(item) = game.items.get_mut(&item_id) {
+                                    item.location = 0;
+                                    game.player.inventory.push(item_id);
+                                    msg.push_str(&format!("\nYou receive: {}.", item.name));
+                                }
+                            }
+                            let checkpoint = game.quests.iter().any(|q| {
+                                q.get("id").and_then(json_id).as_deref() == Some(args[0])
+                                    && q.get("auto_save_on_complete").and_then(|v| v.as_bool()).unwrap_or(false)
+                            });
+                            if checkpoint {
+                                match game.autosave() {
+                                    Ok(true) => msg.push_str("\n[Checkpoint saved.]"),
+                                    Ok(false) => {}
+                                    Err(err) => msg.push_str(&format!("\n[Autosave failed: {}]", err)),
+                                }
+                            }
                             Some(msg)
                         }
                         None => Some(format!("Quest '{}' not found or not active.", args[0])),
@@ -441,22 +769,42 @@ impl System for QuestSystem {
         }
     }
 
-    fn on_events(&mut self, events: &[GameEvent], _game: &mut AdventureGame) -> Option<String> {
+    fn on_turn(&mut self, game: &mut AdventureGame) -> Option<String> {
+        let expired: Vec<String> = self.tracker.active_in_order().into_iter()
+            .filter(|q| match (q.time_limit, q.accepted_turn) {
+                (Some(limit), Some(start)) => game.turn_count - start > limit,
+                _ => false,
+            })
+            .map(|q| q.quest_id.clone())
+            .collect();
+        let failed: Vec<String> = expired.iter()
+            .filter_map(|id| self.tracker.fail_quest(id))
+            .map(|q| format!("[Quest failed: {}] You ran out of time.", q.title))
+            .collect();
+        if failed.is_empty() { None } else { Some(failed.join("\n")) }
+    }
+
+    fn on_events(&mut self, events: &[GameEvent], game: &mut AdventureGame) -> Option<String> {
         let mut notifications: Vec<String> = Vec::new();
 
         for event in events {
             match event {
-                GameEvent::MonsterKilled { monster_name, .. } => {
-                    for quest in self.tracker.active_quests.values_mut() {
+                GameEvent::MonsterKilled { monster_name, monster_id, .. } => {
+                    for quest in self.tracker.active_in_order_mut() {
                         if let Some(stage) = quest.stages.get_mut(quest.current_stage_index) {
                             for obj in &mut stage.objectives {
                                 if obj.obj_type == ObjectiveType::Kill
                                     && !obj.target.is_empty()
-                                    && monster_name.to_lowercase().contains(&obj.target.to_lowercase())
+                                    && target_matches(&obj.target, "monster", *monster_id, monster_name)
                                     && !obj.is_complete()
                                 {
                                     let gained = obj.progress(1);
                                     if gained > 0 {
+                                        game.log_event(LoggedEvent::QuestProgressed {
+                                            quest_id: quest.quest_id.clone(),
+                                            current: obj.current_count,
+                                            required: obj.required_count,
+                                        });
                                         notifications.push(format!(
                                             "[Quest: {}] {} ({}/{})",
                                             quest.title, obj.description,
@@ -468,17 +816,22 @@ impl System for QuestSystem {
                         }
                     }
                 }
-                GameEvent::ItemCollected { item_name, .. } => {
-                    for quest in self.tracker.active_quests.values_mut() {
+                GameEvent::ItemCollected { item_name, item_id } => {
+                    for quest in self.tracker.active_in_order_mut() {
                         if let Some(stage) = quest.stages.get_mut(quest.current_stage_index) {
                             for obj in &mut stage.objectives {
                                 if obj.obj_type == ObjectiveType::Collect
                                     && !obj.target.is_empty()
-                                    && item_name.to_lowercase().contains(&obj.target.to_lowercase())
+                                    && target_matches(&obj.target, "item", *item_id, item_name)
                                     && !obj.is_complete()
                                 {
                                     let gained = obj.progress(1);
                                     if gained > 0 {
+                                        game.log_event(LoggedEvent::QuestProgressed {
+                                            quest_id: quest.quest_id.clone(),
+                                            current: obj.current_count,
+                                            required: obj.required_count,
+                                        });
                                         notifications.push(format!(
                                             "[Quest: {}] {} ({}/{})",
                                             quest.title, obj.description,
@@ -490,15 +843,45 @@ impl System for QuestSystem {
                         }
                     }
                 }
+                GameEvent::TalkedTo { monster_id, monster_name } => {
+                    for (quest_id, title) in self.meet(*monster_id, monster_name) {
+                        notifications.push(format!(
+                            "The {} has work for you: {} (accept {})", monster_name, title, quest_id
+                        ));
+                    }
+                    for quest in self.tracker.active_in_order_mut() {
+                        if let Some(stage) = quest.stages.get_mut(quest.current_stage_index) {
+                            for obj in &mut stage.objectives {
+                                if obj.obj_type == ObjectiveType::Talk
+                                    && target_matches(&obj.target, "monster", *monster_id, monster_name)
+                                    && !obj.is_complete()
+                                {
+                                    obj.progress(1);
+                                    game.log_event(LoggedEvent::QuestProgressed {
+                                        quest_id: quest.quest_id.clone(),
+                                        current: obj.current_count,
+                                        required: obj.required_count,
+                                    });
+                                    notifications.push(format!("[Quest: {}] {}", quest.title, obj.description));
+                                }
+                            }
+                        }
+                    }
+                }
                 GameEvent::RoomEntered { room_id } => {
-                    for quest in self.tracker.active_quests.values_mut() {
+                    for quest in self.tracker.active_in_order_mut() {
                         if let Some(stage) = quest.stages.get_mut(quest.current_stage_index) {
                             for obj in &mut stage.objectives {
                                 if obj.obj_type == ObjectiveType::Explore
-                                    && obj.target == room_id.to_string()
+                                    && target_matches(&obj.target, "room", *room_id, "")
                                     && !obj.is_complete()
                                 {
                                     obj.progress(1);
+                                    game.log_event(LoggedEvent::QuestProgressed {
+                                        quest_id: quest.quest_id.clone(),
+                                        current: obj.current_count,
+                                        required: obj.required_count,
+                                    });
                                     notifications.push(format!(
                                         "[Quest: {}] {}",
                                         quest.title, obj.description
@@ -518,4 +901,181 @@ impl System for QuestSystem {
             Some(format!("Quest update:\n{}", notifications.join("\n")))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_done_objective_renders_percentage() {
+        let mut quest = Quest::new("1".to_string(), "Rat Hunt".to_string(), "Clear the cellar".to_string(), String::new());
+        let mut stage = QuestStage::new("main".to_string(), 1, "Main Objectives".to_string(), String::new());
+        stage.add_objective(QuestObjective::new("obj_0".to_string(), ObjectiveType::Kill, "Kill rats".to_string(), "rat".to_string(), 2));
+        quest.stages.push(stage);
+
+        let mut system = QuestSystem::new();
+        system.add_available_quest(quest);
+        system.accept_quest("1").unwrap();
+        system.tracker.active_quests.get_mut("1").unwrap().stages[0].objectives[0].progress(1);
+
+        let log = system.show_quests();
+        assert!(log.contains("Kill rats (1/2) 50%"));
+        assert!(log.contains("[#####-----] 50%"));
+    }
+
+    #[test]
+    fn abandoning_returns_the_quest_with_progress_cleared() {
+        let mut quest = Quest::new("1".to_string(), "Rat Hunt".to_string(), String::new(), String::new());
+        let mut stage = QuestStage::new("main".to_string(), 1, "Main Objectives".to_string(), String::new());
+        stage.add_objective(QuestObjective::new("obj_0".to_string(), ObjectiveType::Kill, "Kill rats".to_string(), "rat".to_string(), 2));
+        quest.stages.push(stage);
+        let mut once = quest.clone();
+        once.quest_id = "2".to_string();
+        once.one_time = true;
+
+        let mut system = QuestSystem::new();
+        system.add_available_quest(quest);
+        system.add_available_quest(once);
+        system.accept_quest("1").unwrap();
+        system.tracker.active_quests.get_mut("1").unwrap().stages[0].objectives[0].progress(1);
+
+        assert_eq!(system.abandon_quest("1").unwrap(), "Abandoned quest: Rat Hunt");
+        assert_eq!(system.tracker.get_active_count(), 0);
+        let returned = &system.available_quests["1"];
+        assert_eq!(returned.status, QuestStatus::Available);
+        assert_eq!(returned.stages[0].objectives[0].current_count, 0);
+        assert_eq!(system.tracker.quest_history.last().unwrap().1, QuestStatus::Abandoned);
+
+        system.accept_quest("2").unwrap();
+        assert!(system.abandon_quest("2").unwrap().ends_with("It will not be offered again."));
+        assert!(!system.available_quests.contains_key("2"));
+        assert!(system.abandon_quest("2").is_err());
+    }
+
+    #[test]
+    fn reputation_reward_restores_a_merchants_willingness_to_trade() {
+        use crate::game_state::{Monster, MonsterStatus};
+
+        let mut game = AdventureGame::new(String::new());
+        let mut trader = Monster::new(1, "Trader".to_string(), String::new(), 1, 10, 5, MonsterStatus::Friendly, 100);
+        trader.faction = Some("guild".to_string());
+        game.monsters.insert(1, trader);
+        game.quests.push(serde_json::json!({
+            "id": 3, "title": "Lost Ledger",
+            "rewards": { "reputation": { "guild": 20 } }
+        }));
+        game.player.adjust_standing("guild", -15);
+        game.add_system(Box::new(QuestSystem::new()));
+        assert!(!game.will_trade_with(1));
+
+        game.process_command("accept 3");
+        let out = game.process_command("complete 3").join("\n");
+        assert!(out.contains("(+20 standing with guild)"));
+        assert_eq!(game.player.standing("guild"), 5);
+        assert!(game.will_trade_with(1));
+    }
+
+    #[test]
+    fn exported_progress_imports_into_a_fresh_game() {
+        let quest_data = serde_json::json!({
+            "id": 1, "title": "Rat Hunt",
+            "objectives": [{"type": "kill_monster", "target_id": "rat", "required_count": 3}]
+        });
+        let mut game = AdventureGame::new(String::new());
+        game.quests.push(quest_data.clone());
+        game.add_system(Box::new(QuestSystem::new()));
+        game.process_command("accept 1");
+
+        let mut system = QuestSystem::new();
+        system.load_quests_from_game(&game);
+        system.accept_quest("1").unwrap();
+        system.tracker.active_quests.get_mut("1").unwrap().stages[0].objectives[0].progress(2);
+        let exported = system.export_state();
+        assert_eq!(exported["active"][0]["stages"][0]["objectives"][0]["current_count"], 2);
+
+        let mut restored = QuestSystem::new();
+        restored.import_state(&exported).unwrap();
+        let quest = &restored.tracker.active_quests["1"];
+        assert_eq!(quest.status, QuestStatus::Active);
+        assert_eq!(quest.stages[0].objectives[0].current_count, 2);
+        assert_eq!(quest.acceptance_time, system.tracker.active_quests["1"].acceptance_time);
+        assert!(restored.available_quests.is_empty());
+
+        // Through the game's save state
+        let saved = crate::save::SaveState::capture(&game);
+        let mut fresh = AdventureGame::new(String::new());
+        fresh.quests.push(quest_data);
+        fresh.add_system(Box::new(QuestSystem::new()));
+        fresh.restore_system_data(&saved.systems).unwrap();
+        assert!(fresh.process_command("quests").join("\n").contains("- Rat Hunt: \n  Progress: [----------] 0%"));
+        assert_eq!(fresh.process_command("accept 1"), ["Error: Quest not found"]);
+    }
+
+    #[test]
+    fn quest_is_only_accepted_after_talking_to_its_giver() {
+        use crate::game_state::{Monster, MonsterStatus, Room};
+        use crate::systems::DialogueSystem;
+
+        let mut game = AdventureGame::new(String::new());
+        game.rooms.insert(1, Room::new(1, "Barracks".to_string(), String::new()));
+        game.player.current_room = 1;
+        game.monsters.insert(1, Monster::new(1, "Captain".to_string(), String::new(), 1, 10, 5, MonsterStatus::Friendly, 100));
+        game.quests.push(serde_json::json!({"id": 7, "title": "Goblin Cull", "giver_npc": "Captain"}));
+        game.quests.push(serde_json::json!({"id": 8, "title": "Odd Jobs"}));
+        game.add_system(Box::new(DialogueSystem));
+        game.add_system(Box::new(QuestSystem::new()));
+
+        let journal = game.process_command("quests").join("\n");
+        assert!(journal.contains("- Goblin Cull:  (ask Captain)"), "{journal}");
+        assert!(!journal.contains("Offered by"));
+        assert_eq!(game.process_command("accept 7"),
+                   ["Error: No one has offered you that quest yet; try talking to Captain"]);
+
+        let out = game.process_command("talk captain").join("\n");
+        assert!(out.contains("The Captain has work for you: Goblin Cull (accept 7)"), "{out}");
+        let journal = game.process_command("quests").join("\n");
+        assert!(journal.contains("Offered by NPCs You've Met:\n- Goblin Cull:  (from Captain)"), "{journal}");
+        assert!(journal.contains("Available Quests:\n- Odd Jobs: \n"), "{journal}");
+        assert_eq!(game.process_command("accept 7"), ["Accepted quest: Goblin Cull"]);
+    }
+
+    #[test]
+    fn parser_keeps_required_count_and_rewards() {
+        let data = serde_json::json!({
+            "id": 7,
+            "title": "Goblin Cull",
+            "giver_npc": "Captain",
+            "difficulty": "hard",
+            "objectives": [
+                { "type": "kill_monster", "target_id": "Goblin", "required_count": 3, "description": "Slay goblins" }
+            ],
+            "rewards": { "gold": 50, "xp": 20, "items": [4] }
+        });
+        let quest = QuestSystem::new().parse_quest_from_json(&data).unwrap();
+
+        let objective = &quest.stages[0].objectives[0];
+        assert_eq!(objective.required_count, 3);
+        assert_eq!(objective.target, "Goblin");
+        assert_eq!(quest.rewards.gold, 50);
+        assert_eq!(quest.rewards.items, ["4"]);
+        assert_eq!(quest.giver_npc, "Captain");
+        assert_eq!(quest.difficulty, QuestDifficulty::Hard);
+    }
+
+    #[test]
+    fn quest_log_is_stable_and_sorted() {
+        let mut system = QuestSystem::new();
+        for (id, title, difficulty) in [("3", "Zeta", QuestDifficulty::Easy), ("10", "Alpha", QuestDifficulty::Hard),
+                                        ("2", "Beta", QuestDifficulty::Easy), ("5", "Gamma", QuestDifficulty::Moderate)] {
+            let mut quest = Quest::new(id.to_string(), title.to_string(), String::new(), String::new());
+            quest.difficulty = difficulty;
+            system.add_available_quest(quest);
+        }
+
+        let log = system.show_quests();
+        assert_eq!(log, system.show_quests());
+        let order: Vec<&str> = system.get_available_quests().iter().map(|q| q.title.as_str()).collect();
+        assert_eq!(order, ["Beta", "Zeta", "Gamma", "Alpha"]);
+    }
+}
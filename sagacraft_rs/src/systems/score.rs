@@ -0,0 +1,79 @@
+use crate::command::{self, ArgShape, CommandSpec};
+use crate::game_state::AdventureGame;
+use crate::systems::System;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("score", &["points"], ArgShape::None, "Show your score"),
+];
+
+/// Answers `score` with the player's score out of the adventure's best,
+/// and what it is made of.
+#[derive(Debug, Default)]
+pub struct ScoreSystem;
+
+impl ScoreSystem {
+    fn report(game: &AdventureGame) -> String {
+        let treasure = match game.settings.score.trophy_room.and_then(|id| game.rooms.get(&id)) {
+            Some(room) => format!("Treasure left in the {}: {}", room.name, game.treasure_score()),
+            None => format!("Treasure carried: {}", game.treasure_score()),
+        };
+        let mut lines = vec![
+            format!("Score: {} of {}", game.compute_score(), game.max_score()),
+            format!("  Gold: {} ({} banked)", game.player.gold, game.player.banked_gold),
+            format!("  {}", treasure),
+            format!("  Quests completed: {} of {}", game.player.quests_completed, game.quests.len()),
+        ];
+        if game.player.deaths > 0 {
+            lines.push(format!("  Deaths: {}", game.player.deaths));
+        }
+        lines.join("\n")
+    }
+}
+
+impl System for ScoreSystem {
+    fn name(&self) -> &'static str {
+        "score"
+    }
+
+    fn commands(&self) -> &'static [CommandSpec] {
+        COMMANDS
+    }
+
+    fn on_command(&mut self, command: &str, _args: &[&str], game: &mut AdventureGame) -> Option<String> {
+        match command::resolve(COMMANDS, command)? {
+            "score" => Some(Self::report(game)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Engine;
+
+    #[test]
+    fn treasure_scores_once_left_in_the_trophy_room() {
+        let mut engine = Engine::new("");
+        engine.game.load_adventure_str(r#"{
+            "settings": {"score": {"trophy_room": 1}},
+            "rooms": [
+                {"id": 1, "name": "Hall", "exits": {"north": 2}},
+                {"id": 2, "name": "Vault", "exits": {"south": 1}}
+            ],
+            "items": [
+                {"id": 1, "name": "Crown", "type": "treasure", "value": 100, "location": 2, "is_takeable": true},
+                {"id": 2, "name": "Goblet", "type": "treasure", "value": 50, "location": 2, "is_takeable": true}
+            ],
+            "quests": [{"id": 1, "title": "Royal Errand"}]
+        }"#).unwrap();
+        assert_eq!(engine.game.max_score(), 200);
+
+        engine.send("north");
+        engine.send("take crown");
+        assert_eq!(engine.game.compute_score(), 0);
+        engine.send("south");
+        engine.send("drop crown");
+        assert_eq!(engine.game.compute_score(), 100);
+        assert_eq!(engine.send("score")[0], "Score: 100 of 200");
+    }
+}
@@ -0,0 +1,40 @@
+//! A plain-text record of a play session, written as it happens so a crash
+//! still leaves everything up to the last command on disk.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// An open transcript file. Each command is written with the turn it was
+/// typed on, the prompt and the output it produced, then flushed.
+#[derive(Debug)]
+pub struct Transcript {
+    file: File,
+}
+
+impl Transcript {
+    /// Open `path` for appending, creating it if needed, and write a header
+    /// naming the adventure.
+    pub fn open(path: impl AsRef<Path>, adventure_title: &str) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut transcript = Self { file };
+        let started = chrono::Local::now().format("%Y-%m-%d %H:%M");
+        transcript.write_lines(&[format!("=== {} — {} ===", adventure_title, started)])?;
+        Ok(transcript)
+    }
+
+    /// Write output that isn't the answer to a command, such as the
+    /// opening room description.
+    pub fn write_lines(&mut self, lines: &[String]) -> io::Result<()> {
+        for line in lines {
+            writeln!(self.file, "{}", line)?;
+        }
+        self.file.flush()
+    }
+
+    /// Write `command`, typed at `prompt` on `turn`, and the lines it produced.
+    pub fn record(&mut self, turn: i32, prompt: &str, command: &str, output: &[String]) -> io::Result<()> {
+        writeln!(self.file, "[{}] {}{}", turn, prompt, command)?;
+        self.write_lines(output)
+    }
+}
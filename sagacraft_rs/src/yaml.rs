@@ -0,0 +1,512 @@
+//! Just enough YAML for hand-edited adventures: block mappings and
+//! sequences, plain, quoted and block (`|`, `>`) scalars, flow collections
+//! on a single line, and `#` comments. Anchors, tags and multi-document
+//! streams are not supported. Documents go through [`serde_json::Value`],
+//! so anything that (de)serializes as JSON can be read and written as YAML.
+
+use serde_json::{Map, Number, Value};
+
+/// File extensions read and written as YAML.
+pub const YAML_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// Why a document couldn't be read, and the (1-based) line it happened on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for YamlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// Write `value` as a block-style YAML document.
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(map, 0, false, &mut out),
+        Value::Array(items) if !items.is_empty() => write_sequence(items, 0, &mut out),
+        _ => {
+            write_after(value, 0, &mut out);
+            out.remove(0);
+        }
+    }
+    out
+}
+
+/// Read a YAML document.
+pub fn from_str(text: &str) -> Result<Value, YamlError> {
+    let mut parser = Parser {
+        lines: text.lines().map(str::to_string).collect(),
+        pos: 0,
+    };
+    parser.skip_blank();
+    if parser.lines.get(parser.pos).is_some_and(|line| line.trim_end() == "---") {
+        parser.pos += 1;
+    }
+    let value = parser.parse_node(0)?;
+    parser.skip_blank();
+    if parser.pos < parser.lines.len() {
+        return Err(parser.error("unexpected content after the document"));
+    }
+    Ok(value)
+}
+
+fn write_mapping(map: &Map<String, Value>, indent: usize, first_inline: bool, out: &mut String) {
+    for (i, (key, value)) in map.iter().enumerate() {
+        if i > 0 || !first_inline {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str(&scalar_string(key));
+        out.push(':');
+        write_after(value, indent, out);
+    }
+}
+
+fn write_sequence(items: &[Value], indent: usize, out: &mut String) {
+    for item in items {
+        out.push_str(&" ".repeat(indent));
+        out.push('-');
+        match item {
+            Value::Object(map) if !map.is_empty() => {
+                out.push(' ');
+                write_mapping(map, indent + 2, true, out);
+            }
+            _ => write_after(item, indent, out),
+        }
+    }
+}
+
+/// Write `value` after a `key:` or `-` at column `indent`.
+fn write_after(value: &Value, indent: usize, out: &mut String) {
+    match value {
+        Value::Object(map) if map.is_empty() => out.push_str(" {}\n"),
+        Value::Object(map) => {
+            out.push('\n');
+            write_mapping(map, indent + 2, false, out);
+        }
+        Value::Array(items) if items.is_empty() => out.push_str(" []\n"),
+        Value::Array(items) => {
+            out.push('\n');
+            write_sequence(items, indent + 2, out);
+        }
+        Value::String(s) if is_block_literal(s) => {
+            out.push_str(if s.ends_with('\n') { " |\n" } else { " |-\n" });
+            for line in s.strip_suffix('\n').unwrap_or(s).split('\n') {
+                if !line.is_empty() {
+                    out.push_str(&" ".repeat(indent + 2));
+                    out.push_str(line);
+                }
+                out.push('\n');
+            }
+        }
+        Value::String(s) => {
+            out.push(' ');
+            out.push_str(&scalar_string(s));
+            out.push('\n');
+        }
+        Value::Number(n) => out.push_str(&format!(" {n}\n")),
+        Value::Bool(b) => out.push_str(&format!(" {b}\n")),
+        Value::Null => out.push_str(" null\n"),
+    }
+}
+
+/// Multi-line text that reads back unchanged as a `|` block.
+fn is_block_literal(s: &str) -> bool {
+    s.contains('\n')
+        && !s.starts_with([' ', '\n'])
+        && !s.ends_with("\n\n")
+        && !s.chars().any(|c| c.is_control() && c != '\n')
+        && s.lines().all(|line| line.trim_end() == line)
+}
+
+/// `s` as a plain scalar if it would read back as the same string, else double-quoted.
+fn scalar_string(s: &str) -> String {
+    let ambiguous = s.is_empty()
+        || s.trim() != s
+        || s.starts_with(['-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`'])
+        || s.ends_with(':')
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.chars().any(char::is_control)
+        || !matches!(plain_scalar(s), Value::String(_))
+        || ["yes", "no", "on", "off"].iter().any(|word| s.eq_ignore_ascii_case(word));
+    if ambiguous {
+        serde_json::to_string(s).expect("strings always serialize")
+    } else {
+        s.to_string()
+    }
+}
+
+/// The value of an unquoted scalar.
+fn plain_scalar(s: &str) -> Value {
+    match s {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(n) = s.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    if s.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        && let Some(n) = s.parse::<f64>().ok().and_then(Number::from_f64)
+    {
+        return Value::Number(n);
+    }
+    Value::String(s.to_string())
+}
+
+/// Whether a quote after `previous` opens a quoted scalar rather than
+/// being part of a plain one ("It's").
+fn opens_quote(previous: char) -> bool {
+    previous.is_whitespace() || matches!(previous, ':' | ',' | '[' | '{' | '-')
+}
+
+/// `line` with any trailing `# comment` removed, ignoring `#` inside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (at, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if opens_quote(previous) => quote = Some(c),
+            (Some('"'), '\\') if previous != '\\' => {}
+            (Some(q), _) if c == q && !(q == '"' && previous == '\\') => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..at],
+            _ => {}
+        }
+        previous = if previous == '\\' && c == '\\' { ' ' } else { c };
+    }
+    line
+}
+
+/// Byte offset of the `:` that ends a mapping key in `content`, if any.
+fn key_colon(content: &str) -> Option<usize> {
+    if content.starts_with(['[', '{']) {
+        return None;
+    }
+    let mut quote = None;
+    let mut previous = ' ';
+    for (at, c) in content.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if at == 0 => quote = Some(c),
+            (Some(q), _) if c == q && !(q == '"' && previous == '\\') => quote = None,
+            (None, ':') if content[at + 1..].is_empty() || content[at + 1..].starts_with(' ') => return Some(at),
+            _ => {}
+        }
+        previous = if previous == '\\' && c == '\\' { ' ' } else { c };
+    }
+    None
+}
+
+/// Split a flow collection's inside on top-level commas.
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quote, mut start, mut previous) = (0, None, 0, ' ');
+    for (at, c) in inner.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if opens_quote(previous) => quote = Some(c),
+            (Some(q), _) if c == q && !(q == '"' && previous == '\\') => quote = None,
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(inner[start..at].trim());
+                start = at + 1;
+            }
+            _ => {}
+        }
+        previous = if previous == '\\' && c == '\\' { ' ' } else { c };
+    }
+    parts.push(inner[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+struct Parser {
+    lines: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> YamlError {
+        YamlError { line: self.pos + 1, message: message.to_string() }
+    }
+
+    /// Move past blank and comment-only lines.
+    fn skip_blank(&mut self) {
+        while self.lines.get(self.pos).is_some_and(|line| strip_comment(line).trim().is_empty()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Indentation and comment-free content of the current line.
+    fn current(&self) -> Result<(usize, String), YamlError> {
+        let line = &self.lines[self.pos];
+        let content = line.trim_start_matches(' ');
+        if content.starts_with('\t') {
+            return Err(self.error("tabs can't be used for indentation"));
+        }
+        Ok((line.len() - content.len(), strip_comment(content).trim_end().to_string()))
+    }
+
+    /// Whatever starts at the next non-blank line, if it is indented at least `min_indent`.
+    fn parse_node(&mut self, min_indent: usize) -> Result<Value, YamlError> {
+        self.skip_blank();
+        if self.pos >= self.lines.len() {
+            return Ok(Value::Null);
+        }
+        let (indent, content) = self.current()?;
+        if indent < min_indent {
+            return Ok(Value::Null);
+        }
+        if content == "-" || content.starts_with("- ") {
+            self.parse_sequence(indent)
+        } else if key_colon(&content).is_some() {
+            self.parse_mapping(indent)
+        } else {
+            self.pos += 1;
+            self.parse_inline(&content)
+        }
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut map = Map::new();
+        loop {
+            self.skip_blank();
+            if self.pos >= self.lines.len() {
+                break;
+            }
+            let (line_indent, content) = self.current()?;
+            if line_indent < indent {
+                break;
+            }
+            if line_indent > indent {
+                return Err(self.error("unexpected indentation"));
+            }
+            let Some(colon) = key_colon(&content) else {
+                return Err(self.error("expected 'key: value'"));
+            };
+            let key = match self.parse_inline(content[..colon].trim())? {
+                Value::String(key) => key,
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            if map.contains_key(&key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            let rest = content[colon + 1..].trim().to_string();
+            self.pos += 1;
+            let value = self.parse_value(&rest, indent, true)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank();
+            if self.pos >= self.lines.len() {
+                break;
+            }
+            let (line_indent, content) = self.current()?;
+            if line_indent < indent || !(content == "-" || content.starts_with("- ")) {
+                break;
+            }
+            if line_indent > indent {
+                return Err(self.error("unexpected indentation"));
+            }
+            let rest = content[1..].trim_start();
+            if rest.starts_with("- ") || rest == "-" || key_colon(rest).is_some() {
+                // "- key: value" or "- - item": the item is a block starting mid-line
+                let column = indent + content.len() - rest.len();
+                self.lines[self.pos] = format!("{}{}", " ".repeat(column), rest);
+                items.push(self.parse_node(column)?);
+            } else {
+                let rest = rest.to_string();
+                self.pos += 1;
+                items.push(self.parse_value(&rest, indent, false)?);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    /// The value after a `key:` or `-` at column `indent`; `rest` is what
+    /// follows on the same line.
+    fn parse_value(&mut self, rest: &str, indent: usize, sequence_may_align: bool) -> Result<Value, YamlError> {
+        if rest.starts_with(['|', '>']) {
+            return self.parse_block_scalar(rest, indent);
+        }
+        if !rest.is_empty() {
+            return self.parse_inline(rest);
+        }
+        self.skip_blank();
+        if self.pos >= self.lines.len() {
+            return Ok(Value::Null);
+        }
+        let (next_indent, content) = self.current()?;
+        if next_indent > indent {
+            self.parse_node(indent + 1)
+        } else if sequence_may_align && next_indent == indent && (content == "-" || content.starts_with("- ")) {
+            self.parse_sequence(indent)
+        } else {
+            Ok(Value::Null)
+        }
+    }
+
+    /// A `|` (literal) or `>` (folded) block whose header is `header`.
+    fn parse_block_scalar(&mut self, header: &str, indent: usize) -> Result<Value, YamlError> {
+        let folded = header.starts_with('>');
+        let mut chomp = ' ';
+        let mut explicit = None;
+        for c in header[1..].chars() {
+            match c {
+                '-' | '+' => chomp = c,
+                '1'..='9' => explicit = c.to_digit(10).map(|d| indent + d as usize),
+                _ => return Err(self.error("bad block scalar header")),
+            }
+        }
+        let block_indent = explicit.or_else(|| {
+            self.lines[self.pos..].iter()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start_matches(' ').len())
+                .filter(|&found| found > indent)
+        });
+        let mut lines: Vec<&str> = Vec::new();
+        if let Some(block_indent) = block_indent {
+            while let Some(line) = self.lines.get(self.pos) {
+                let line_indent = line.len() - line.trim_start_matches(' ').len();
+                if line.trim().is_empty() {
+                    lines.push("");
+                } else if line_indent >= block_indent {
+                    lines.push(&line[block_indent..]);
+                } else {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+        let trailing = lines.iter().rev().take_while(|line| line.is_empty()).count();
+        let body = &lines[..lines.len() - trailing];
+        let mut text = if folded {
+            let mut text = String::new();
+            for (i, line) in body.iter().enumerate() {
+                if i > 0 {
+                    text.push(if line.is_empty() || body[i - 1].is_empty() { '\n' } else { ' ' });
+                }
+                text.push_str(line);
+            }
+            text
+        } else {
+            body.join("\n")
+        };
+        match chomp {
+            '-' => {}
+            '+' => text.push_str(&"\n".repeat(trailing + usize::from(!body.is_empty()))),
+            _ if !body.is_empty() => text.push('\n'),
+            _ => {}
+        }
+        Ok(Value::String(text))
+    }
+
+    /// A scalar or flow collection written on one line.
+    fn parse_inline(&self, s: &str) -> Result<Value, YamlError> {
+        let s = s.trim();
+        if s.starts_with('"') {
+            return serde_json::from_str::<String>(s)
+                .map(Value::String)
+                .map_err(|_| self.error(&format!("bad double-quoted string: {}", s)));
+        }
+        if let Some(inner) = s.strip_prefix('\'') {
+            let Some(inner) = inner.strip_suffix('\'') else {
+                return Err(self.error(&format!("unterminated single-quoted string: {}", s)));
+            };
+            return Ok(Value::String(inner.replace("''", "'")));
+        }
+        if let Some(inner) = s.strip_prefix('[') {
+            let Some(inner) = inner.strip_suffix(']') else {
+                return Err(self.error("flow sequences must close on the same line"));
+            };
+            return split_flow(inner).into_iter().map(|part| self.parse_inline(part)).collect();
+        }
+        if let Some(inner) = s.strip_prefix('{') {
+            let Some(inner) = inner.strip_suffix('}') else {
+                return Err(self.error("flow mappings must close on the same line"));
+            };
+            let mut map = Map::new();
+            for part in split_flow(inner) {
+                let (key, value) = match key_colon(part) {
+                    Some(colon) => (&part[..colon], &part[colon + 1..]),
+                    None => (part, ""),
+                };
+                let key = match self.parse_inline(key)? {
+                    Value::String(key) => key,
+                    other => other.to_string(),
+                };
+                map.insert(key, self.parse_inline(value)?);
+            }
+            return Ok(Value::Object(map));
+        }
+        Ok(plain_scalar(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hand_written_document_reads_as_expected() {
+        let text = "\
+# The cellar
+id: cellar
+title: 'Wine Cellar'
+short: It's cold   # and damp
+description: >
+  Racks of dusty bottles
+  line the walls.
+exits: {up: hall, north: \"vault\"}
+items:
+- id: bottle   # an empty one
+  weight: 1.5
+  tags: [glass, fragile]
+- id: cork
+  hidden: true
+notes: |
+  Line one
+    indented
+";
+        assert_eq!(from_str(text).unwrap(), json!({
+            "id": "cellar",
+            "title": "Wine Cellar",
+            "short": "It's cold",
+            "description": "Racks of dusty bottles line the walls.\n",
+            "exits": {"up": "hall", "north": "vault"},
+            "items": [
+                {"id": "bottle", "weight": 1.5, "tags": ["glass", "fragile"]},
+                {"id": "cork", "hidden": true}
+            ],
+            "notes": "Line one\n  indented\n",
+        }));
+        assert_eq!(from_str("title: x\n  oops: y").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn awkward_values_survive_a_round_trip() {
+        let value = json!({
+            "strings": ["", " padded", "yes", "42", "null", "a: b", "# hash", "two\nlines", "trailing\n", "\"quoted\"", "It's", "back\\slash"],
+            "numbers": [0, -3, 2.5],
+            "nested": [[1, 2], [], {}, {"deep": {"er": [true, null]}}],
+            "": "empty key",
+            "# a\\": 1,
+            "flow": [{"# b\\": "c"}],
+        });
+        assert_eq!(from_str(&to_string(&value)).unwrap(), value);
+        assert_eq!(from_str(r#""a\\": 1"#).unwrap(), json!({"a\\": 1}));
+    }
+}